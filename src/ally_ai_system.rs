@@ -0,0 +1,133 @@
+use crate::{
+    closed_door_at, Ally, AllyOrder, Door, Map, Monster, Position, RunState, StatusEffects,
+    WantsToMelee, WantsToOpenDoor,
+};
+
+use super::Viewshed;
+use bracket_lib::terminal::{DistanceAlg, Point};
+use specs::prelude::*;
+
+/// How far from the player a following ally will wander to pick a fight
+const FOLLOW_LEASH: f32 = 6.0;
+
+pub struct AllyAI {}
+
+impl<'a> System<'a> for AllyAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+        Entities<'a>,
+        WriteStorage<'a, Viewshed>,
+        WriteStorage<'a, Ally>,
+        ReadStorage<'a, Monster>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, WantsToMelee>,
+        ReadStorage<'a, Door>,
+        WriteStorage<'a, WantsToOpenDoor>,
+        ReadStorage<'a, StatusEffects>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut map,
+            player_pos,
+            runstate,
+            entities,
+            mut viewshed,
+            mut allies,
+            monsters,
+            mut position,
+            mut wants_to_melee,
+            doors,
+            mut wants_to_open,
+            statuses,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        let hostiles: Vec<(Entity, Point)> = (&entities, &monsters, &position)
+            .join()
+            .map(|(entity, _monster, pos)| (entity, Point::new(pos.x, pos.y)))
+            .collect();
+
+        for (entity, viewshed, ally, pos) in
+            (&entities, &mut viewshed, &mut allies, &mut position).join()
+        {
+            let ally_pos = Point::new(pos.x, pos.y);
+            if statuses
+                .get(entity)
+                .is_some_and(|active| active.helpless().is_some())
+            {
+                continue;
+            }
+
+            // Fall back to following once an attack target is gone
+            if let AllyOrder::Attack(target) = ally.order {
+                if !hostiles.iter().any(|(hostile, _)| *hostile == target) {
+                    ally.order = AllyOrder::Follow;
+                }
+            }
+
+            let target = match ally.order {
+                AllyOrder::Attack(target) => hostiles.iter().find(|(e, _)| *e == target).copied(),
+                AllyOrder::Follow => hostiles
+                    .iter()
+                    .filter(|(_, p)| viewshed.visible_tiles.contains(p))
+                    .filter(|(_, p)| {
+                        DistanceAlg::Pythagoras.distance2d(*p, *player_pos) < FOLLOW_LEASH
+                    })
+                    .min_by(|(_, a), (_, b)| {
+                        let distance_a = DistanceAlg::Pythagoras.distance2d(ally_pos, *a);
+                        let distance_b = DistanceAlg::Pythagoras.distance2d(ally_pos, *b);
+                        distance_a.partial_cmp(&distance_b).unwrap()
+                    })
+                    .copied(),
+                AllyOrder::Stay => hostiles
+                    .iter()
+                    .find(|(_, p)| DistanceAlg::Pythagoras.distance2d(ally_pos, *p) < 1.5)
+                    .copied(),
+            };
+
+            let destination = match target {
+                Some((target, target_pos)) => {
+                    if DistanceAlg::Pythagoras.distance2d(ally_pos, target_pos) < 1.5 {
+                        wants_to_melee
+                            .insert(entity, WantsToMelee { target })
+                            .expect("Unable to insert attack");
+                        continue;
+                    }
+                    target_pos
+                }
+                None => {
+                    if ally.order != AllyOrder::Follow
+                        || DistanceAlg::Pythagoras.distance2d(ally_pos, *player_pos) < 2.5
+                    {
+                        continue;
+                    }
+                    *player_pos
+                }
+            };
+
+            let old_idx = map.xy_idx(pos.x, pos.y);
+            let destination_idx = map.xy_idx(destination.x, destination.y);
+            if let Some(step) = map.next_step_towards(old_idx, destination_idx) {
+                if let Some(door) = closed_door_at(&map, step, &doors) {
+                    wants_to_open
+                        .insert(entity, WantsToOpenDoor { door })
+                        .expect("Unable to insert door opening");
+                    continue;
+                }
+                // Keep the 'blocked' map current so the monsters don't stack onto us
+                map.blocked[old_idx] = false;
+                pos.x = step as i32 % map.width;
+                pos.y = step as i32 / map.width;
+                viewshed.dirty = true;
+                map.blocked[step] = true;
+            }
+        }
+    }
+}
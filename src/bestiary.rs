@@ -0,0 +1,144 @@
+use std::sync::OnceLock;
+
+use bracket_lib::terminal::{to_cp437, FontCharType};
+
+use crate::{DamageType, Resistance, Spell};
+
+const MONSTER_DATA: &str = include_str!("../resources/monsters.txt");
+
+/// Everything resources/monsters.txt says about one kind of monster
+#[derive(Debug, Clone)]
+pub struct MonsterDef {
+    pub name: String,
+    pub glyph: FontCharType,
+    pub hp: i32,
+    pub defense: i32,
+    pub power: i32,
+    pub perception: i32,
+    pub souls: i32,
+    pub mana: i32,
+    pub stamina: i32,
+    /// The damage type of its melee blows, when it isn't just bludgeoning
+    pub attack: Option<DamageType>,
+    pub resistances: Vec<(DamageType, Resistance)>,
+    pub spells: Vec<Spell>,
+    /// How far a monster that shoots tries to keep from its target
+    pub keep_distance: Option<f32>,
+    /// Bosses stay dead, where everything else comes back when the player rests
+    pub boss: bool,
+}
+
+impl MonsterDef {
+    fn new(name: &str) -> MonsterDef {
+        MonsterDef {
+            name: name.to_string(),
+            glyph: to_cp437('?'),
+            hp: 1,
+            defense: 0,
+            power: 1,
+            perception: 0,
+            souls: 0,
+            mana: 0,
+            stamina: 0,
+            attack: None,
+            resistances: Vec::new(),
+            spells: Vec::new(),
+            keep_distance: None,
+            boss: false,
+        }
+    }
+}
+
+/// The definition of the monster called `name`, if the data file has one
+pub fn monster_def(name: &str) -> Option<&'static MonsterDef> {
+    static BESTIARY: OnceLock<Vec<MonsterDef>> = OnceLock::new();
+    BESTIARY
+        .get_or_init(|| parse_bestiary(MONSTER_DATA))
+        .iter()
+        .find(|def| def.name == name)
+}
+
+/// Reads monster definitions in the format described at the top of resources/monsters.txt.
+/// The file ships inside the binary, so a mistake in it is a bug and panics with its line number.
+fn parse_bestiary(text: &str) -> Vec<MonsterDef> {
+    let mut defs: Vec<MonsterDef> = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fail = |why: &str| -> ! { panic!("monsters.txt line {}: {}", line_no + 1, why) };
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            defs.push(MonsterDef::new(name.trim()));
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            fail("expected `key = value`")
+        };
+        let Some(def) = defs.last_mut() else {
+            fail("a value before the first [Monster]")
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let number = || {
+            value
+                .parse::<i32>()
+                .unwrap_or_else(|_| fail("expected a number"))
+        };
+        let damage_types = || -> Vec<DamageType> {
+            list(value)
+                .map(|name| {
+                    DamageType::from_name(name).unwrap_or_else(|| fail("unknown damage type"))
+                })
+                .collect()
+        };
+
+        match key {
+            "glyph" => match value.chars().next() {
+                Some(glyph) => def.glyph = to_cp437(glyph),
+                None => fail("an empty glyph"),
+            },
+            "hp" => def.hp = number(),
+            "defense" => def.defense = number(),
+            "power" => def.power = number(),
+            "perception" => def.perception = number(),
+            "souls" => def.souls = number(),
+            "mana" => def.mana = number(),
+            "stamina" => def.stamina = number(),
+            "attack" => match DamageType::from_name(value) {
+                Some(kind) => def.attack = Some(kind),
+                None => fail("unknown damage type"),
+            },
+            "immune" => add_resistances(def, damage_types(), Resistance::Immune),
+            "resistant" => add_resistances(def, damage_types(), Resistance::Resistant),
+            "weak" => add_resistances(def, damage_types(), Resistance::Weak),
+            "spells" => {
+                def.spells = list(value)
+                    .map(|name| Spell::from_name(name).unwrap_or_else(|| fail("unknown spell")))
+                    .collect()
+            }
+            "keep_distance" => def.keep_distance = Some(number() as f32),
+            "boss" => match value.parse::<bool>() {
+                Ok(boss) => def.boss = boss,
+                Err(_) => fail("expected true or false"),
+            },
+            _ => fail("unknown key"),
+        }
+    }
+    defs
+}
+
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn add_resistances(def: &mut MonsterDef, kinds: Vec<DamageType>, resistance: Resistance) {
+    def.resistances
+        .extend(kinds.into_iter().map(|kind| (kind, resistance)));
+}
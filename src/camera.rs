@@ -0,0 +1,122 @@
+use bracket_lib::terminal::{BTerm, Point, RGB};
+use specs::prelude::*;
+
+use crate::{Map, Position, Renderable};
+
+/// How much of the console the map gets; the rows below it belong to the UI
+pub const VIEW_WIDTH: i32 = 80;
+pub const VIEW_HEIGHT: i32 = 43;
+
+/// The window of the map that fits on screen. It keeps its subject in the middle, but stops at
+/// the map's edges rather than showing empty space past them; a map smaller than the view is
+/// centered instead.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    /// The world position drawn in the top left screen cell
+    pub min_x: i32,
+    pub min_y: i32,
+}
+
+impl Camera {
+    pub fn new(map: &Map, center: Point) -> Camera {
+        Camera {
+            min_x: Camera::axis_start(center.x, map.width, VIEW_WIDTH),
+            min_y: Camera::axis_start(center.y, map.height, VIEW_HEIGHT),
+        }
+    }
+
+    /// Follows the player around the current map
+    pub fn follow_player(ecs: &World) -> Camera {
+        let map = ecs.fetch::<Map>();
+        let player_pos = ecs.fetch::<Point>();
+        Camera::new(&map, *player_pos)
+    }
+
+    fn axis_start(center: i32, map_size: i32, view_size: i32) -> i32 {
+        if map_size <= view_size {
+            (map_size - view_size) / 2
+        } else {
+            (center - view_size / 2).clamp(0, map_size - view_size)
+        }
+    }
+
+    /// Where a world position lands on screen, if it is in view at all
+    pub fn world_to_screen(&self, world: Point) -> Option<Point> {
+        let screen = Point::new(world.x - self.min_x, world.y - self.min_y);
+        if screen.x < 0 || screen.x >= VIEW_WIDTH || screen.y < 0 || screen.y >= VIEW_HEIGHT {
+            None
+        } else {
+            Some(screen)
+        }
+    }
+
+    /// The world position under a screen cell, if the cell is part of the map view and the
+    /// position is on the map
+    pub fn screen_to_world(&self, map: &Map, screen: Point) -> Option<Point> {
+        if screen.x < 0 || screen.x >= VIEW_WIDTH || screen.y < 0 || screen.y >= VIEW_HEIGHT {
+            return None;
+        }
+        let world = Point::new(screen.x + self.min_x, screen.y + self.min_y);
+        if world.x < 0 || world.x >= map.width || world.y < 0 || world.y >= map.height {
+            None
+        } else {
+            Some(world)
+        }
+    }
+
+    /// The map position under the mouse, if it is over the map
+    pub fn mouse_world_pos(&self, map: &Map, ctx: &BTerm) -> Option<Point> {
+        let (x, y) = ctx.mouse_pos();
+        self.screen_to_world(map, Point::new(x, y))
+    }
+}
+
+/// Draws the part of the level around the player, along with whatever they can see on it
+pub fn render_camera(ecs: &World, ctx: &mut BTerm) {
+    let camera = Camera::follow_player(ecs);
+    let map = ecs.fetch::<Map>();
+    render_map(&map, &camera, ctx);
+
+    let positions = ecs.read_storage::<Position>();
+    let renderables = ecs.read_storage::<Renderable>();
+    for (pos, render) in (&positions, &renderables).join() {
+        let idx = map.xy_idx(pos.x, pos.y);
+        if !map.visible_tiles[idx] {
+            continue;
+        }
+        if let Some(screen) = camera.world_to_screen(Point::new(pos.x, pos.y)) {
+            ctx.set(screen.x, screen.y, render.fg, render.bg, render.glyph);
+        }
+    }
+}
+
+/// Draws the tiles in the camera's view that the player has seen, tinted by the light falling on
+/// them, and greyed out if they aren't currently in view
+pub fn render_map(map: &Map, camera: &Camera, ctx: &mut BTerm) {
+    for screen_y in 0..VIEW_HEIGHT {
+        for screen_x in 0..VIEW_WIDTH {
+            let world = match camera.screen_to_world(map, Point::new(screen_x, screen_y)) {
+                Some(world) => world,
+                None => continue,
+            };
+            let idx = map.xy_idx(world.x, world.y);
+            if !map.revealed_tiles[idx] {
+                continue;
+            }
+
+            let info = map.tiles[idx].info();
+            let (fg, bg) = if map.visible_tiles[idx] {
+                let light = map.light[idx];
+                (tint(info.fg, light), tint(info.bg, light))
+            } else {
+                (info.fg.to_greyscale(), info.bg.to_greyscale())
+            };
+            ctx.set(screen_x, screen_y, fg, bg, info.glyph);
+        }
+    }
+}
+
+/// Shades a color by the light falling on it, channel by channel
+fn tint(color: RGB, light: RGB) -> RGB {
+    RGB::from_f32(color.r * light.r, color.g * light.g, color.b * light.b)
+}
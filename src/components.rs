@@ -2,6 +2,8 @@ use bracket_lib::terminal::{FontCharType, Point, RGB};
 use specs::prelude::*;
 use specs_derive::*;
 
+use crate::{Rarity, Spell};
+
 #[derive(Clone, Component)]
 pub struct Position {
     pub x: i32,
@@ -36,6 +38,308 @@ pub struct Name {
 #[derive(Component, Debug)]
 pub struct BlocksTile {}
 
+/// Something that can be picked up and carried. Its weight, in pounds, counts against its
+/// carrier's capacity; a stack's weight is per missile.
+#[derive(Component, Debug)]
+pub struct Item {
+    pub weight: f32,
+    /// What it is worth, in gold
+    pub value: i32,
+}
+
+/// Used up when it is used
+#[derive(Component, Debug)]
+pub struct Consumable {}
+
+#[derive(Component, Debug)]
+pub struct ProvidesHealing {
+    pub heal_amount: i32,
+}
+
+#[derive(Component, Debug)]
+pub struct ProvidesMana {
+    pub mana_amount: i32,
+}
+
+/// Whisks its user off to somewhere up to `range` tiles away
+#[derive(Component, Debug)]
+pub struct TeleportsUser {
+    pub range: i32,
+}
+
+/// Reveals the real names of everything its user carries, and any curses on it
+#[derive(Component, Debug)]
+pub struct IdentifiesItems {}
+
+/// Lifts the curses from everything its user carries
+#[derive(Component, Debug)]
+pub struct RemovesCurses {}
+
+/// Mends everything its user carries back to full durability
+#[derive(Component, Debug)]
+pub struct RepairsItems {}
+
+/// Equipment that can't be taken off once it is on, and saps its wearer's defense. Nobody knows
+/// it's cursed until it is equipped or identified.
+#[derive(Component, Debug)]
+pub struct Cursed {
+    pub known: bool,
+}
+
+/// Casts `spell` for free until its charges run out
+#[derive(Component, Debug)]
+pub struct Wand {
+    pub spell: Spell,
+    pub charges: i32,
+    pub max_charges: i32,
+}
+
+/// Wear on a weapon or shield; at zero it is broken and does nothing until repaired
+#[derive(Component, Debug)]
+pub struct Durability {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl Durability {
+    pub fn new(max: i32) -> Durability {
+        Durability { current: max, max }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.current <= 0
+    }
+
+    /// Wears the item down a point, returning true if that is what broke it
+    pub fn wear(&mut self) -> bool {
+        if self.is_broken() {
+            return false;
+        }
+        self.current -= 1;
+        self.is_broken()
+    }
+}
+
+/// A spellbook; reading it teaches its spell
+#[derive(Component, Debug)]
+pub struct TeachesSpell {
+    pub spell: Spell,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToUseItem {
+    pub item: Entity,
+}
+
+/// Carried by `owner` instead of lying on the map
+#[derive(Component, Debug, Clone)]
+pub struct InBackpack {
+    pub owner: Entity,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToPickupItem {
+    pub item: Entity,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToDropItem {
+    pub item: Entity,
+}
+
+/// How strong a creature is
+#[derive(Component, Debug)]
+pub struct Attributes {
+    pub might: i32,
+}
+
+impl Attributes {
+    /// How many pounds can be carried before it starts to slow their bearer down
+    pub fn carry_capacity(&self) -> f32 {
+        15.0 + 5.0 * self.might as f32
+    }
+}
+
+/// What a creature is carrying against what it can carry
+#[derive(Component, Debug)]
+pub struct Encumbrance {
+    pub load: f32,
+    pub capacity: f32,
+    /// Set by a step taken while burdened, which the monsters get a second turn for
+    pub lagging: bool,
+}
+
+impl Encumbrance {
+    pub fn is_burdened(&self) -> bool {
+        self.load > self.capacity
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum EquipmentSlot {
+    Melee,
+    Ranged,
+    Shield,
+}
+
+/// An item that can be wielded or worn in the given slot
+#[derive(Component, Debug)]
+pub struct Equippable {
+    pub slot: EquipmentSlot,
+}
+
+/// Wielded or worn by `owner`, who also carries it in their pack; each owner has at most one item
+/// per slot
+#[derive(Component, Debug, Clone)]
+pub struct Equipped {
+    pub owner: Entity,
+    pub slot: EquipmentSlot,
+}
+
+/// A weapon for close fighting, which adds `power` to its wielder's blows and decides what kind of
+/// damage they do
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MeleeWeapon {
+    pub power: i32,
+    pub kind: DamageType,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum AmmoKind {
+    SlingStones,
+    Arrows,
+}
+
+impl AmmoKind {
+    /// The percent chance that a shot missile lands somewhere it can be picked up again
+    pub fn recovery_chance(self) -> i32 {
+        match self {
+            AmmoKind::SlingStones => 75,
+            AmmoKind::Arrows => 50,
+        }
+    }
+
+    pub fn plural(self) -> &'static str {
+        match self {
+            AmmoKind::SlingStones => "sling stones",
+            AmmoKind::Arrows => "arrows",
+        }
+    }
+
+    pub fn damage_type(self) -> DamageType {
+        match self {
+            AmmoKind::SlingStones => DamageType::Bludgeoning,
+            AmmoKind::Arrows => DamageType::Piercing,
+        }
+    }
+
+    /// Names `count` of this ammunition, such as "1 arrow" or "12 sling stones"
+    pub fn describe(self, count: i32) -> String {
+        if count == 1 {
+            format!("1 {}", self.plural().trim_end_matches('s'))
+        } else {
+            format!("{} {}", count, self.plural())
+        }
+    }
+}
+
+/// A missile weapon, such as a sling or bow, that shoots `ammo` out of its wielder's pack
+#[derive(Component, Debug, Clone, Copy)]
+pub struct RangedWeapon {
+    pub range: i32,
+    pub power: i32,
+    pub ammo: AmmoKind,
+}
+
+/// A stack of missiles for a ranged weapon; picking up more of the same kind adds to the stack
+#[derive(Component, Debug)]
+pub struct Ammunition {
+    pub kind: AmmoKind,
+    pub count: i32,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToShoot {
+    pub target: Entity,
+}
+
+/// Marks a monster that would rather fight from a distance. It backs away from anyone closer
+/// than `min_distance` while it still has something to shoot.
+#[derive(Component, Debug)]
+pub struct RangedAI {
+    pub min_distance: f32,
+}
+
+/// Sheds light of the given color over everything it can see within `range` tiles, fading
+/// towards the edge
+#[derive(Component, Debug)]
+pub struct LightSource {
+    pub color: RGB,
+    pub range: i32,
+}
+
+/// Stops sight passing through the bearer's tile, on top of whatever the terrain blocks
+#[derive(Component, Debug)]
+pub struct BlocksVisibility {}
+
+/// A door that can be opened by walking into it and closed again; while shut it blocks movement
+/// and sight
+#[derive(Component, Debug)]
+pub struct Door {
+    pub open: bool,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToOpenDoor {
+    pub door: Entity,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToCloseDoor {
+    pub door: Entity,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pool {
+    pub current: i32,
+    pub max: i32,
+}
+
+/// Resources besides hit points. Mana pays for spells, and trickles back one point at a time.
+/// Stamina pays for attacks, blocks and dodges, and only comes back on turns spent not using it.
+#[derive(Component, Debug)]
+pub struct Pools {
+    pub mana: Pool,
+    /// Turns until the next point of mana comes back
+    pub mana_regen_timer: i32,
+    pub stamina: Pool,
+    /// Set when stamina is spent, so none comes back this turn
+    pub exerted: bool,
+}
+
+impl Pools {
+    /// Pays `cost` out of stamina, returning true if that leaves none at all
+    pub fn spend_stamina(&mut self, cost: i32) -> bool {
+        self.stamina.current = i32::max(0, self.stamina.current - cost);
+        self.exerted = true;
+        self.stamina.current == 0
+    }
+}
+
+/// The spells a caster can choose from, in hotbar order
+#[derive(Component, Debug)]
+pub struct KnownSpells {
+    pub spells: Vec<Spell>,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct WantsToCastSpell {
+    pub spell: Spell,
+    pub target: Point,
+    /// The wand the spell is zapped from, which pays for it in place of mana
+    pub wand: Option<Entity>,
+}
+
 #[derive(Component, Debug)]
 pub struct CombatStats {
     pub max_hp: i32,
@@ -49,20 +353,393 @@ pub struct WantsToMelee {
     pub target: Entity,
 }
 
+/// The kinds of harm there are, which creatures can resist or be weak against
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum DamageType {
+    Slashing,
+    Piercing,
+    Bludgeoning,
+    Fire,
+    Cold,
+    Lightning,
+    Poison,
+    Spirit,
+}
+
+impl DamageType {
+    pub const ALL: [DamageType; 8] = [
+        DamageType::Slashing,
+        DamageType::Piercing,
+        DamageType::Bludgeoning,
+        DamageType::Fire,
+        DamageType::Cold,
+        DamageType::Lightning,
+        DamageType::Poison,
+        DamageType::Spirit,
+    ];
+
+    pub fn from_name(name: &str) -> Option<DamageType> {
+        DamageType::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DamageType::Slashing => "slashing",
+            DamageType::Piercing => "piercing",
+            DamageType::Bludgeoning => "bludgeoning",
+            DamageType::Fire => "fire",
+            DamageType::Cold => "cold",
+            DamageType::Lightning => "lightning",
+            DamageType::Poison => "poison",
+            DamageType::Spirit => "spirit",
+        }
+    }
+
+    /// True for weapon blows, which shields can block
+    pub fn is_physical(self) -> bool {
+        matches!(
+            self,
+            DamageType::Slashing | DamageType::Piercing | DamageType::Bludgeoning
+        )
+    }
+
+    /// What the log calls the thing doing the damage, as in "resists your iron"
+    pub fn noun(self) -> &'static str {
+        match self {
+            DamageType::Slashing => "iron",
+            DamageType::Piercing => "points",
+            DamageType::Bludgeoning => "blows",
+            DamageType::Fire => "fire",
+            DamageType::Cold => "frost",
+            DamageType::Lightning => "lightning",
+            DamageType::Poison => "poison",
+            DamageType::Spirit => "spirit",
+        }
+    }
+}
+
+/// One hit's worth of damage, before the victim's resistances have had a say
+#[derive(Debug, Clone, Copy)]
+pub struct DamageRecord {
+    pub amount: i32,
+    pub kind: DamageType,
+    /// Whoever dealt it, if anyone
+    pub source: Option<Entity>,
+}
+
 #[derive(Component, Debug)]
 pub struct SufferDamage {
-    pub amount: Vec<i32>,
+    pub records: Vec<DamageRecord>,
 }
 
 impl SufferDamage {
-    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32) {
+    pub fn new_damage(
+        store: &mut WriteStorage<SufferDamage>,
+        victim: Entity,
+        amount: i32,
+        kind: DamageType,
+        source: Option<Entity>,
+    ) {
+        let record = DamageRecord {
+            amount,
+            kind,
+            source,
+        };
         if let Some(suffering) = store.get_mut(victim) {
-            suffering.amount.push(amount);
+            suffering.records.push(record);
         } else {
             let dmg = SufferDamage {
-                amount: vec![amount],
+                records: vec![record],
             };
             store.insert(victim, dmg).expect("Unable to insert damage");
         }
     }
 }
+
+/// How well a creature stands up to one type of damage
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Resistance {
+    Immune,
+    Resistant,
+    Weak,
+}
+
+impl Resistance {
+    /// What's left of `amount` once this resistance has been applied
+    pub fn scale(self, amount: i32) -> i32 {
+        match self {
+            Resistance::Immune => 0,
+            Resistance::Resistant => amount / 2,
+            Resistance::Weak => amount * 2,
+        }
+    }
+}
+
+/// Damage types a creature takes more or less from than usual; anything not listed hits normally
+#[derive(Component, Debug, Clone)]
+pub struct Resistances {
+    pub against: Vec<(DamageType, Resistance)>,
+}
+
+impl Resistances {
+    pub fn get(&self, kind: DamageType) -> Option<Resistance> {
+        self.against
+            .iter()
+            .find(|(against, _)| *against == kind)
+            .map(|(_, resistance)| *resistance)
+    }
+}
+
+/// The kind of damage a creature does in melee; without one, it just bludgeons
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MeleeAttack {
+    pub kind: DamageType,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum AllyOrder {
+    Follow,
+    Stay,
+    Attack(Entity),
+}
+
+/// Marks a creature as fighting on the player's side, along with the order it is following
+#[derive(Component, Debug)]
+pub struct Ally {
+    pub order: AllyOrder,
+}
+
+/// Marks a creature that was summoned rather than recruited; only one can be bound at a time
+#[derive(Component, Debug)]
+pub struct Summoned {}
+
+/// A monster that stays dead: resting at a shrine brings everything else on the level back
+#[derive(Component, Debug)]
+pub struct Boss {}
+
+/// A shrine the player can rest at, to heal and to come back to after dying
+#[derive(Component, Debug)]
+pub struct Shrine {}
+
+/// Souls held: the player gathers them from whatever dies, and monsters give theirs up
+#[derive(Component, Debug)]
+pub struct Souls {
+    pub amount: i32,
+}
+
+/// The souls the player was carrying when they died, waiting where they fell to be taken back
+#[derive(Component, Debug)]
+pub struct SoulCache {
+    pub souls: i32,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum AwarenessState {
+    Asleep,
+    Unaware,
+    Aware,
+}
+
+/// How alert a monster is to its enemies. Unaware monsters will wander over to investigate the
+/// last noise they heard.
+#[derive(Component, Debug)]
+pub struct Awareness {
+    pub state: AwarenessState,
+    pub perception: i32,
+    pub investigating: Option<Point>,
+}
+
+/// Makes the bearer harder to notice when a monster's perception is checked against it
+#[derive(Component, Debug)]
+pub struct Stealth {
+    pub skill: i32,
+}
+
+/// A noise made this turn, heard by anything the volume can reach through the map
+#[derive(Component, Debug)]
+pub struct EmitsNoise {
+    pub volume: i32,
+}
+
+impl EmitsNoise {
+    pub fn new_noise(store: &mut WriteStorage<EmitsNoise>, source: Entity, volume: i32) {
+        if let Some(noise) = store.get_mut(source) {
+            noise.volume = i32::max(noise.volume, volume);
+        } else {
+            store
+                .insert(source, EmitsNoise { volume })
+                .expect("Unable to insert noise");
+        }
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Status {
+    /// Takes a little damage every turn
+    Poisoned,
+    /// Loses its turns
+    Stunned,
+    /// Out of stamina: loses its turns and can't block
+    Staggered,
+    /// Mid dodge-roll, and can't be hurt
+    Evading,
+}
+
+impl Status {
+    pub const ALL: [Status; 4] = [
+        Status::Poisoned,
+        Status::Stunned,
+        Status::Staggered,
+        Status::Evading,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Status> {
+        Status::ALL.into_iter().find(|status| status.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::Poisoned => "poisoned",
+            Status::Stunned => "stunned",
+            Status::Staggered => "staggered",
+            Status::Evading => "evading",
+        }
+    }
+
+    /// True if the status costs its bearer their turns
+    pub fn prevents_acting(self) -> bool {
+        matches!(self, Status::Stunned | Status::Staggered)
+    }
+}
+
+/// Lingering conditions on a creature, each with how many more turns it lasts
+#[derive(Component, Debug)]
+pub struct StatusEffects {
+    pub statuses: Vec<(Status, i32)>,
+}
+
+impl StatusEffects {
+    /// Starts a status, or extends it if it is already running
+    pub fn add(&mut self, status: Status, turns: i32) {
+        match self
+            .statuses
+            .iter_mut()
+            .find(|(active, _)| *active == status)
+        {
+            Some((_, remaining)) => *remaining = i32::max(*remaining, turns),
+            None => self.statuses.push((status, turns)),
+        }
+    }
+
+    pub fn has(&self, status: Status) -> bool {
+        self.statuses.iter().any(|(active, _)| *active == status)
+    }
+
+    /// The first status keeping its bearer from acting, if any
+    pub fn helpless(&self) -> Option<Status> {
+        self.statuses
+            .iter()
+            .map(|(status, _)| *status)
+            .find(|status| status.prevents_acting())
+    }
+
+    /// Starts `status` on `victim`, giving it a `StatusEffects` if it has none yet
+    pub fn new_status(
+        store: &mut WriteStorage<StatusEffects>,
+        victim: Entity,
+        status: Status,
+        turns: i32,
+    ) {
+        if let Some(active) = store.get_mut(victim) {
+            active.add(status, turns);
+        } else {
+            let mut active = StatusEffects {
+                statuses: Vec::new(),
+            };
+            active.add(status, turns);
+            store
+                .insert(victim, active)
+                .expect("Unable to insert status");
+        }
+    }
+}
+
+/// A shield, which takes `block` off each physical blow its bearer takes for a little stamina
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Shield {
+    pub block: i32,
+}
+
+/// How rare a generated item is; anything without one is common
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ItemRarity {
+    pub rarity: Rarity,
+}
+
+/// A percent chance for each hit a weapon lands to inflict a status
+#[derive(Debug, Clone, Copy)]
+pub struct StatusProc {
+    pub status: Status,
+    pub chance: i32,
+    pub turns: i32,
+}
+
+/// What an item's affixes do for whoever has it equipped
+#[derive(Component, Debug, Clone, Default)]
+pub struct EquipmentBonus {
+    /// Added to the damage of blows or shots made with the item
+    pub power: i32,
+    /// Added to the wearer's defense
+    pub defense: i32,
+    /// The kind of damage a weapon deals instead of its usual one
+    pub damage_type: Option<DamageType>,
+    /// Damage types the wearer resists
+    pub resists: Vec<DamageType>,
+    pub procs: Vec<StatusProc>,
+}
+
+impl EquipmentBonus {
+    /// The bonus on whatever `owner` has equipped in `slot`, if it has one
+    pub fn in_slot<'a>(
+        owner: Entity,
+        slot: EquipmentSlot,
+        equipped: &ReadStorage<Equipped>,
+        bonuses: &'a ReadStorage<EquipmentBonus>,
+    ) -> Option<&'a EquipmentBonus> {
+        (equipped, bonuses)
+            .join()
+            .find(|(e, _)| e.owner == owner && e.slot == slot)
+            .map(|(_, bonus)| bonus)
+    }
+
+    /// The defense `owner` gains from everything they have equipped
+    pub fn defense_of(
+        owner: Entity,
+        equipped: &ReadStorage<Equipped>,
+        bonuses: &ReadStorage<EquipmentBonus>,
+    ) -> i32 {
+        (equipped, bonuses)
+            .join()
+            .filter(|(e, _)| e.owner == owner)
+            .map(|(_, bonus)| bonus.defense)
+            .sum()
+    }
+
+    /// True if something `owner` has equipped resists `kind`
+    pub fn resists_for(
+        owner: Entity,
+        kind: DamageType,
+        equipped: &ReadStorage<Equipped>,
+        bonuses: &ReadStorage<EquipmentBonus>,
+    ) -> bool {
+        (equipped, bonuses)
+            .join()
+            .any(|(e, bonus)| e.owner == owner && bonus.resists.contains(&kind))
+    }
+}
+
+/// A purely visual entity that disappears after a moment
+#[derive(Component, Debug)]
+pub struct ParticleLifetime {
+    pub lifetime_ms: f32,
+}
@@ -1,21 +1,154 @@
-use bracket_lib::prelude::console;
-use specs::{Entity, Join, System, World, WorldExt, WriteStorage};
+use bracket_lib::{prelude::console, random::RandomNumberGenerator};
+use specs::{
+    Entities, Entity, Join, ReadExpect, ReadStorage, System, World, WorldExt, WriteExpect,
+    WriteStorage,
+};
 
-use crate::{CombatStats, GameLog, Name, Player, SufferDamage};
+use crate::{
+    item_name, loot, spawner, CombatStats, DamageRecord, Durability, EquipmentBonus, Equipped,
+    GameLog, IdentificationRegistry, InBackpack, Map, Monster, Name, Player, Pools, Position,
+    Resistance, Resistances, Shield, Souls, StatusEffects, SufferDamage,
+};
 
+/// What blocking a blow with a shield costs in stamina
+const BLOCK_STAMINA_COST: i32 = 2;
+
+/// Applies the damage dealt this turn, less whatever shields block and scaled by each victim's
+/// resistances, and logs the hits. Every block wears the shield down.
 pub struct DamageSystem {}
 
 impl<'a> System<'a> for DamageSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadExpect<'a, IdentificationRegistry>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Resistances>,
         WriteStorage<'a, CombatStats>,
         WriteStorage<'a, SufferDamage>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, Shield>,
+        WriteStorage<'a, Durability>,
+        ReadStorage<'a, EquipmentBonus>,
+        ReadStorage<'a, StatusEffects>,
+        WriteStorage<'a, Pools>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut stats, mut damage) = data;
+        let (
+            entities,
+            player_entity,
+            mut log,
+            registry,
+            names,
+            resistances,
+            mut stats,
+            mut damage,
+            equipped,
+            shields,
+            mut durability,
+            bonuses,
+            statuses,
+            mut pools,
+        ) = data;
+
+        let name_of = |entity: Entity| names.get(entity).map_or("Something", |n| n.name.as_str());
+        // "your iron", "Goblin Archer's points", or "the poison" when nobody is to blame
+        let describe = |record: &DamageRecord| match record.source {
+            Some(source) if source == *player_entity => format!("your {}", record.kind.noun()),
+            Some(source) => format!("{}'s {}", name_of(source), record.kind.noun()),
+            None => format!("the {}", record.kind.noun()),
+        };
+
+        for (victim, stats, damage) in (&entities, &mut stats, &damage).join() {
+            let shield = (&entities, &equipped, &shields)
+                .join()
+                .find(|(_, e, _)| e.owner == victim)
+                .map(|(item, _, shield)| (item, *shield));
+            let staggered = statuses
+                .get(victim)
+                .is_some_and(|active| active.helpless().is_some());
+
+            for record in damage.records.iter() {
+                let mut amount = record.amount;
 
-        for (stats, damage) in (&mut stats, &damage).join() {
-            stats.hp -= damage.amount.iter().sum::<i32>();
+                // A ready shield turns aside some of a blow, as long as there's the stamina to
+                // raise it
+                if let (Some((item, shield)), Some(victim_pools)) = (shield, pools.get_mut(victim))
+                {
+                    if record.kind.is_physical()
+                        && !staggered
+                        && victim_pools.stamina.current >= BLOCK_STAMINA_COST
+                        && durability.get(item).is_none_or(|d| !d.is_broken())
+                    {
+                        victim_pools.spend_stamina(BLOCK_STAMINA_COST);
+                        amount = i32::max(0, amount - shield.block);
+                        let shield_name = item_name(item, &names, &registry);
+                        log.entries.push(format!(
+                            "{} blocks with the {}.",
+                            name_of(victim),
+                            shield_name
+                        ));
+                        if durability.get_mut(item).is_some_and(|d| d.wear()) {
+                            log.entries.push(format!(
+                                "{}'s {} breaks!",
+                                name_of(victim),
+                                shield_name
+                            ));
+                        }
+                    }
+                }
+
+                // A creature's own nature comes first; failing that, its gear may resist
+                let resistance = resistances
+                    .get(victim)
+                    .and_then(|resists| resists.get(record.kind))
+                    .or_else(|| {
+                        EquipmentBonus::resists_for(victim, record.kind, &equipped, &bonuses)
+                            .then_some(Resistance::Resistant)
+                    });
+                let amount = resistance.map_or(amount, |r| r.scale(amount));
+
+                match resistance {
+                    Some(Resistance::Immune) => log.entries.push(format!(
+                        "{} is unharmed by {}.",
+                        name_of(victim),
+                        describe(record)
+                    )),
+                    Some(Resistance::Resistant) => log.entries.push(format!(
+                        "{} resists {}.",
+                        name_of(victim),
+                        describe(record)
+                    )),
+                    Some(Resistance::Weak) => log.entries.push(format!(
+                        "{} is vulnerable to {}!",
+                        name_of(victim),
+                        describe(record)
+                    )),
+                    None => {}
+                }
+                if amount <= 0 {
+                    continue;
+                }
+
+                match record.source.filter(|source| *source != victim) {
+                    Some(source) => log.entries.push(format!(
+                        "{} hits {}, for {} hp.",
+                        name_of(source),
+                        name_of(victim),
+                        amount
+                    )),
+                    None => log.entries.push(format!(
+                        "{} takes {} {} damage.",
+                        name_of(victim),
+                        amount,
+                        record.kind.name()
+                    )),
+                }
+                stats.hp -= amount;
+            }
         }
 
         damage.clear();
@@ -24,12 +157,18 @@ impl<'a> System<'a> for DamageSystem {
 
 pub fn delete_the_dead(ecs: &mut World) {
     let mut dead: Vec<Entity> = Vec::new();
+    // Where each dead monster fell, and what it was, to roll its loot
+    let mut fallen: Vec<(Position, String)> = Vec::new();
     {
         let combat_stats = ecs.read_storage::<CombatStats>();
         let players = ecs.read_storage::<Player>();
+        let monsters = ecs.read_storage::<Monster>();
+        let positions = ecs.read_storage::<Position>();
         let names = ecs.read_storage::<Name>();
         let entities = ecs.entities();
         let mut log = ecs.write_resource::<GameLog>();
+        let mut souls = ecs.write_storage::<Souls>();
+        let player_entity = *ecs.fetch::<Entity>();
 
         for (entity, stats) in (&entities, &combat_stats).join() {
             if stats.hp < 1 {
@@ -40,6 +179,16 @@ pub fn delete_the_dead(ecs: &mut World) {
                         if let Some(victim_name) = victim_name {
                             log.entries.push(format!("{} is dead", &victim_name.name));
                         }
+                        // Whatever dies gives up its souls to the player
+                        let released = souls.get(entity).map_or(0, |s| s.amount);
+                        if let Some(player_souls) = souls.get_mut(player_entity) {
+                            player_souls.amount += released;
+                        }
+                        if let (Some(_), Some(pos), Some(name)) =
+                            (monsters.get(entity), positions.get(entity), victim_name)
+                        {
+                            fallen.push((pos.clone(), name.name.clone()));
+                        }
                         dead.push(entity)
                     }
                     Some(_) => console::log("You are dead"),
@@ -49,6 +198,63 @@ pub fn delete_the_dead(ecs: &mut World) {
     }
 
     for victim in dead {
+        drop_carried_items(ecs, victim);
         ecs.delete_entity(victim).expect("Unable to delete");
     }
+    for (pos, monster) in fallen {
+        drop_loot(ecs, pos, &monster);
+    }
+}
+
+/// Rolls a dead monster's loot table, leaving whatever it drops where it fell
+fn drop_loot(ecs: &mut World, pos: Position, monster: &str) {
+    let mut rng = RandomNumberGenerator::new();
+    let Some(drop) = loot::roll_drop(&mut rng, monster) else {
+        return;
+    };
+    let (idx, depth) = {
+        let map = ecs.fetch::<Map>();
+        (map.xy_idx(pos.x, pos.y), map.depth)
+    };
+
+    let dropped = match loot::base_item(drop) {
+        Some(base) => {
+            let roll = loot::roll_loot(&mut rng, base, depth);
+            spawner::loot_item(ecs, pos.x, pos.y, &roll);
+            roll.rarity.markup(&roll.name)
+        }
+        None => {
+            spawner::spawn_entity(ecs, &(idx, drop.to_string()));
+            ecs.fetch::<IdentificationRegistry>()
+                .name_for(drop)
+                .to_string()
+        }
+    };
+    let mut log = ecs.write_resource::<GameLog>();
+    log.entries.push(format!("{} drops {}.", monster, dropped));
+}
+
+/// Leaves everything the victim was carrying lying where they fell
+fn drop_carried_items(ecs: &mut World, victim: Entity) {
+    let victim_pos = match ecs.read_storage::<Position>().get(victim) {
+        Some(pos) => pos.clone(),
+        None => return,
+    };
+    let entities = ecs.entities();
+    let mut backpack = ecs.write_storage::<InBackpack>();
+    let mut equipped = ecs.write_storage::<Equipped>();
+    let mut positions = ecs.write_storage::<Position>();
+
+    let carried: Vec<Entity> = (&entities, &backpack)
+        .join()
+        .filter(|(_, carried)| carried.owner == victim)
+        .map(|(item, _)| item)
+        .collect();
+    for item in carried {
+        backpack.remove(item);
+        equipped.remove(item);
+        positions
+            .insert(item, victim_pos.clone())
+            .expect("Unable to drop item");
+    }
 }
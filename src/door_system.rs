@@ -0,0 +1,87 @@
+use bracket_lib::terminal::FontCharType;
+use specs::prelude::*;
+
+use crate::{
+    BlocksTile, BlocksVisibility, Door, Map, Renderable, Viewshed, WantsToCloseDoor,
+    WantsToOpenDoor,
+};
+
+pub const CLOSED_DOOR_GLYPH: FontCharType = 0x2B;
+pub const OPEN_DOOR_GLYPH: FontCharType = 0x2F;
+
+/// Swings doors open or shut for anyone who asked this turn. A door changing state alters what
+/// everyone can see, so every viewshed is refreshed when one does.
+pub struct DoorSystem {}
+
+impl<'a> System<'a> for DoorSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'a, WantsToOpenDoor>,
+        WriteStorage<'a, WantsToCloseDoor>,
+        WriteStorage<'a, Door>,
+        WriteStorage<'a, BlocksTile>,
+        WriteStorage<'a, BlocksVisibility>,
+        WriteStorage<'a, Renderable>,
+        WriteStorage<'a, Viewshed>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut wants_open,
+            mut wants_close,
+            mut doors,
+            mut blockers,
+            mut view_blockers,
+            mut renderables,
+            mut viewsheds,
+        ) = data;
+
+        let mut changed = false;
+        for wants in wants_open.join() {
+            if let Some(door) = doors.get_mut(wants.door) {
+                if !door.open {
+                    door.open = true;
+                    blockers.remove(wants.door);
+                    view_blockers.remove(wants.door);
+                    if let Some(render) = renderables.get_mut(wants.door) {
+                        render.glyph = OPEN_DOOR_GLYPH;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        for wants in wants_close.join() {
+            if let Some(door) = doors.get_mut(wants.door) {
+                if door.open {
+                    door.open = false;
+                    blockers
+                        .insert(wants.door, BlocksTile {})
+                        .expect("Unable to block the door");
+                    view_blockers
+                        .insert(wants.door, BlocksVisibility {})
+                        .expect("Unable to block sight through the door");
+                    if let Some(render) = renderables.get_mut(wants.door) {
+                        render.glyph = CLOSED_DOOR_GLYPH;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        wants_open.clear();
+        wants_close.clear();
+
+        if changed {
+            for viewshed in (&mut viewsheds).join() {
+                viewshed.dirty = true;
+            }
+        }
+    }
+}
+
+/// The closed door standing at `idx`, if there is one
+pub fn closed_door_at(map: &Map, idx: usize, doors: &ReadStorage<Door>) -> Option<Entity> {
+    map.tile_content[idx]
+        .iter()
+        .find(|entity| doors.get(**entity).is_some_and(|door| !door.open))
+        .copied()
+}
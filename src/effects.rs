@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+
+use bracket_lib::{
+    pathfinding::field_of_view,
+    prelude::{line2d, LineAlg},
+    random::RandomNumberGenerator,
+    terminal::{DistanceAlg, FontCharType, Point, RGB},
+};
+use specs::prelude::*;
+
+use crate::{
+    spawner, Awareness, AwarenessState, CombatStats, DamageType, GameLog, Map, Name,
+    ParticleLifetime, Pools, Position, Renderable, Status, StatusEffects, SufferDamage, Viewshed,
+};
+
+/// Something that happens to a creature or a tile
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Effect {
+    Damage {
+        amount: i32,
+        kind: DamageType,
+    },
+    Heal {
+        amount: i32,
+    },
+    RestoreMana {
+        amount: i32,
+    },
+    ApplyStatus {
+        status: Status,
+        turns: i32,
+    },
+    /// Moves the target to a random open tile up to `range` tiles away
+    Teleport {
+        range: i32,
+    },
+    /// A short-lived glyph drawn over each tile, purely for show
+    Particle {
+        glyph: FontCharType,
+        fg: RGB,
+        lifetime_ms: f32,
+    },
+    /// Creates whatever the spawner calls `name` on each tile
+    Spawn {
+        name: &'static str,
+    },
+}
+
+/// Where an effect lands
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Targets {
+    Single {
+        target: Entity,
+    },
+    Tile {
+        tile: Point,
+    },
+    /// Every tile within `radius` of `center` that a wall doesn't shelter
+    TilesInRadius {
+        center: Point,
+        radius: i32,
+    },
+    /// A quarter circle spreading out `range` tiles from `origin` towards `toward`
+    Cone {
+        origin: Point,
+        toward: Point,
+        range: i32,
+    },
+    /// The tiles from `from` to `to`, not counting `from`, stopping at the first wall
+    Line {
+        from: Point,
+        to: Point,
+    },
+}
+
+pub struct EffectSpawner {
+    pub creator: Option<Entity>,
+    pub effect: Effect,
+    pub targets: Targets,
+}
+
+/// Effects waiting to be applied, oldest first. Melee, missiles, spells, items and statuses all
+/// go through here rather than changing their targets themselves.
+#[derive(Default)]
+pub struct EffectQueue {
+    queue: VecDeque<EffectSpawner>,
+}
+
+impl EffectQueue {
+    pub fn add_effect(&mut self, creator: Option<Entity>, effect: Effect, targets: Targets) {
+        self.queue.push_back(EffectSpawner {
+            creator,
+            effect,
+            targets,
+        });
+    }
+}
+
+impl Targets {
+    /// The tiles these targets cover on `map`. A single creature covers whatever tile it is
+    /// standing on, which takes looking up, so it gives none here.
+    pub fn tiles(self, map: &Map) -> Vec<Point> {
+        let on_map =
+            |tile: &Point| tile.x >= 0 && tile.x < map.width && tile.y >= 0 && tile.y < map.height;
+        match self {
+            Targets::Single { .. } => Vec::new(),
+            Targets::Tile { tile } => vec![tile],
+            Targets::TilesInRadius { center, radius } => field_of_view(center, radius, map)
+                .into_iter()
+                .filter(|tile| {
+                    on_map(tile)
+                        && DistanceAlg::Pythagoras.distance2d(center, *tile) <= radius as f32
+                })
+                .collect(),
+            Targets::Cone {
+                origin,
+                toward,
+                range,
+            } => {
+                let (aim_x, aim_y) = ((toward.x - origin.x) as f32, (toward.y - origin.y) as f32);
+                let aim_length = (aim_x * aim_x + aim_y * aim_y).sqrt();
+                field_of_view(origin, range, map)
+                    .into_iter()
+                    .filter(|tile| {
+                        let (dx, dy) = ((tile.x - origin.x) as f32, (tile.y - origin.y) as f32);
+                        let length = (dx * dx + dy * dy).sqrt();
+                        // Within 45 degrees either side of the aim
+                        on_map(tile)
+                            && *tile != origin
+                            && length <= range as f32
+                            && aim_length > 0.0
+                            && (dx * aim_x + dy * aim_y) / (length * aim_length)
+                                >= std::f32::consts::FRAC_1_SQRT_2
+                    })
+                    .collect()
+            }
+            Targets::Line { from, to } => line2d(LineAlg::Bresenham, from, to)
+                .into_iter()
+                .filter(|tile| *tile != from)
+                .take_while(|tile| on_map(tile) && !map.tiles[map.xy_idx(tile.x, tile.y)].opaque())
+                .collect(),
+        }
+    }
+}
+
+/// Applies queued effects in the order they were added, logging what they do
+pub struct EffectsSystem {}
+
+impl<'a> System<'a> for EffectsSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Point>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, Map>,
+        Write<'a, EffectQueue>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, CombatStats>,
+        WriteStorage<'a, Pools>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Awareness>,
+        WriteStorage<'a, StatusEffects>,
+        WriteStorage<'a, Viewshed>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            mut player_pos,
+            mut log,
+            mut map,
+            mut effects,
+            lazy,
+            names,
+            mut positions,
+            mut combat_stats,
+            mut pools,
+            mut inflict_damage,
+            mut awareness,
+            mut statuses,
+            mut viewsheds,
+        ) = data;
+
+        let mut rng = RandomNumberGenerator::new();
+        let name_of = |entity: Entity| names.get(entity).map_or("Something", |n| n.name.as_str());
+
+        while let Some(queued) = effects.queue.pop_front() {
+            let tiles = match queued.targets {
+                Targets::Single { target } => positions
+                    .get(target)
+                    .map(|pos| vec![Point::new(pos.x, pos.y)])
+                    .unwrap_or_default(),
+                targets => targets.tiles(&map),
+            };
+
+            // Particles and spawns land on tiles; everything else on the creatures standing there
+            match queued.effect {
+                Effect::Particle {
+                    glyph,
+                    fg,
+                    lifetime_ms,
+                } => {
+                    for tile in tiles {
+                        lazy.create_entity(&entities)
+                            .with(Position {
+                                x: tile.x,
+                                y: tile.y,
+                            })
+                            .with(Renderable {
+                                glyph,
+                                fg,
+                                bg: RGB::named(bracket_lib::terminal::BLACK),
+                            })
+                            .with(ParticleLifetime { lifetime_ms })
+                            .build();
+                    }
+                    continue;
+                }
+                Effect::Spawn { name } => {
+                    for tile in tiles {
+                        let idx = map.xy_idx(tile.x, tile.y);
+                        lazy.exec_mut(move |world| {
+                            spawner::spawn_entity(world, &(idx, name.to_string()))
+                        });
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let victims: Vec<Entity> = match queued.targets {
+                Targets::Single { target } => vec![target],
+                _ => (&entities, &positions, &combat_stats)
+                    .join()
+                    .filter(|(_, pos, _)| tiles.contains(&Point::new(pos.x, pos.y)))
+                    .map(|(entity, _, _)| entity)
+                    .collect(),
+            };
+
+            for victim in victims {
+                let harmful = matches!(
+                    queued.effect,
+                    Effect::Damage { .. } | Effect::ApplyStatus { .. }
+                );
+                if harmful
+                    && statuses
+                        .get(victim)
+                        .is_some_and(|active| active.has(Status::Evading))
+                {
+                    log.entries
+                        .push(format!("{} rolls out of harm's way.", name_of(victim)));
+                    continue;
+                }
+
+                match queued.effect {
+                    Effect::Damage { amount, kind } => {
+                        // The damage system logs the hit, once resistances have had their say
+                        SufferDamage::new_damage(
+                            &mut inflict_damage,
+                            victim,
+                            amount,
+                            kind,
+                            queued.creator,
+                        );
+                        if let Some(victim_awareness) = awareness.get_mut(victim) {
+                            victim_awareness.state = AwarenessState::Aware;
+                            victim_awareness.investigating = None;
+                        }
+                    }
+                    Effect::Heal { amount } => {
+                        if let Some(stats) = combat_stats.get_mut(victim) {
+                            stats.hp = i32::min(stats.max_hp, stats.hp + amount);
+                            log.entries.push(format!(
+                                "{} recovers {} hp.",
+                                name_of(victim),
+                                amount
+                            ));
+                        }
+                    }
+                    Effect::RestoreMana { amount } => {
+                        if let Some(pools) = pools.get_mut(victim) {
+                            pools.mana.current =
+                                i32::min(pools.mana.max, pools.mana.current + amount);
+                            log.entries.push(format!(
+                                "{} recovers {} mana.",
+                                name_of(victim),
+                                amount
+                            ));
+                        }
+                    }
+                    Effect::ApplyStatus { status, turns } => {
+                        StatusEffects::new_status(&mut statuses, victim, status, turns);
+                        log.entries
+                            .push(format!("{} is {}.", name_of(victim), status.name()));
+                    }
+                    Effect::Teleport { range } => {
+                        let Some(pos) = positions.get_mut(victim) else {
+                            continue;
+                        };
+                        let origin = Point::new(pos.x, pos.y);
+                        let destinations: Vec<Point> = map
+                            .open_tiles_near(pos.x, pos.y)
+                            .into_iter()
+                            .take_while(|tile| {
+                                DistanceAlg::Pythagoras.distance2d(origin, *tile) <= range as f32
+                            })
+                            .collect();
+                        if destinations.is_empty() {
+                            continue;
+                        }
+                        let pick = (rng.roll_dice(1, destinations.len() as i32) - 1) as usize;
+                        let destination = destinations[pick];
+
+                        let old_idx = map.xy_idx(pos.x, pos.y);
+                        let new_idx = map.xy_idx(destination.x, destination.y);
+                        map.blocked[old_idx] = false;
+                        map.blocked[new_idx] = true;
+                        pos.x = destination.x;
+                        pos.y = destination.y;
+                        if let Some(viewshed) = viewsheds.get_mut(victim) {
+                            viewshed.dirty = true;
+                        }
+                        if victim == *player_entity {
+                            *player_pos = destination;
+                        }
+                        log.entries
+                            .push(format!("{} vanishes and reappears.", name_of(victim)));
+                    }
+                    Effect::Particle { .. } | Effect::Spawn { .. } => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use specs::prelude::*;
+
+use crate::{Ammunition, Attributes, Encumbrance, GameLog, InBackpack, Item};
+
+/// Weighs what everyone with attributes is carrying against what their might lets them carry,
+/// warning the player whenever they become burdened or shed enough to move freely again
+pub struct EncumbranceSystem {}
+
+impl<'a> System<'a> for EncumbranceSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Ammunition>,
+        WriteStorage<'a, Encumbrance>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            mut log,
+            attributes,
+            items,
+            backpack,
+            ammunition,
+            mut encumbrance,
+        ) = data;
+
+        for (carrier, attributes) in (&entities, &attributes).join() {
+            let load: f32 = (&entities, &items, &backpack)
+                .join()
+                .filter(|(_, _, carried)| carried.owner == carrier)
+                .map(|(item, details, _)| {
+                    let count = ammunition.get(item).map_or(1, |ammo| ammo.count);
+                    details.weight * count as f32
+                })
+                .sum();
+            let capacity = attributes.carry_capacity();
+
+            let was_burdened = encumbrance
+                .get(carrier)
+                .is_some_and(|before| before.is_burdened());
+            let lagging = encumbrance
+                .get(carrier)
+                .is_some_and(|before| before.lagging);
+            let now = Encumbrance {
+                load,
+                capacity,
+                lagging,
+            };
+
+            if carrier == *player_entity && now.is_burdened() != was_burdened {
+                if now.is_burdened() {
+                    log.entries.push(
+                        "You are burdened by your load, and every step comes slowly.".to_string(),
+                    );
+                } else {
+                    log.entries.push("You are no longer burdened.".to_string());
+                }
+            }
+            encumbrance
+                .insert(carrier, now)
+                .expect("Unable to insert encumbrance");
+        }
+    }
+}
@@ -1,10 +1,18 @@
 use bracket_lib::{
-    color::{BLACK, GREY, MAGENTA, RED, RGB, WHITE, YELLOW},
-    prelude::{BTerm, Point},
+    color::{
+        BLACK, BLUE, CYAN, DARK_RED, GREEN, GREY, LIGHT_CYAN, MAGENTA, ORANGE, RED, RGB, WHITE,
+        YELLOW,
+    },
+    prelude::{letter_to_option, to_cp437, BTerm, DistanceAlg, Point, TextAlign, VirtualKeyCode},
 };
-use specs::{Join, World, WorldExt};
+use specs::{Entity, Join, LendJoin, World, WorldExt};
 
-use crate::{CombatStats, GameLog, Map, Name, Player, Position};
+use crate::{
+    item_name, Ally, Ammunition, Awareness, AwarenessState, Camera, CombatStats, Cursed,
+    Durability, Encumbrance, Equipped, GameLog, IdentificationRegistry, InBackpack, Item,
+    ItemRarity, KnownSpells, Map, Name, Player, Pools, Position, RangedWeapon, Rarity, Souls,
+    Spell, SpellShape, TileType, Wand,
+};
 
 pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     // Draw the console box
@@ -15,11 +23,11 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     let players = ecs.read_storage::<Player>();
     for (_player, stats) in (&players, &combat_stats).join() {
         let health = format!(" Hp: {} / {}", stats.hp, stats.max_hp);
-        ctx.print_color(12, 43, RGB::named(YELLOW), RGB::named(BLACK), &health);
+        ctx.print_color(2, 43, RGB::named(YELLOW), RGB::named(BLACK), &health);
         ctx.draw_bar_horizontal(
-            28,
+            16,
             43,
-            51,
+            20,
             stats.hp,
             stats.max_hp,
             RGB::named(RED),
@@ -27,14 +35,71 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
         );
     }
 
-    // Draw the log statements
-    let log = ecs.fetch::<GameLog>();
-    let mut y = 44;
-    for s in log.entries.iter().rev() {
-        if y < 49 {
-            ctx.print(2, y, s);
+    let player_entity = *ecs.fetch::<Entity>();
+    let pools = ecs.read_storage::<Pools>();
+    let player_pools = pools.get(player_entity);
+    if let Some(pools) = player_pools {
+        let stamina = format!(
+            " Stamina: {} / {} ",
+            pools.stamina.current, pools.stamina.max
+        );
+        ctx.print_color(38, 43, RGB::named(GREEN), RGB::named(BLACK), &stamina);
+        let mana = format!(" Mana: {} / {} ", pools.mana.current, pools.mana.max);
+        ctx.print_color(61, 43, RGB::named(CYAN), RGB::named(BLACK), &mana);
+    }
+
+    // The right end of the bottom of the box holds souls and, when it matters, the burden; it's
+    // drawn first so the hotbar knows where it has to stop
+    let mut right = 78;
+    if let Some(souls) = ecs.read_storage::<Souls>().get(player_entity) {
+        let souls = format!(" Souls: {} ", souls.amount);
+        right -= souls.len() as i32;
+        ctx.print_color(right, 49, RGB::named(LIGHT_CYAN), RGB::named(BLACK), &souls);
+    }
+
+    // Only worth a place on the bar once it starts to slow the player down
+    if let Some(encumbrance) = ecs.read_storage::<Encumbrance>().get(player_entity) {
+        if encumbrance.is_burdened() {
+            let burden = format!(
+                " Burdened {:.0}/{:.0} lb ",
+                encumbrance.load, encumbrance.capacity
+            );
+            right -= burden.len() as i32;
+            ctx.print_color(right, 49, RGB::named(ORANGE), RGB::named(BLACK), &burden);
         }
-        y += 1;
+    }
+
+    // The left end shows what the player has ready to shoot, then the spell hotbar
+    let mut x = 2;
+    if let Some(ranged) = ranged_status(ecs) {
+        ctx.print_color(x, 49, RGB::named(WHITE), RGB::named(BLACK), &ranged);
+        x += ranged.len() as i32 + 1;
+    }
+    if let Some(known) = ecs.read_storage::<KnownSpells>().get(player_entity) {
+        let mana = player_pools.map_or(0, |pools| pools.mana.current);
+        let slots = hotbar_slots(&known.spells, right - x);
+        let shown = slots.len();
+        for (slot, spell) in slots.iter().zip(known.spells.iter()) {
+            let fg = if mana >= spell.info().mana_cost {
+                CYAN
+            } else {
+                GREY
+            };
+            ctx.print_color(x, 49, RGB::named(fg), RGB::named(BLACK), slot);
+            x += slot.len() as i32;
+        }
+        let hidden = known.spells.len().min(HOTBAR_SLOTS) - shown;
+        let more = format!("+{}", hidden);
+        if hidden > 0 && x + more.len() as i32 <= right {
+            ctx.print_color(x, 49, RGB::named(GREY), RGB::named(BLACK), &more);
+        }
+    }
+
+    // Draw the log statements; item names in them carry color markup for their rarity, and the
+    // leading reset keeps the text before the first one from being taken for a color name
+    let log = ecs.fetch::<GameLog>();
+    for (y, s) in (44..49).zip(log.entries.iter().rev()) {
+        ctx.printer(2, y, format!("#[]{}", s), TextAlign::Left, None);
     }
 
     // Draw mouse curso
@@ -43,30 +108,131 @@ pub fn draw_ui(ecs: &World, ctx: &mut BTerm) {
     draw_tooltips(ecs, ctx);
 }
 
+/// How many spells the hotbar has keys for
+const HOTBAR_SLOTS: usize = 9;
+
+/// The hotbar's slots, fitted into `width` columns: full names and costs when they all fit, short
+/// names otherwise, and as many of those as there's room for, keeping space for a `+n` marker
+pub fn hotbar_slots(spells: &[Spell], width: i32) -> Vec<String> {
+    let spells = &spells[..spells.len().min(HOTBAR_SLOTS)];
+    let full: Vec<String> = spells
+        .iter()
+        .enumerate()
+        .map(|(i, spell)| {
+            let info = spell.info();
+            format!(" {}:{} ({}) ", i + 1, info.name, info.mana_cost)
+        })
+        .collect();
+    if full.iter().map(|slot| slot.len() as i32).sum::<i32>() <= width {
+        return full;
+    }
+
+    let mut slots = Vec::new();
+    let mut used = 0;
+    for (i, spell) in spells.iter().enumerate() {
+        let slot = format!(" {}:{} ", i + 1, spell.info().abbr);
+        let last = i + 1 == spells.len();
+        let marker = if last { 0 } else { 2 };
+        if used + slot.len() as i32 + marker > width {
+            break;
+        }
+        used += slot.len() as i32;
+        slots.push(slot);
+    }
+    slots
+}
+
+/// The player's ranged weapon and how much ammunition they carry for it
+fn ranged_status(ecs: &World) -> Option<String> {
+    let player_entity = *ecs.fetch::<Entity>();
+    let names = ecs.read_storage::<Name>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let weapons = ecs.read_storage::<RangedWeapon>();
+    let backpack = ecs.read_storage::<InBackpack>();
+    let ammunition = ecs.read_storage::<Ammunition>();
+    let registry = ecs.fetch::<IdentificationRegistry>();
+    let entities = ecs.entities();
+
+    let (item, _, weapon) = (&entities, &equipped, &weapons)
+        .join()
+        .find(|(_, equipped, _)| equipped.owner == player_entity)?;
+    let count: i32 = (&backpack, &ammunition)
+        .join()
+        .filter(|(carried, ammo)| carried.owner == player_entity && ammo.kind == weapon.ammo)
+        .map(|(_, ammo)| ammo.count)
+        .sum();
+    Some(format!(
+        " {}: {} ",
+        item_name(item, &names, &registry),
+        weapon.ammo.describe(count)
+    ))
+}
+
 fn draw_tooltips(ecs: &World, ctx: &mut BTerm) {
     let map = ecs.fetch::<Map>();
     let names = ecs.read_storage::<Name>();
     let positions = ecs.read_storage::<Position>();
+    let awareness = ecs.read_storage::<Awareness>();
+    let ammunition = ecs.read_storage::<Ammunition>();
+    let rarities = ecs.read_storage::<ItemRarity>();
+    let registry = ecs.fetch::<IdentificationRegistry>();
+    let entities = ecs.entities();
 
     let mouse_pos = ctx.mouse_pos();
-    if mouse_pos.0 >= map.width || mouse_pos.1 >= map.height {
-        return;
-    }
-    let mut tooltip: Vec<String> = Vec::new();
-    let tooltip_idx = map.xy_idx(mouse_pos.0, mouse_pos.1);
+    let mouse_world = match Camera::follow_player(ecs).mouse_world_pos(&map, ctx) {
+        Some(world) => world,
+        None => return,
+    };
+    let mut tooltip: Vec<(String, RGB)> = Vec::new();
+    let tooltip_idx = map.xy_idx(mouse_world.x, mouse_world.y);
 
     // Only process tooltips if the tooltip is coming from a visible spot
     if map.visible_tiles[tooltip_idx] {
-        for (name, position) in (&names, &positions).join() {
-            if position.x == mouse_pos.0 && position.y == mouse_pos.1 {
-                tooltip.push(name.name.to_string());
+        for (entity, _, position, awareness, ammo, rarity) in (
+            &entities,
+            &names,
+            &positions,
+            (&awareness).maybe(),
+            (&ammunition).maybe(),
+            (&rarities).maybe(),
+        )
+            .join()
+        {
+            if position.x == mouse_world.x && position.y == mouse_world.y {
+                let white = RGB::named(WHITE);
+                if let Some(ammo) = ammo {
+                    tooltip.push((ammo.kind.describe(ammo.count), white));
+                    if let Some(worth) = weight_and_value(ecs, entity) {
+                        tooltip.push((format!(" {}", worth), white));
+                    }
+                    continue;
+                }
+                let name = item_name(entity, &names, &registry);
+                let label = match awareness.map(|a| a.state) {
+                    Some(AwarenessState::Asleep) => format!("{} (asleep)", name),
+                    Some(AwarenessState::Unaware) => format!("{} (unaware)", name),
+                    _ => name,
+                };
+                tooltip.push((label, rarity.map_or(white, |r| r.rarity.color())));
+                for note in item_notes(ecs, entity) {
+                    tooltip.push((format!(" {}", note), white));
+                }
+                if let Some(worth) = weight_and_value(ecs, entity) {
+                    tooltip.push((format!(" {}", worth), white));
+                }
             }
         }
     }
 
+    // Plain floor and walls go without saying, but other terrain is worth naming
+    let tile = map.tiles[tooltip_idx];
+    if map.revealed_tiles[tooltip_idx] && tile != TileType::Floor && tile != TileType::Wall {
+        tooltip.push((tile.info().name.to_string(), RGB::named(WHITE)));
+    }
+
     if !tooltip.is_empty() {
         let mut width: i32 = 0;
-        for s in tooltip.iter() {
+        for (s, _) in tooltip.iter() {
             if width < s.len() as i32 {
                 width = s.len() as i32;
             }
@@ -76,34 +242,25 @@ fn draw_tooltips(ecs: &World, ctx: &mut BTerm) {
         if mouse_pos.0 > 40 {
             let arrow_pos = Point::new(mouse_pos.0 - 2, mouse_pos.1);
             let left_x = mouse_pos.0 - width;
-            let mut y = mouse_pos.1;
-            for s in tooltip.iter() {
-                ctx.print_color(left_x, y, RGB::named(WHITE), RGB::named(GREY), s);
+            for (y, (s, fg)) in (mouse_pos.1..).zip(tooltip.iter()) {
+                ctx.print_color(left_x, y, *fg, RGB::named(GREY), s);
                 let padding = (width - s.len() as i32) - 1;
                 for i in 0..padding {
-                    ctx.print_color(
-                        arrow_pos.x - i,
-                        y,
-                        RGB::named(WHITE),
-                        RGB::named(GREY),
-                        &" ".to_string(),
-                    );
+                    ctx.print_color(arrow_pos.x - i, y, RGB::named(WHITE), RGB::named(GREY), " ");
                 }
-                y += 1;
             }
             ctx.print_color(
                 arrow_pos.x,
                 arrow_pos.y,
                 RGB::named(WHITE),
                 RGB::named(GREY),
-                &"->".to_string(),
+                "->",
             );
         } else {
             let arrow_pos = Point::new(mouse_pos.0 + 1, mouse_pos.1);
             let left_x = mouse_pos.0 + 3;
-            let mut y = mouse_pos.1;
-            for s in tooltip.iter() {
-                ctx.print_color(left_x + 1, y, RGB::named(WHITE), RGB::named(GREY), s);
+            for (y, (s, fg)) in (mouse_pos.1..).zip(tooltip.iter()) {
+                ctx.print_color(left_x + 1, y, *fg, RGB::named(GREY), s);
                 let padding = (width - s.len() as i32) - 1;
                 for i in 0..padding {
                     ctx.print_color(
@@ -111,18 +268,389 @@ fn draw_tooltips(ecs: &World, ctx: &mut BTerm) {
                         y,
                         RGB::named(WHITE),
                         RGB::named(GREY),
-                        &" ".to_string(),
+                        " ",
                     );
                 }
-                y += 1;
             }
             ctx.print_color(
                 arrow_pos.x,
                 arrow_pos.y,
                 RGB::named(WHITE),
                 RGB::named(GREY),
-                &"<-".to_string(),
+                "<-",
             );
         }
     }
 }
+
+/// What the player is picking a target for
+#[derive(PartialEq, Copy, Clone)]
+pub enum TargetingAction {
+    Shoot,
+    Cast(Spell),
+    /// Casting the wand's spell out of its charges
+    Zap {
+        wand: Entity,
+        spell: Spell,
+    },
+}
+
+impl TargetingAction {
+    fn title(self) -> &'static str {
+        match self.spell() {
+            Some(spell) => &spell.info().name,
+            None => "Shoot",
+        }
+    }
+
+    /// The spell being aimed, whether it comes from the player or a wand
+    fn spell(self) -> Option<Spell> {
+        match self {
+            TargetingAction::Shoot => None,
+            TargetingAction::Cast(spell) | TargetingAction::Zap { spell, .. } => Some(spell),
+        }
+    }
+
+    /// True if nothing stops the shot or spell on its way from `from` to `to`. Shots and
+    /// single-target spells need nobody in the way; area spells only need the way clear of walls.
+    fn has_clear_way(self, map: &Map, from: Point, to: Point) -> bool {
+        match self.spell() {
+            Some(spell) if !spell.info().shape.needs_line_of_fire() => map.has_clear_path(from, to),
+            _ => map.has_line_of_fire(from, to),
+        }
+    }
+
+    /// Area spells can be aimed at any tile in reach, not just at a creature
+    fn aims_at_tiles(self) -> bool {
+        self.spell().is_some_and(|spell| {
+            matches!(
+                spell.info().shape,
+                SpellShape::Radius(_) | SpellShape::Cone | SpellShape::Line
+            )
+        })
+    }
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum TargetingResult {
+    Cancel,
+    NoResponse,
+    Selected(Point),
+}
+
+/// True if the player can see `tile`, it is within `range` and nothing stops `action` getting there
+fn in_reach(
+    map: &Map,
+    player_pos: Point,
+    tile: Point,
+    range: i32,
+    action: TargetingAction,
+) -> bool {
+    tile.x >= 0
+        && tile.x < map.width
+        && tile.y >= 0
+        && tile.y < map.height
+        && map.visible_tiles[map.xy_idx(tile.x, tile.y)]
+        && DistanceAlg::Pythagoras.distance2d(player_pos, tile) <= range as f32
+        && action.has_clear_way(map, player_pos, tile)
+}
+
+/// Everything hostile the player can see within `range` and could reach with `action`, nearest
+/// first
+pub fn targets_in_range(ecs: &World, range: i32, action: TargetingAction) -> Vec<(Entity, Point)> {
+    let player_pos = *ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let entities = ecs.entities();
+    let positions = ecs.read_storage::<Position>();
+    let combat_stats = ecs.read_storage::<CombatStats>();
+    let players = ecs.read_storage::<Player>();
+    let allies = ecs.read_storage::<Ally>();
+
+    let mut targets: Vec<(Entity, Point)> =
+        (&entities, &positions, &combat_stats, !&players, !&allies)
+            .join()
+            .map(|(entity, pos, _, _, _)| (entity, Point::new(pos.x, pos.y)))
+            .filter(|(_, pos)| in_reach(&map, player_pos, *pos, range, action))
+            .collect();
+    targets.sort_by(|(_, a), (_, b)| {
+        let distance_a = DistanceAlg::Pythagoras.distance2d(player_pos, *a);
+        let distance_b = DistanceAlg::Pythagoras.distance2d(player_pos, *b);
+        distance_a.partial_cmp(&distance_b).unwrap()
+    });
+    targets
+}
+
+/// Lets the player pick a target within `range`: click one, or press V or Enter for the nearest
+pub fn ranged_target(
+    ecs: &World,
+    ctx: &BTerm,
+    range: i32,
+    action: TargetingAction,
+) -> TargetingResult {
+    let targets = targets_in_range(ecs, range, action);
+    let area = action.aims_at_tiles();
+    if targets.is_empty() && !area {
+        return TargetingResult::Cancel;
+    }
+
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) => return TargetingResult::Cancel,
+        Some(VirtualKeyCode::V | VirtualKeyCode::Return) => {
+            if let Some((_, nearest)) = targets.first() {
+                return TargetingResult::Selected(*nearest);
+            }
+        }
+        _ => {}
+    }
+
+    if ctx.left_click {
+        let map = ecs.fetch::<Map>();
+        let player_pos = *ecs.fetch::<Point>();
+        if let Some(clicked) = Camera::follow_player(ecs).mouse_world_pos(&map, ctx) {
+            if targets.iter().any(|(_, pos)| *pos == clicked)
+                || (area && in_reach(&map, player_pos, clicked, range, action))
+            {
+                return TargetingResult::Selected(clicked);
+            }
+        }
+    }
+    TargetingResult::NoResponse
+}
+
+/// Shades the tiles within reach, marks what could be hit, and for area spells shows exactly which
+/// tiles the spell would reach
+pub fn draw_targeting(ecs: &World, ctx: &mut BTerm, range: i32, action: TargetingAction) {
+    let camera = Camera::follow_player(ecs);
+    let player_pos = *ecs.fetch::<Point>();
+    {
+        let map = ecs.fetch::<Map>();
+        for y in player_pos.y - range..=player_pos.y + range {
+            for x in player_pos.x - range..=player_pos.x + range {
+                let tile = Point::new(x, y);
+                if !in_reach(&map, player_pos, tile, range, action) {
+                    continue;
+                }
+                if let Some(screen) = camera.world_to_screen(tile) {
+                    ctx.set_bg(screen.x, screen.y, RGB::named(BLUE));
+                }
+            }
+        }
+
+        if let (Some(spell), Some(aim)) = (action.spell(), camera.mouse_world_pos(&map, ctx)) {
+            if action.aims_at_tiles() && in_reach(&map, player_pos, aim, range, action) {
+                let player_entity = *ecs.fetch::<Entity>();
+                for tile in spell.targets(player_entity, player_pos, aim).tiles(&map) {
+                    if let Some(screen) = camera.world_to_screen(tile) {
+                        ctx.set_bg(screen.x, screen.y, RGB::named(DARK_RED));
+                    }
+                }
+            }
+        }
+    }
+
+    for (i, (_, pos)) in targets_in_range(ecs, range, action).iter().enumerate() {
+        if let Some(screen) = camera.world_to_screen(*pos) {
+            let highlight = if i == 0 { ORANGE } else { CYAN };
+            ctx.set_bg(screen.x, screen.y, RGB::named(highlight));
+        }
+    }
+
+    ctx.print_color(
+        1,
+        0,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        format!(
+            "{}: click a target, V for the nearest, Escape to cancel",
+            action.title()
+        ),
+    );
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum ItemMenuResult {
+    Cancel,
+    NoResponse,
+    Selected(Entity),
+    Dropped(Entity),
+}
+
+/// Everything the player carries, in the order the inventory lists it
+fn player_items(ecs: &World) -> Vec<Entity> {
+    let player_entity = *ecs.fetch::<Entity>();
+    let entities = ecs.entities();
+    let backpack = ecs.read_storage::<InBackpack>();
+    (&entities, &backpack)
+        .join()
+        .filter(|(_, carried)| carried.owner == player_entity)
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// What's worth knowing about an item besides its name: a curse that has shown itself, how worn it
+/// is, and how many charges it has left
+fn item_notes(ecs: &World, item: Entity) -> Vec<String> {
+    let mut notes = Vec::new();
+    if ecs
+        .read_storage::<Cursed>()
+        .get(item)
+        .is_some_and(|curse| curse.known)
+    {
+        notes.push("cursed".to_string());
+    }
+    if let Some(wear) = ecs.read_storage::<Durability>().get(item) {
+        if wear.is_broken() {
+            notes.push("broken".to_string());
+        } else {
+            notes.push(format!("durability {}/{}", wear.current, wear.max));
+        }
+    }
+    if let Some(wand) = ecs.read_storage::<Wand>().get(item) {
+        notes.push(format!("{}/{} charges", wand.charges, wand.max_charges));
+    }
+    notes
+}
+
+/// What an item weighs and is worth; a stack counts every missile in it
+fn weight_and_value(ecs: &World, item: Entity) -> Option<String> {
+    let details = ecs.read_storage::<Item>();
+    let details = details.get(item)?;
+    let count = ecs
+        .read_storage::<Ammunition>()
+        .get(item)
+        .map_or(1, |ammo| ammo.count);
+    Some(format!(
+        "{:.1} lb, worth {} gold",
+        details.weight * count as f32,
+        details.value * count
+    ))
+}
+
+/// How an item is listed in the inventory, colored by its rarity: stacks show their count, and
+/// readied gear and anything else worth knowing follow in brackets
+fn item_label(ecs: &World, item: Entity) -> (String, RGB) {
+    let names = ecs.read_storage::<Name>();
+    let ammunition = ecs.read_storage::<Ammunition>();
+    let equipped = ecs.read_storage::<Equipped>();
+    let registry = ecs.fetch::<IdentificationRegistry>();
+    let rarity = ecs
+        .read_storage::<ItemRarity>()
+        .get(item)
+        .map_or(Rarity::Common, |r| r.rarity);
+
+    let mut label = match ammunition.get(item) {
+        Some(ammo) => ammo.kind.describe(ammo.count),
+        None => item_name(item, &names, &registry),
+    };
+    let mut notes = item_notes(ecs, item);
+    if equipped.get(item).is_some() {
+        notes.insert(0, "ready".to_string());
+    }
+    if !notes.is_empty() {
+        label.push_str(&format!(" ({})", notes.join(", ")));
+    }
+    (label, rarity.color())
+}
+
+/// Picks an item from the inventory by its letter, or with shift held drops it; Escape closes it
+pub fn inventory_input(ecs: &World, ctx: &BTerm) -> ItemMenuResult {
+    match ctx.key {
+        None => ItemMenuResult::NoResponse,
+        Some(VirtualKeyCode::Escape) => ItemMenuResult::Cancel,
+        Some(key) => {
+            let items = player_items(ecs);
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < items.len() {
+                let item = items[selection as usize];
+                if ctx.shift {
+                    ItemMenuResult::Dropped(item)
+                } else {
+                    ItemMenuResult::Selected(item)
+                }
+            } else {
+                ItemMenuResult::NoResponse
+            }
+        }
+    }
+}
+
+/// Lists what the player carries, each with the letter that uses it
+pub fn draw_inventory(ecs: &World, ctx: &mut BTerm) {
+    let labels: Vec<(String, RGB)> = player_items(ecs)
+        .into_iter()
+        .map(|item| item_label(ecs, item))
+        .collect();
+    let height = labels.len() as i32;
+    let top = 25 - height / 2;
+    ctx.draw_box(
+        12,
+        top - 2,
+        64,
+        height + 3,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+    );
+    ctx.print_color(
+        15,
+        top - 2,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        "Inventory",
+    );
+    ctx.print_color(
+        15,
+        top + height + 1,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        "Escape to close, shift to drop",
+    );
+    if let Some(encumbrance) = ecs
+        .read_storage::<Encumbrance>()
+        .get(*ecs.fetch::<Entity>())
+    {
+        let load = format!(
+            "Carrying {:.1} / {:.1} lb",
+            encumbrance.load, encumbrance.capacity
+        );
+        let fg = if encumbrance.is_burdened() {
+            ORANGE
+        } else {
+            YELLOW
+        };
+        ctx.print_color(
+            74 - load.len() as i32,
+            top + height + 1,
+            RGB::named(fg),
+            RGB::named(BLACK),
+            &load,
+        );
+    }
+
+    if labels.is_empty() {
+        ctx.print(14, top, "You carry nothing.");
+    }
+    for (i, (label, fg)) in labels.iter().enumerate() {
+        let y = top + i as i32;
+        ctx.set(14, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437('('));
+        ctx.set(
+            15,
+            y,
+            RGB::named(YELLOW),
+            RGB::named(BLACK),
+            97 + i as bracket_lib::prelude::FontCharType,
+        );
+        ctx.set(16, y, RGB::named(WHITE), RGB::named(BLACK), to_cp437(')'));
+        ctx.print_color(18, y, *fg, RGB::named(BLACK), label);
+    }
+}
+
+/// The end of a permadeath run
+pub fn draw_game_over(ctx: &mut BTerm) {
+    ctx.print_color_centered(20, RGB::named(RED), RGB::named(BLACK), "You are dead.");
+    ctx.print_color_centered(
+        22,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "Your journey ends here. Press any key to quit.",
+    );
+}
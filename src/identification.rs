@@ -0,0 +1,105 @@
+use std::collections::{HashMap, HashSet};
+
+use bracket_lib::random::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+
+use crate::Name;
+
+/// Potions and scrolls, by their real names, whose names are hidden until the player learns them
+const UNIDENTIFIED_POTIONS: [&str; 2] = ["Health Potion", "Mana Potion"];
+const UNIDENTIFIED_SCROLLS: [&str; 3] = [
+    "Scroll of Identify",
+    "Scroll of Teleportation",
+    "Scroll of Remove Curse",
+];
+
+const POTION_LOOKS: [&str; 8] = [
+    "murky", "bubbling", "cloudy", "fizzing", "oily", "glowing", "swirling", "thick",
+];
+const POTION_COLORS: [&str; 8] = [
+    "green", "red", "blue", "amber", "violet", "black", "silver", "pink",
+];
+const SCROLL_SYLLABLES: [&str; 10] = [
+    "ka", "zu", "mor", "eth", "ul", "vin", "dra", "pho", "ix", "nel",
+];
+
+/// The disguises potions and scrolls wear this run, and which of them the player has seen through.
+/// The disguises come from the run's seed, so the same seed always hides things the same way.
+/// It derives serde's traits so it can go into a saved game, once there is one.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IdentificationRegistry {
+    /// Real name to disguised name
+    pub disguises: HashMap<String, String>,
+    /// Real names the player knows
+    pub identified: HashSet<String>,
+}
+
+fn pick<'a>(rng: &mut RandomNumberGenerator, from: &[&'a str]) -> &'a str {
+    from[(rng.roll_dice(1, from.len() as i32) - 1) as usize]
+}
+
+impl IdentificationRegistry {
+    pub fn new(seed: u64) -> IdentificationRegistry {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let mut disguises: HashMap<String, String> = HashMap::new();
+
+        for potion in UNIDENTIFIED_POTIONS {
+            let disguise = loop {
+                let candidate = format!(
+                    "{} {} potion",
+                    pick(&mut rng, &POTION_LOOKS),
+                    pick(&mut rng, &POTION_COLORS)
+                );
+                if !disguises.values().any(|taken| *taken == candidate) {
+                    break candidate;
+                }
+            };
+            disguises.insert(potion.to_string(), disguise);
+        }
+
+        for scroll in UNIDENTIFIED_SCROLLS {
+            let disguise = loop {
+                let syllables = rng.roll_dice(1, 2) + 1;
+                let label: String = (0..syllables)
+                    .map(|_| pick(&mut rng, &SCROLL_SYLLABLES))
+                    .collect();
+                let candidate = format!("scroll labelled {}", label.to_uppercase());
+                if !disguises.values().any(|taken| *taken == candidate) {
+                    break candidate;
+                }
+            };
+            disguises.insert(scroll.to_string(), disguise);
+        }
+
+        IdentificationRegistry {
+            disguises,
+            identified: HashSet::new(),
+        }
+    }
+
+    /// What the player calls something with this real name
+    pub fn name_for<'a>(&'a self, real_name: &'a str) -> &'a str {
+        match self.disguises.get(real_name) {
+            Some(disguise) if !self.identified.contains(real_name) => disguise,
+            _ => real_name,
+        }
+    }
+
+    /// Learns the real name behind a disguise, returning true if it wasn't already known
+    pub fn identify(&mut self, real_name: &str) -> bool {
+        self.disguises.contains_key(real_name) && self.identified.insert(real_name.to_string())
+    }
+}
+
+/// The name an item goes by wherever the player can see it: its real name, unless it is still
+/// disguised. All item names shown in the UI come through here.
+pub fn item_name(
+    item: Entity,
+    names: &ReadStorage<Name>,
+    registry: &IdentificationRegistry,
+) -> String {
+    names.get(item).map_or("something".to_string(), |name| {
+        registry.name_for(&name.name).to_string()
+    })
+}
@@ -0,0 +1,454 @@
+use specs::prelude::*;
+
+use crate::{
+    item_name, Ammunition, Consumable, Cursed, Durability, Effect, EffectQueue, Equippable,
+    Equipped, GameLog, IdentificationRegistry, IdentifiesItems, InBackpack, ItemRarity,
+    KnownSpells, Name, Position, ProvidesHealing, ProvidesMana, Rarity, RemovesCurses,
+    RepairsItems, Targets, TeachesSpell, TeleportsUser, WantsToDropItem, WantsToPickupItem,
+    WantsToUseItem,
+};
+
+/// An item's name as the log shows it, in its rarity's color
+fn log_name(
+    item: Entity,
+    names: &ReadStorage<Name>,
+    rarities: &ReadStorage<ItemRarity>,
+    registry: &IdentificationRegistry,
+) -> String {
+    let rarity = rarities.get(item).map_or(Rarity::Common, |r| r.rarity);
+    rarity.markup(&item_name(item, names, registry))
+}
+
+/// Lets a cursed item show itself for what it is, once it has been put on
+fn reveal_curse(
+    cursed: &mut WriteStorage<Cursed>,
+    log: &mut GameLog,
+    item: Entity,
+    by_player: bool,
+) {
+    if let Some(curse) = cursed.get_mut(item) {
+        if by_player && !curse.known {
+            log.entries
+                .push("It's cursed! You can't let go of it.".to_string());
+        }
+        curse.known = true;
+    }
+}
+
+/// Moves picked up items into their collector's pack. Ammunition joins a stack of the same kind
+/// if there is one, and anything that can be equipped is, if its slot is free.
+pub struct ItemCollectionSystem {}
+
+impl<'a> System<'a> for ItemCollectionSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadExpect<'a, IdentificationRegistry>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToPickupItem>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, ItemRarity>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Ammunition>,
+        ReadStorage<'a, Equippable>,
+        WriteStorage<'a, Equipped>,
+        WriteStorage<'a, Cursed>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut log,
+            registry,
+            entities,
+            mut wants_pickup,
+            mut positions,
+            names,
+            rarities,
+            mut backpack,
+            mut ammunition,
+            equippable,
+            mut equipped,
+            mut cursed,
+        ) = data;
+
+        for (collector, pickup) in (&entities, &wants_pickup).join() {
+            let item = pickup.item;
+            positions.remove(item);
+            let item_name = match ammunition.get(item) {
+                Some(ammo) => ammo.kind.describe(ammo.count),
+                None => format!("the {}", log_name(item, &names, &rarities, &registry)),
+            };
+
+            let merge_into = ammunition.get(item).and_then(|picked_up| {
+                (&entities, &backpack, &ammunition)
+                    .join()
+                    .find(|(_, carried, ammo)| {
+                        carried.owner == collector && ammo.kind == picked_up.kind
+                    })
+                    .map(|(stack, _, _)| stack)
+            });
+            match merge_into {
+                Some(stack) => {
+                    let count = ammunition.get(item).unwrap().count;
+                    ammunition.get_mut(stack).unwrap().count += count;
+                    entities
+                        .delete(item)
+                        .expect("Unable to delete merged ammunition");
+                }
+                None => {
+                    backpack
+                        .insert(item, InBackpack { owner: collector })
+                        .expect("Unable to insert backpack entry");
+                }
+            }
+
+            let mut wielded = false;
+            if let Some(can_equip) = equippable.get(item) {
+                let slot_taken = (&equipped)
+                    .join()
+                    .any(|e| e.owner == collector && e.slot == can_equip.slot);
+                if !slot_taken {
+                    equipped
+                        .insert(
+                            item,
+                            Equipped {
+                                owner: collector,
+                                slot: can_equip.slot,
+                            },
+                        )
+                        .expect("Unable to equip item");
+                    wielded = true;
+                }
+            }
+
+            if collector == *player_entity {
+                if wielded {
+                    log.entries
+                        .push(format!("You pick up and ready {}.", item_name));
+                } else {
+                    log.entries.push(format!("You pick up {}.", item_name));
+                }
+            }
+            if wielded {
+                reveal_curse(&mut cursed, &mut log, item, collector == *player_entity);
+            }
+        }
+
+        wants_pickup.clear();
+    }
+}
+
+/// Uses items out of the pack: potions are drunk, scrolls and spellbooks read, and weapons readied
+/// in place of whatever was in their slot. Consumables are gone once used, and using something is
+/// one way for the player to learn what it really is.
+pub struct ItemUseSystem {}
+
+impl<'a> System<'a> for ItemUseSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, IdentificationRegistry>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToUseItem>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, ItemRarity>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Consumable>,
+        ReadStorage<'a, ProvidesHealing>,
+        ReadStorage<'a, ProvidesMana>,
+        ReadStorage<'a, TeleportsUser>,
+        ReadStorage<'a, IdentifiesItems>,
+        ReadStorage<'a, RemovesCurses>,
+        ReadStorage<'a, RepairsItems>,
+        WriteStorage<'a, Cursed>,
+        WriteStorage<'a, Durability>,
+        ReadStorage<'a, TeachesSpell>,
+        WriteStorage<'a, KnownSpells>,
+        ReadStorage<'a, Equippable>,
+        WriteStorage<'a, Equipped>,
+        Write<'a, EffectQueue>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut log,
+            mut registry,
+            entities,
+            mut wants_use,
+            names,
+            rarities,
+            backpack,
+            consumables,
+            healing,
+            restores_mana,
+            teleports,
+            identifies,
+            removes_curses,
+            repairs,
+            mut cursed,
+            mut durability,
+            teaches,
+            mut known_spells,
+            equippable,
+            mut equipped,
+            mut effects,
+        ) = data;
+
+        for (user, use_item) in (&entities, &wants_use).join() {
+            let item = use_item.item;
+            let item_name = &log_name(item, &names, &rarities, &registry);
+            let mut used = false;
+
+            if let Some(can_equip) = equippable.get(item) {
+                if equipped.get(item).is_some() {
+                    log.entries
+                        .push(format!("The {} is already ready.", item_name));
+                } else {
+                    let in_slot: Vec<Entity> = (&entities, &equipped)
+                        .join()
+                        .filter(|(_, e)| e.owner == user && e.slot == can_equip.slot)
+                        .map(|(entity, _)| entity)
+                        .collect();
+                    let stuck = in_slot.iter().find(|previous| cursed.contains(**previous));
+                    if let Some(stuck) = stuck {
+                        if let Some(curse) = cursed.get_mut(*stuck) {
+                            curse.known = true;
+                        }
+                        log.entries.push(format!(
+                            "The cursed {} won't let go of you.",
+                            log_name(*stuck, &names, &rarities, &registry)
+                        ));
+                    } else {
+                        for previous in in_slot {
+                            equipped.remove(previous);
+                        }
+                        equipped
+                            .insert(
+                                item,
+                                Equipped {
+                                    owner: user,
+                                    slot: can_equip.slot,
+                                },
+                            )
+                            .expect("Unable to equip item");
+                        log.entries.push(format!("You ready the {}.", item_name));
+                        reveal_curse(&mut cursed, &mut log, item, user == *player_entity);
+                    }
+                }
+                used = true;
+            }
+
+            if let Some(healing) = healing.get(item) {
+                log.entries.push(format!("You drink the {}.", item_name));
+                effects.add_effect(
+                    Some(user),
+                    Effect::Heal {
+                        amount: healing.heal_amount,
+                    },
+                    Targets::Single { target: user },
+                );
+                used = true;
+            }
+
+            if let Some(mana) = restores_mana.get(item) {
+                log.entries.push(format!("You drink the {}.", item_name));
+                effects.add_effect(
+                    Some(user),
+                    Effect::RestoreMana {
+                        amount: mana.mana_amount,
+                    },
+                    Targets::Single { target: user },
+                );
+                used = true;
+            }
+
+            if let Some(teleports) = teleports.get(item) {
+                log.entries.push(format!("You read the {}.", item_name));
+                effects.add_effect(
+                    Some(user),
+                    Effect::Teleport {
+                        range: teleports.range,
+                    },
+                    Targets::Single { target: user },
+                );
+                used = true;
+            }
+
+            if identifies.get(item).is_some() {
+                log.entries.push(format!("You read the {}.", item_name));
+                let carried: Vec<Entity> = (&entities, &backpack)
+                    .join()
+                    .filter(|(other, carried)| carried.owner == user && *other != item)
+                    .map(|(other, _)| other)
+                    .collect();
+                let mut learned = false;
+                for other in carried {
+                    let disguised = log_name(other, &names, &rarities, &registry);
+                    let real_name = &names.get(other).unwrap().name;
+                    if registry.identify(real_name) {
+                        log.entries
+                            .push(format!("The {} is a {}.", disguised, real_name));
+                        learned = true;
+                    }
+                    if let Some(curse) = cursed.get_mut(other).filter(|curse| !curse.known) {
+                        curse.known = true;
+                        log.entries.push(format!(
+                            "The {} is cursed.",
+                            log_name(other, &names, &rarities, &registry)
+                        ));
+                        learned = true;
+                    }
+                }
+                if !learned {
+                    log.entries
+                        .push("You learn nothing you didn't already know.".to_string());
+                }
+                used = true;
+            }
+
+            if removes_curses.get(item).is_some() {
+                log.entries.push(format!("You read the {}.", item_name));
+                let lifted: Vec<Entity> = (&entities, &backpack, &cursed)
+                    .join()
+                    .filter(|(_, carried, _)| carried.owner == user)
+                    .map(|(other, _, _)| other)
+                    .collect();
+                if lifted.is_empty() {
+                    log.entries
+                        .push("You feel as if someone is watching over you.".to_string());
+                }
+                for other in lifted {
+                    cursed.remove(other);
+                    log.entries.push(format!(
+                        "The {} glows softly.",
+                        log_name(other, &names, &rarities, &registry)
+                    ));
+                }
+                used = true;
+            }
+
+            if repairs.get(item).is_some() {
+                log.entries.push(format!("You use the {}.", item_name));
+                for (_, carried, wear) in (&entities, &backpack, &mut durability).join() {
+                    if carried.owner == user {
+                        wear.current = wear.max;
+                    }
+                }
+                log.entries.push("Your gear is as good as new.".to_string());
+                used = true;
+            }
+
+            if let Some(teaches) = teaches.get(item) {
+                let spell_name = &teaches.spell.info().name;
+                log.entries.push(format!("You read the {}.", item_name));
+                match known_spells.get_mut(user) {
+                    Some(known) if known.spells.contains(&teaches.spell) => {
+                        log.entries
+                            .push(format!("You already know {}.", spell_name));
+                    }
+                    Some(known) => {
+                        known.spells.push(teaches.spell);
+                        log.entries.push(format!("You learn {}!", spell_name));
+                    }
+                    None => {
+                        known_spells
+                            .insert(
+                                user,
+                                KnownSpells {
+                                    spells: vec![teaches.spell],
+                                },
+                            )
+                            .expect("Unable to insert known spells");
+                        log.entries.push(format!("You learn {}!", spell_name));
+                    }
+                }
+                used = true;
+            }
+
+            // Whatever the player has used, they now know for what it is
+            let real_name = &names.get(item).unwrap().name;
+            if used && user == *player_entity && registry.identify(real_name) {
+                log.entries.push(format!("It was a {}!", real_name));
+            }
+
+            if !used {
+                log.entries
+                    .push(format!("You can't do anything with the {}.", item_name));
+            } else if consumables.get(item).is_some() {
+                entities.delete(item).expect("Unable to delete used item");
+            }
+        }
+
+        wants_use.clear();
+    }
+}
+
+/// Sets dropped items down where their owner stands. Cursed gear that is put on can't be dropped.
+pub struct ItemDropSystem {}
+
+impl<'a> System<'a> for ItemDropSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadExpect<'a, IdentificationRegistry>,
+        Entities<'a>,
+        WriteStorage<'a, WantsToDropItem>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, ItemRarity>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Equipped>,
+        WriteStorage<'a, Cursed>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            mut log,
+            registry,
+            entities,
+            mut wants_drop,
+            mut positions,
+            names,
+            rarities,
+            mut backpack,
+            mut equipped,
+            mut cursed,
+        ) = data;
+
+        for (dropper, to_drop) in (&entities, &wants_drop).join() {
+            let item = to_drop.item;
+            let item_name = log_name(item, &names, &rarities, &registry);
+            if equipped.get(item).is_some() {
+                if let Some(curse) = cursed.get_mut(item) {
+                    curse.known = true;
+                    if dropper == *player_entity {
+                        log.entries
+                            .push(format!("The cursed {} won't let go of you.", item_name));
+                    }
+                    continue;
+                }
+            }
+            let Some(dropper_pos) = positions.get(dropper).cloned() else {
+                continue;
+            };
+
+            positions
+                .insert(item, dropper_pos)
+                .expect("Unable to insert dropped item's position");
+            backpack.remove(item);
+            equipped.remove(item);
+            if dropper == *player_entity {
+                log.entries.push(format!("You drop the {}.", item_name));
+            }
+        }
+
+        wants_drop.clear();
+    }
+}
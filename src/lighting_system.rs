@@ -0,0 +1,54 @@
+use bracket_lib::{
+    pathfinding::field_of_view,
+    terminal::{DistanceAlg, Point, RGB},
+};
+use specs::prelude::*;
+
+use crate::{LightSource, Map, Position, Viewshed};
+
+/// Works out how much light falls on every tile. Each source lights what it can see, brightest
+/// at its own tile and fading to nothing at the edge of its range, on top of the level's ambient
+/// light. What can be seen depends on the light, so viewsheds are refreshed whenever it changes.
+pub struct LightingSystem {}
+
+impl<'a> System<'a> for LightingSystem {
+    type SystemData = (
+        WriteExpect<'a, Map>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, LightSource>,
+        WriteStorage<'a, Viewshed>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut map, positions, light_sources, mut viewsheds) = data;
+
+        let mut light = vec![map.ambient_light; map.tiles.len()];
+        for (pos, source) in (&positions, &light_sources).join() {
+            let origin = Point::new(pos.x, pos.y);
+            for tile in field_of_view(origin, source.range, &*map) {
+                if tile.x < 0 || tile.x >= map.width || tile.y < 0 || tile.y >= map.height {
+                    continue;
+                }
+                let distance = DistanceAlg::Pythagoras.distance2d(origin, tile);
+                let intensity = 1.0 - distance / source.range as f32;
+                if intensity <= 0.0 {
+                    continue;
+                }
+                let idx = map.xy_idx(tile.x, tile.y);
+                let lit = light[idx];
+                light[idx] = RGB::from_f32(
+                    f32::min(1.0, lit.r + source.color.r * intensity),
+                    f32::min(1.0, lit.g + source.color.g * intensity),
+                    f32::min(1.0, lit.b + source.color.b * intensity),
+                );
+            }
+        }
+
+        if light != map.light {
+            map.light = light;
+            for viewshed in (&mut viewsheds).join() {
+                viewshed.dirty = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::mem::discriminant;
+
+use bracket_lib::{
+    color::{register_palette_color, RGB},
+    random::RandomNumberGenerator,
+};
+
+use crate::{DamageType, EquipmentBonus, EquipmentSlot, Status, StatusProc};
+
+/// How rare a piece of loot is, and so how much magic it carries
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub enum Rarity {
+    Common,
+    /// One or two affixes, named after them
+    Magic,
+    /// Three affixes and a name of its own
+    Rare,
+    /// One of a kind, with fixed powers
+    Unique,
+}
+
+impl Rarity {
+    pub fn color(self) -> RGB {
+        match self {
+            Rarity::Common => RGB::from_f32(1.0, 1.0, 1.0),
+            Rarity::Magic => RGB::from_f32(0.4, 0.5, 1.0),
+            Rarity::Rare => RGB::from_f32(1.0, 1.0, 0.3),
+            Rarity::Unique => RGB::from_f32(0.9, 0.6, 0.2),
+        }
+    }
+
+    /// The palette name the log's color markup uses for this rarity
+    fn palette_name(self) -> &'static str {
+        match self {
+            Rarity::Common => "common",
+            Rarity::Magic => "magic",
+            Rarity::Rare => "rare",
+            Rarity::Unique => "unique",
+        }
+    }
+
+    /// How many times its base item's worth an item of this rarity fetches
+    pub fn value_multiplier(self) -> i32 {
+        match self {
+            Rarity::Common => 1,
+            Rarity::Magic => 3,
+            Rarity::Rare => 6,
+            Rarity::Unique => 12,
+        }
+    }
+
+    /// Wraps `text` in markup so the log shows it in this rarity's color
+    pub fn markup(self, text: &str) -> String {
+        format!("#[{}]{}#[]", self.palette_name(), text)
+    }
+}
+
+/// Teaches the log's color markup the rarity colors; call once before drawing anything
+pub fn register_rarity_colors() {
+    for rarity in [Rarity::Common, Rarity::Magic, Rarity::Rare, Rarity::Unique] {
+        register_palette_color(rarity.palette_name(), rarity.color());
+    }
+}
+
+/// What an affix does for the item it is on
+#[derive(Copy, Clone, Debug)]
+pub enum AffixBonus {
+    Power(i32),
+    Defense(i32),
+    /// The weapon deals this kind of damage instead
+    Converts(DamageType),
+    Resists(DamageType),
+    Proc(StatusProc),
+}
+
+impl AffixBonus {
+    /// True for bonuses that only mean something on a weapon
+    fn weapons_only(self) -> bool {
+        matches!(
+            self,
+            AffixBonus::Power(_) | AffixBonus::Converts(_) | AffixBonus::Proc(_)
+        )
+    }
+
+    fn apply(self, bonus: &mut EquipmentBonus) {
+        match self {
+            AffixBonus::Power(power) => bonus.power += power,
+            AffixBonus::Defense(defense) => bonus.defense += defense,
+            AffixBonus::Converts(kind) => bonus.damage_type = Some(kind),
+            AffixBonus::Resists(kind) => bonus.resists.push(kind),
+            AffixBonus::Proc(proc) => bonus.procs.push(proc),
+        }
+    }
+}
+
+/// A prefix or suffix that can be rolled onto loot from `min_level` down
+pub struct Affix {
+    pub name: &'static str,
+    pub min_level: i32,
+    pub bonus: AffixBonus,
+}
+
+const fn affix(name: &'static str, min_level: i32, bonus: AffixBonus) -> Affix {
+    Affix {
+        name,
+        min_level,
+        bonus,
+    }
+}
+
+const fn proc(status: Status, chance: i32, turns: i32) -> AffixBonus {
+    AffixBonus::Proc(StatusProc {
+        status,
+        chance,
+        turns,
+    })
+}
+
+static PREFIXES: [Affix; 8] = [
+    affix("Sharp", 1, AffixBonus::Power(1)),
+    affix("Sturdy", 1, AffixBonus::Defense(1)),
+    affix("Flaming", 2, AffixBonus::Converts(DamageType::Fire)),
+    affix("Vicious", 3, AffixBonus::Power(2)),
+    affix("Frozen", 3, AffixBonus::Converts(DamageType::Cold)),
+    affix("Warded", 4, AffixBonus::Defense(2)),
+    affix("Spectral", 5, AffixBonus::Converts(DamageType::Spirit)),
+    affix("Deadly", 6, AffixBonus::Power(3)),
+];
+
+static SUFFIXES: [Affix; 8] = [
+    affix("of Might", 1, AffixBonus::Power(1)),
+    affix("of Warmth", 1, AffixBonus::Resists(DamageType::Cold)),
+    affix("of the Bog", 1, AffixBonus::Resists(DamageType::Poison)),
+    affix("of Venom", 2, proc(Status::Poisoned, 25, 4)),
+    affix("of Protection", 2, AffixBonus::Defense(1)),
+    affix(
+        "of the Salamander",
+        2,
+        AffixBonus::Resists(DamageType::Fire),
+    ),
+    affix("of Stunning", 4, proc(Status::Stunned, 15, 1)),
+    affix("of Warding", 5, AffixBonus::Resists(DamageType::Spirit)),
+];
+
+/// Something equippable that loot can be rolled on; the spawner knows how to build each one
+pub struct BaseItem {
+    pub name: &'static str,
+    pub slot: EquipmentSlot,
+}
+
+static BASE_ITEMS: [BaseItem; 6] = [
+    BaseItem {
+        name: "Dagger",
+        slot: EquipmentSlot::Melee,
+    },
+    BaseItem {
+        name: "Short Sword",
+        slot: EquipmentSlot::Melee,
+    },
+    BaseItem {
+        name: "Mace",
+        slot: EquipmentSlot::Melee,
+    },
+    BaseItem {
+        name: "Sling",
+        slot: EquipmentSlot::Ranged,
+    },
+    BaseItem {
+        name: "Shortbow",
+        slot: EquipmentSlot::Ranged,
+    },
+    BaseItem {
+        name: "Buckler",
+        slot: EquipmentSlot::Shield,
+    },
+];
+
+pub fn base_item(name: &str) -> Option<&'static BaseItem> {
+    BASE_ITEMS.iter().find(|base| base.name == name)
+}
+
+/// Any base item, at random
+pub fn random_base_item(rng: &mut RandomNumberGenerator) -> &'static BaseItem {
+    &BASE_ITEMS[(rng.roll_dice(1, BASE_ITEMS.len() as i32) - 1) as usize]
+}
+
+/// A named item with fixed powers, found no shallower than `min_level`
+struct UniqueItem {
+    name: &'static str,
+    base: &'static str,
+    min_level: i32,
+    bonuses: &'static [AffixBonus],
+}
+
+static UNIQUES: [UniqueItem; 3] = [
+    UniqueItem {
+        name: "Goblinbane",
+        base: "Short Sword",
+        min_level: 2,
+        bonuses: &[AffixBonus::Power(2), proc(Status::Stunned, 20, 1)],
+    },
+    UniqueItem {
+        name: "Marshlight Ward",
+        base: "Buckler",
+        min_level: 2,
+        bonuses: &[
+            AffixBonus::Defense(1),
+            AffixBonus::Resists(DamageType::Poison),
+            AffixBonus::Resists(DamageType::Cold),
+        ],
+    },
+    UniqueItem {
+        name: "Emberstring",
+        base: "Shortbow",
+        min_level: 3,
+        bonuses: &[AffixBonus::Power(1), AffixBonus::Converts(DamageType::Fire)],
+    },
+];
+
+/// Rare items are named by putting a word from each list in front of the base item's name
+static RARE_FIRST: [&str; 8] = [
+    "Grim", "Storm", "Blood", "Doom", "Ghoul", "Dusk", "Bone", "Rune",
+];
+static RARE_SECOND: [&str; 8] = [
+    "Bite", "Song", "Ward", "Fang", "Mark", "Hook", "Shroud", "Thirst",
+];
+
+/// A piece of loot as rolled: the base it is built on, and what the roll made of it
+pub struct LootRoll {
+    pub base: &'static str,
+    pub name: String,
+    pub rarity: Rarity,
+    pub bonus: EquipmentBonus,
+    pub cursed: bool,
+}
+
+/// The percent chance that loot other than a unique comes out cursed
+const CURSE_CHANCE: i32 = 10;
+
+/// Draws an affix from `table` that suits the base and the level, and doesn't do the same kind of
+/// thing as one already chosen
+fn pick_affix(
+    rng: &mut RandomNumberGenerator,
+    table: &'static [Affix],
+    base: &BaseItem,
+    level: i32,
+    chosen: &[&'static Affix],
+) -> Option<&'static Affix> {
+    let is_weapon = base.slot != EquipmentSlot::Shield;
+    let candidates: Vec<&'static Affix> = table
+        .iter()
+        .filter(|affix| affix.min_level <= level)
+        .filter(|affix| is_weapon || !affix.bonus.weapons_only())
+        .filter(|affix| {
+            chosen
+                .iter()
+                .all(|other| discriminant(&other.bonus) != discriminant(&affix.bonus))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+    Some(candidates[(rng.roll_dice(1, candidates.len() as i32) - 1) as usize])
+}
+
+/// Rolls loot on `base` for a level `level` deep: usually a plain item, sometimes one with magic
+/// affixes, and now and then a rare or unique one. Deeper levels roll better.
+pub fn roll_loot(rng: &mut RandomNumberGenerator, base: &'static BaseItem, level: i32) -> LootRoll {
+    let roll = rng.roll_dice(1, 100) + level * 2;
+    let unique = UNIQUES
+        .iter()
+        .find(|unique| unique.base == base.name && unique.min_level <= level);
+
+    let mut bonus = EquipmentBonus::default();
+    if let (true, Some(unique)) = (roll >= 99, unique) {
+        for affix_bonus in unique.bonuses {
+            affix_bonus.apply(&mut bonus);
+        }
+        return LootRoll {
+            base: base.name,
+            name: unique.name.to_string(),
+            rarity: Rarity::Unique,
+            bonus,
+            cursed: false,
+        };
+    }
+
+    let (rarity, prefixes, suffixes) = if roll >= 90 {
+        // A third affix goes on whichever end has room
+        if rng.roll_dice(1, 2) == 1 {
+            (Rarity::Rare, 2, 1)
+        } else {
+            (Rarity::Rare, 1, 2)
+        }
+    } else if roll >= 65 {
+        match rng.roll_dice(1, 3) {
+            1 => (Rarity::Magic, 1, 0),
+            2 => (Rarity::Magic, 0, 1),
+            _ => (Rarity::Magic, 1, 1),
+        }
+    } else {
+        (Rarity::Common, 0, 0)
+    };
+
+    let mut chosen: Vec<&'static Affix> = Vec::new();
+    let mut prefix_names: Vec<&str> = Vec::new();
+    let mut suffix_names: Vec<&str> = Vec::new();
+    for _ in 0..prefixes {
+        if let Some(affix) = pick_affix(rng, &PREFIXES, base, level, &chosen) {
+            chosen.push(affix);
+            prefix_names.push(affix.name);
+        }
+    }
+    for _ in 0..suffixes {
+        if let Some(affix) = pick_affix(rng, &SUFFIXES, base, level, &chosen) {
+            chosen.push(affix);
+            suffix_names.push(affix.name);
+        }
+    }
+    for affix in chosen.iter() {
+        affix.bonus.apply(&mut bonus);
+    }
+    // With no affixes to be had at this level, a lucky roll is just a plain item
+    let rarity = if chosen.is_empty() {
+        Rarity::Common
+    } else {
+        rarity
+    };
+
+    let name = match rarity {
+        Rarity::Rare => {
+            let first = RARE_FIRST[(rng.roll_dice(1, RARE_FIRST.len() as i32) - 1) as usize];
+            let second = RARE_SECOND[(rng.roll_dice(1, RARE_SECOND.len() as i32) - 1) as usize];
+            format!("{} {} {}", first, second, base.name)
+        }
+        _ => prefix_names
+            .iter()
+            .chain(std::iter::once(&base.name))
+            .chain(suffix_names.iter())
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(" "),
+    };
+    // A curse saps its wearer's defense on top of whatever else the item does
+    let cursed = rng.roll_dice(1, 100) <= CURSE_CHANCE;
+    if cursed {
+        bonus.defense -= 1;
+    }
+
+    LootRoll {
+        base: base.name,
+        name,
+        rarity,
+        bonus,
+        cursed,
+    }
+}
+
+/// What a monster may leave behind when it dies: one of `drops`, by weight, `drop_chance` percent
+/// of the time
+struct LootTable {
+    monster: &'static str,
+    drop_chance: i32,
+    drops: &'static [(&'static str, i32)],
+}
+
+static LOOT_TABLES: [LootTable; 6] = [
+    LootTable {
+        monster: "Goblin",
+        drop_chance: 30,
+        drops: &[
+            ("Dagger", 3),
+            ("Short Sword", 1),
+            ("Health Potion", 2),
+            ("Sling Stones", 2),
+        ],
+    },
+    LootTable {
+        monster: "Orc",
+        drop_chance: 40,
+        drops: &[
+            ("Mace", 3),
+            ("Short Sword", 2),
+            ("Buckler", 1),
+            ("Health Potion", 2),
+        ],
+    },
+    LootTable {
+        monster: "Goblin Archer",
+        drop_chance: 40,
+        drops: &[("Shortbow", 2), ("Sling", 1), ("Arrows", 4)],
+    },
+    LootTable {
+        monster: "Goblin Shaman",
+        drop_chance: 50,
+        drops: &[("Health Potion", 3), ("Dagger", 1), ("Buckler", 1)],
+    },
+    LootTable {
+        monster: "Orc Warlord",
+        drop_chance: 100,
+        drops: &[("Mace", 2), ("Short Sword", 2), ("Buckler", 1)],
+    },
+    LootTable {
+        monster: "Bog Wraith",
+        drop_chance: 60,
+        drops: &[
+            ("Mace", 2),
+            ("Short Sword", 2),
+            ("Shortbow", 1),
+            ("Buckler", 2),
+        ],
+    },
+];
+
+/// Rolls the named monster's loot table, returning the spawn name of what it drops, if anything
+pub fn roll_drop(rng: &mut RandomNumberGenerator, monster: &str) -> Option<&'static str> {
+    let table = LOOT_TABLES.iter().find(|table| table.monster == monster)?;
+    if rng.roll_dice(1, 100) > table.drop_chance {
+        return None;
+    }
+    let total: i32 = table.drops.iter().map(|(_, weight)| weight).sum();
+    let mut roll = rng.roll_dice(1, total);
+    for (name, weight) in table.drops.iter() {
+        if roll <= *weight {
+            return Some(name);
+        }
+        roll -= weight;
+    }
+    None
+}
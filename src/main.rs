@@ -1,10 +1,16 @@
-use bracket_lib::terminal::{BError, BTerm, GameState, Point, RGB};
+use bracket_lib::random::RandomNumberGenerator;
+use bracket_lib::terminal::{BError, BTerm, GameState, Point, VirtualKeyCode, RGB};
 use specs::prelude::*;
 
 mod components;
 pub use components::*;
+mod tiles;
+pub use tiles::*;
 mod map;
 pub use map::*;
+mod camera;
+pub use camera::*;
+mod minimap;
 mod player;
 pub use player::*;
 mod rect;
@@ -17,15 +23,57 @@ mod visibility_system;
 pub use visibility_system::*;
 mod monster_ai_system;
 pub use monster_ai_system::*;
+mod ally_ai_system;
+pub use ally_ai_system::*;
 mod map_indexing_system;
 pub use map_indexing_system::*;
 mod melee_combat_system;
 pub use melee_combat_system::*;
+mod ranged_combat_system;
+pub use ranged_combat_system::*;
+mod inventory_system;
+pub use inventory_system::*;
+mod effects;
+pub use effects::*;
+mod spells;
+pub use spells::*;
+mod loot;
+pub use loot::*;
+mod identification;
+pub use identification::*;
+mod spell_system;
+pub use spell_system::*;
+mod pool_regen_system;
+pub use pool_regen_system::*;
+mod status_system;
+pub use status_system::*;
 mod damage_system;
+mod particle_system;
+mod shrine;
+pub use shrine::*;
+mod soul_system;
 pub use damage_system::*;
+pub use soul_system::*;
+mod noise_system;
+pub use noise_system::*;
+mod door_system;
+pub use door_system::*;
+mod lighting_system;
+pub use lighting_system::*;
+mod encumbrance_system;
+pub use encumbrance_system::*;
+mod bestiary;
+pub use bestiary::*;
 mod spawner;
 pub use spawner::*;
 pub mod map_builders;
+use map_builders::MapSnapshot;
+mod mapgen_harness;
+#[cfg(test)]
+mod tests;
+
+/// How long each map generation snapshot stays on screen, in milliseconds
+const MAPGEN_FRAME_TIME: f32 = 100.0;
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum RunState {
@@ -33,14 +81,26 @@ pub enum RunState {
     PreRun,
     PlayerTurn,
     MonsterTurn,
+    NextLevel,
+    MapGeneration,
+    ShowOverview,
+    ShowTargeting { range: i32, action: TargetingAction },
+    ShowInventory,
+    GameOver,
 }
 
 pub struct State {
     pub ecs: World,
+    show_mapgen: bool,
+    mapgen_next_state: Option<RunState>,
+    mapgen_history: Vec<MapSnapshot>,
+    mapgen_index: usize,
+    mapgen_timer: f32,
 }
 impl GameState for State {
     fn tick(&mut self, ctx: &mut BTerm) {
         ctx.cls();
+        particle_system::cull_dead_particles(&mut self.ecs, ctx.frame_time_ms);
         let mut newrunstate;
         {
             let runstate = self.ecs.fetch::<RunState>();
@@ -60,118 +120,570 @@ impl GameState for State {
                 newrunstate = RunState::MonsterTurn;
             }
             RunState::MonsterTurn => {
-                self.run_systems();
-                newrunstate = RunState::AwaitingInput;
+                newrunstate = self.monster_turn();
+            }
+            RunState::NextLevel => {
+                self.goto_next_level();
+                newrunstate = self.play_mapgen_then(RunState::PreRun);
+            }
+            RunState::MapGeneration => {
+                // Any key skips the rest of the playback
+                if ctx.key.is_some() {
+                    self.mapgen_index = self.mapgen_history.len();
+                }
+
+                if self.mapgen_index < self.mapgen_history.len() {
+                    self.draw_mapgen_frame(ctx);
+                    self.mapgen_timer += ctx.frame_time_ms;
+                    if self.mapgen_timer > MAPGEN_FRAME_TIME {
+                        self.mapgen_timer = 0.0;
+                        self.mapgen_index += 1;
+                    }
+                } else {
+                    newrunstate = self.mapgen_next_state.unwrap_or(RunState::PreRun);
+                }
             }
+            RunState::ShowOverview => {
+                if let Some(VirtualKeyCode::M | VirtualKeyCode::Escape) = ctx.key {
+                    newrunstate = RunState::AwaitingInput;
+                }
+            }
+            RunState::ShowTargeting { range, action } => {
+                match gui::ranged_target(&self.ecs, ctx, range, action) {
+                    gui::TargetingResult::Cancel => newrunstate = RunState::AwaitingInput,
+                    gui::TargetingResult::NoResponse => {}
+                    gui::TargetingResult::Selected(target) => {
+                        self.act_on_target(action, target);
+                        newrunstate = RunState::PlayerTurn;
+                    }
+                }
+            }
+            RunState::GameOver => {
+                if ctx.key.is_some() {
+                    ctx.quit();
+                }
+            }
+            RunState::ShowInventory => match gui::inventory_input(&self.ecs, ctx) {
+                gui::ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
+                gui::ItemMenuResult::NoResponse => {}
+                gui::ItemMenuResult::Dropped(item) => {
+                    let player_entity = *self.ecs.fetch::<Entity>();
+                    self.ecs
+                        .write_storage::<WantsToDropItem>()
+                        .insert(player_entity, WantsToDropItem { item })
+                        .expect("Unable to insert item drop");
+                    newrunstate = RunState::PlayerTurn;
+                }
+                gui::ItemMenuResult::Selected(item) => {
+                    // Wands need aiming before they can be zapped
+                    let wand_spell = self.ecs.read_storage::<Wand>().get(item).map(|w| w.spell);
+                    match wand_spell {
+                        Some(spell) => {
+                            newrunstate = RunState::ShowTargeting {
+                                range: spell.info().range,
+                                action: TargetingAction::Zap { wand: item, spell },
+                            };
+                        }
+                        None => {
+                            let player_entity = *self.ecs.fetch::<Entity>();
+                            self.ecs
+                                .write_storage::<WantsToUseItem>()
+                                .insert(player_entity, WantsToUseItem { item })
+                                .expect("Unable to insert item use");
+                            newrunstate = RunState::PlayerTurn;
+                        }
+                    }
+                }
+            },
         }
 
+        damage_system::delete_the_dead(&mut self.ecs);
+        if newrunstate != RunState::GameOver && self.player_is_dead() {
+            newrunstate = self.player_died();
+        }
         {
             let mut runwriter = self.ecs.write_resource::<RunState>();
             *runwriter = newrunstate;
         }
-        damage_system::delete_the_dead(&mut self.ecs);
 
-        draw_map(&self.ecs, ctx);
-
-        let positions = self.ecs.read_storage::<Position>();
-        let renderables = self.ecs.read_storage::<Renderable>();
-        let map = self.ecs.fetch::<Map>();
-
-        for (pos, render) in (&positions, &renderables).join() {
-            let idx = map.xy_idx(pos.x, pos.y);
-            if map.visible_tiles[idx] {
-                ctx.set(pos.x, pos.y, render.fg, render.bg, render.glyph);
-            }
+        if newrunstate == RunState::MapGeneration {
+            return;
+        }
+        if newrunstate == RunState::GameOver {
+            gui::draw_game_over(ctx);
+            return;
+        }
+        if newrunstate == RunState::ShowOverview {
+            minimap::draw_overview(&self.ecs, ctx);
+            return;
         }
 
+        camera::render_camera(&self.ecs, ctx);
+        minimap::draw_minimap(&self.ecs, ctx);
         gui::draw_ui(&self.ecs, ctx);
+        match newrunstate {
+            RunState::ShowTargeting { range, action } => {
+                gui::draw_targeting(&self.ecs, ctx, range, action)
+            }
+            RunState::ShowInventory => gui::draw_inventory(&self.ecs, ctx),
+            _ => {}
+        }
     }
 }
 
 impl State {
+    /// Shoots at whatever stands on the chosen tile, or casts the spell there
+    fn act_on_target(&mut self, action: TargetingAction, target: Point) {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        match action {
+            TargetingAction::Shoot => {
+                let victim = {
+                    let entities = self.ecs.entities();
+                    let positions = self.ecs.read_storage::<Position>();
+                    let combat_stats = self.ecs.read_storage::<CombatStats>();
+                    (&entities, &positions, &combat_stats)
+                        .join()
+                        .find(|(_, pos, _)| pos.x == target.x && pos.y == target.y)
+                        .map(|(entity, _, _)| entity)
+                };
+                if let Some(victim) = victim {
+                    self.ecs
+                        .write_storage::<WantsToShoot>()
+                        .insert(player_entity, WantsToShoot { target: victim })
+                        .expect("Unable to insert shot");
+                }
+            }
+            TargetingAction::Cast(spell) => {
+                self.ecs
+                    .write_storage::<WantsToCastSpell>()
+                    .insert(
+                        player_entity,
+                        WantsToCastSpell {
+                            spell,
+                            target,
+                            wand: None,
+                        },
+                    )
+                    .expect("Unable to insert spell");
+            }
+            TargetingAction::Zap { wand, spell } => {
+                self.ecs
+                    .write_storage::<WantsToCastSpell>()
+                    .insert(
+                        player_entity,
+                        WantsToCastSpell {
+                            spell,
+                            target,
+                            wand: Some(wand),
+                        },
+                    )
+                    .expect("Unable to insert spell");
+            }
+        }
+    }
+
     fn run_systems(&mut self) {
+        let mut lighting = LightingSystem {};
         let mut vis = VisibilitySystem {};
         let mut mob = MonsterAI {};
+        let mut allies = AllyAI {};
+        let mut doors = DoorSystem {};
+        let mut pickup = ItemCollectionSystem {};
+        let mut drop_items = ItemDropSystem {};
+        let mut item_use = ItemUseSystem {};
+        let mut souls = SoulSystem {};
         let mut mapindex = MapIndexingSystem {};
         let mut melee = MeleeCombatSystem {};
+        let mut ranged = RangedCombatSystem {};
+        let mut spells = SpellCastingSystem {};
+        let mut status = StatusSystem {};
+        let mut effects = EffectsSystem {};
+        let mut pool_regen = PoolRegenSystem {};
         let mut damagesystem = DamageSystem {};
+        let mut noise = NoiseSystem {};
+        lighting.run_now(&self.ecs);
         vis.run_now(&self.ecs);
         mob.run_now(&self.ecs);
+        allies.run_now(&self.ecs);
+        doors.run_now(&self.ecs);
+        pickup.run_now(&self.ecs);
+        drop_items.run_now(&self.ecs);
+        item_use.run_now(&self.ecs);
+        souls.run_now(&self.ecs);
         mapindex.run_now(&self.ecs);
         melee.run_now(&self.ecs);
+        ranged.run_now(&self.ecs);
+        spells.run_now(&self.ecs);
+        status.run_now(&self.ecs);
+        effects.run_now(&self.ecs);
         damagesystem.run_now(&self.ecs);
+        noise.run_now(&self.ecs);
+        pool_regen.run_now(&self.ecs);
         self.ecs.maintain();
+
+        // Weighed once used up items are gone for good
+        let mut encumbrance = EncumbranceSystem {};
+        encumbrance.run_now(&self.ecs);
+    }
+
+    /// Builds a fresh level, spawns its monsters and installs the new map, returning where the
+    /// player should start
+    fn generate_world_map(&mut self, new_depth: i32) -> Position {
+        let mut rng = RandomNumberGenerator::new();
+        let (width, height) = map_builders::level_size(new_depth);
+        let mut builder = map_builders::random_builder(new_depth, width, height, &mut rng);
+        builder.build_map(&mut rng);
+        self.mapgen_history = std::mem::take(&mut builder.build_data.history);
+        self.mapgen_index = 0;
+        self.mapgen_timer = 0.0;
+        self.ecs.insert(builder.build_data.map.clone());
+        builder.spawn_entities(&mut self.ecs);
+        shrine::record_roster(&mut self.ecs);
+
+        let start = builder
+            .build_data
+            .starting_position
+            .clone()
+            .expect("The map builder chain never chose a starting position");
+        // Every floor is a fresh checkpoint, since there is no going back to the shrine on the last
+        self.ecs.insert(RespawnPoint { pos: start.clone() });
+        start
+    }
+
+    /// Lets the monsters act, returning whose turn it is next
+    fn monster_turn(&mut self) -> RunState {
+        self.run_systems();
+        // A burdened step takes long enough for the monsters to go twice
+        if player::take_lagging_turn(&mut self.ecs) {
+            RunState::MonsterTurn
+        } else {
+            RunState::AwaitingInput
+        }
+    }
+
+    fn player_is_dead(&self) -> bool {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        self.ecs
+            .read_storage::<CombatStats>()
+            .get(player_entity)
+            .is_some_and(|stats| stats.hp < 1)
+    }
+
+    /// Ends the run, or in shrine mode sends the player and their allies back to the last shrine
+    fn player_died(&mut self) -> RunState {
+        let mode = *self.ecs.fetch::<GameMode>();
+        match mode {
+            GameMode::Permadeath => RunState::GameOver,
+            GameMode::Shrines => {
+                let respawn = shrine::respawn_player(&mut self.ecs);
+                self.place_party(&respawn);
+                RunState::PreRun
+            }
+        }
+    }
+
+    /// Plays back how the current level was built before carrying on to `next`, if the visualizer
+    /// is turned on
+    fn play_mapgen_then(&mut self, next: RunState) -> RunState {
+        if self.show_mapgen {
+            self.mapgen_next_state = Some(next);
+            RunState::MapGeneration
+        } else {
+            next
+        }
+    }
+
+    fn draw_mapgen_frame(&self, ctx: &mut BTerm) {
+        let snapshot = &self.mapgen_history[self.mapgen_index];
+        let map = &snapshot.map;
+        let camera = Camera::new(map, Point::new(map.width / 2, map.height / 2));
+        render_map(map, &camera, ctx);
+        ctx.print_color(
+            1,
+            VIEW_HEIGHT + 1,
+            RGB::named(bracket_lib::terminal::YELLOW),
+            RGB::named(bracket_lib::terminal::BLACK),
+            format!(
+                "Depth {}: {} (step {} of {})",
+                snapshot.map.depth,
+                snapshot.builder,
+                self.mapgen_index + 1,
+                self.mapgen_history.len()
+            ),
+        );
+        ctx.print_color(
+            1,
+            VIEW_HEIGHT + 3,
+            RGB::named(bracket_lib::terminal::GREY),
+            RGB::named(bracket_lib::terminal::BLACK),
+            "Press any key to skip",
+        );
+    }
+
+    /// Everything except the player, their allies and what they carry is left behind on a level
+    /// change
+    fn entities_to_remove_on_level_change(&mut self) -> Vec<Entity> {
+        let entities = self.ecs.entities();
+        let players = self.ecs.read_storage::<Player>();
+        let allies = self.ecs.read_storage::<Ally>();
+        let backpack = self.ecs.read_storage::<InBackpack>();
+
+        (&entities, !&players, !&allies)
+            .join()
+            .map(|(entity, _player, _ally)| entity)
+            .filter(|entity| {
+                !backpack.get(*entity).is_some_and(|carried| {
+                    players.get(carried.owner).is_some() || allies.get(carried.owner).is_some()
+                })
+            })
+            .collect()
+    }
+
+    /// Moves the player to `start` and gathers their allies on the open tiles closest to them
+    fn place_party(&mut self, start: &Position) {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        {
+            let mut positions = self.ecs.write_storage::<Position>();
+            let allies = self.ecs.read_storage::<Ally>();
+            for (entity, pos) in (&self.ecs.entities(), &mut positions).join() {
+                if entity == player_entity || allies.get(entity).is_some() {
+                    *pos = start.clone();
+                }
+            }
+        }
+
+        // Index the map so the monsters on it are accounted for before placing anyone, and closed
+        // doors block the very first look around. This waits until the player is on the new level,
+        // since where they stood on the last one may be off the edge of this one.
+        let mut mapindex = MapIndexingSystem {};
+        mapindex.run_now(&self.ecs);
+
+        let map = self.ecs.fetch::<Map>();
+        let mut open_tiles = map.open_tiles_near(start.x, start.y).into_iter();
+        let mut positions = self.ecs.write_storage::<Position>();
+        let allies = self.ecs.read_storage::<Ally>();
+        let mut viewsheds = self.ecs.write_storage::<Viewshed>();
+        for (_ally, pos) in (&allies, &mut positions).join() {
+            if let Some(tile) = open_tiles.next() {
+                pos.x = tile.x;
+                pos.y = tile.y;
+            }
+        }
+        for viewshed in (&mut viewsheds).join() {
+            viewshed.dirty = true;
+        }
+
+        let mut player_pos = self.ecs.write_resource::<Point>();
+        *player_pos = Point::new(start.x, start.y);
+    }
+
+    fn goto_next_level(&mut self) {
+        let to_delete = self.entities_to_remove_on_level_change();
+        for target in to_delete {
+            self.ecs
+                .delete_entity(target)
+                .expect("Unable to delete entity");
+        }
+
+        // Any standing attack orders refer to monsters on the old level
+        for ally in (&mut self.ecs.write_storage::<Ally>()).join() {
+            if let AllyOrder::Attack(_) = ally.order {
+                ally.order = AllyOrder::Follow;
+            }
+        }
+
+        let current_depth = self.ecs.fetch::<Map>().depth;
+        let player_start = self.generate_world_map(current_depth + 1);
+        self.place_party(&player_start);
+
+        let mut log = self.ecs.fetch_mut::<GameLog>();
+        log.entries
+            .push("You descend deeper beneath the earth.".to_string());
+    }
+}
+
+/// Command line switches
+struct Options {
+    show_mapgen: bool,
+    dump_mapgen: bool,
+    seed: Option<u64>,
+    depth: i32,
+    mode: GameMode,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        show_mapgen: false,
+        dump_mapgen: false,
+        seed: None,
+        depth: 1,
+        mode: GameMode::Permadeath,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--show-mapgen" => options.show_mapgen = true,
+            "--dump-mapgen" => options.dump_mapgen = true,
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a number")?;
+                let seed = value
+                    .parse()
+                    .map_err(|_| format!("--seed needs a number, not '{}'", value))?;
+                options.seed = Some(seed);
+            }
+            "--depth" => {
+                let value = args.next().ok_or("--depth needs a number")?;
+                options.depth = value
+                    .parse()
+                    .map_err(|_| format!("--depth needs a number, not '{}'", value))?;
+            }
+            "--mode" => {
+                options.mode = match args.next().as_deref() {
+                    Some("permadeath") => GameMode::Permadeath,
+                    Some("shrines") => GameMode::Shrines,
+                    _ => return Err("--mode needs to be permadeath or shrines".to_string()),
+                };
+            }
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        }
     }
+
+    Ok(options)
+}
+
+/// Registers every component with the world
+fn register_components(ecs: &mut World) {
+    ecs.register::<Position>();
+    ecs.register::<Renderable>();
+    ecs.register::<Player>();
+    ecs.register::<Viewshed>();
+    ecs.register::<Monster>();
+    ecs.register::<Name>();
+    ecs.register::<BlocksTile>();
+    ecs.register::<Item>();
+    ecs.register::<Consumable>();
+    ecs.register::<ProvidesHealing>();
+    ecs.register::<ProvidesMana>();
+    ecs.register::<TeleportsUser>();
+    ecs.register::<IdentifiesItems>();
+    ecs.register::<RemovesCurses>();
+    ecs.register::<RepairsItems>();
+    ecs.register::<Cursed>();
+    ecs.register::<Wand>();
+    ecs.register::<Durability>();
+    ecs.register::<TeachesSpell>();
+    ecs.register::<WantsToUseItem>();
+    ecs.register::<Pools>();
+    ecs.register::<KnownSpells>();
+    ecs.register::<WantsToCastSpell>();
+    ecs.register::<StatusEffects>();
+    ecs.register::<ParticleLifetime>();
+    ecs.register::<InBackpack>();
+    ecs.register::<WantsToPickupItem>();
+    ecs.register::<WantsToDropItem>();
+    ecs.register::<Attributes>();
+    ecs.register::<Encumbrance>();
+    ecs.register::<Equippable>();
+    ecs.register::<Equipped>();
+    ecs.register::<Shield>();
+    ecs.register::<MeleeWeapon>();
+    ecs.register::<ItemRarity>();
+    ecs.register::<EquipmentBonus>();
+    ecs.register::<RangedWeapon>();
+    ecs.register::<Ammunition>();
+    ecs.register::<WantsToShoot>();
+    ecs.register::<RangedAI>();
+    ecs.register::<LightSource>();
+    ecs.register::<BlocksVisibility>();
+    ecs.register::<Door>();
+    ecs.register::<WantsToOpenDoor>();
+    ecs.register::<WantsToCloseDoor>();
+    ecs.register::<CombatStats>();
+    ecs.register::<SufferDamage>();
+    ecs.register::<Resistances>();
+    ecs.register::<MeleeAttack>();
+    ecs.register::<WantsToMelee>();
+    ecs.register::<Ally>();
+    ecs.register::<Summoned>();
+    ecs.register::<Boss>();
+    ecs.register::<Shrine>();
+    ecs.register::<Souls>();
+    ecs.register::<SoulCache>();
+    ecs.register::<Awareness>();
+    ecs.register::<Stealth>();
+    ecs.register::<EmitsNoise>();
 }
 
 fn main() -> BError {
+    if std::env::args().nth(1).as_deref() == Some("mapgen") {
+        std::process::exit(mapgen_harness::run_batch(std::env::args().skip(2)));
+    }
+
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!(
+                "Usage: portals_of_balor [--show-mapgen] [--mode permadeath|shrines] [--seed N]"
+            );
+            eprintln!("       portals_of_balor --dump-mapgen [--seed N] [--depth N]");
+            eprintln!("       portals_of_balor mapgen --help for generating levels in bulk");
+            eprintln!("In shrines mode, death wakes you at the last shrine you rested at on the");
+            eprintln!(
+                "current floor, or at the floor's entrance until you rest at one of its own."
+            );
+            std::process::exit(2);
+        }
+    };
+    if options.dump_mapgen {
+        mapgen_harness::dump_snapshots(options.seed, options.depth);
+        return Ok(());
+    }
+
     use bracket_lib::terminal::BTermBuilder;
     let context: BTerm = BTermBuilder::simple(80, 50)
         .expect("Could not construct BTermBuilder")
         .with_title("Portals of Balor")
         .with_tile_dimensions(16, 16)
         .build()?;
-    let mut gs: State = State { ecs: World::new() };
-
-    // Register components to the world
-    gs.ecs.register::<Position>();
-    gs.ecs.register::<Renderable>();
-    gs.ecs.register::<Player>();
-    gs.ecs.register::<Viewshed>();
-    gs.ecs.register::<Monster>();
-    gs.ecs.register::<Name>();
-    gs.ecs.register::<BlocksTile>();
-    gs.ecs.register::<CombatStats>();
-    gs.ecs.register::<SufferDamage>();
-    gs.ecs.register::<WantsToMelee>();
+    let mut gs: State = State {
+        ecs: World::new(),
+        show_mapgen: options.show_mapgen,
+        mapgen_next_state: None,
+        mapgen_history: Vec::new(),
+        mapgen_index: 0,
+        mapgen_timer: 0.0,
+    };
+
+    register_components(&mut gs.ecs);
 
     // Add shared data for the world
-    let mut builder = map_builders::random_builder();
-    builder.build_map();
-    let map = builder.get_map();
-    let player_start = builder.get_starting_position();
-
-    // Create the player entity
-    let player_entity = gs
-        .ecs
-        .create_entity()
-        .with(Position {
-            x: player_start.x,
-            y: player_start.y,
-        })
-        .with(Renderable {
-            glyph: 0x40,
-            fg: RGB::named(bracket_lib::terminal::YELLOW),
-            bg: RGB::named(bracket_lib::terminal::BLACK),
-        })
-        .with(Player {})
-        .with(Name {
-            name: "Player".to_string(),
-        })
-        .with(Viewshed {
-            visible_tiles: Vec::new(),
-            range: 8,
-            dirty: true,
-        })
-        .with(CombatStats {
-            max_hp: 30,
-            hp: 30,
-            defense: 2,
-            power: 5,
-        })
-        .build();
+    let player_start = gs.generate_world_map(1);
+
+    let player_entity = spawner::player(&mut gs.ecs, player_start.x, player_start.y);
 
     gs.ecs.insert(player_entity);
+    gs.ecs.insert(EffectQueue::default());
+    gs.ecs.insert(options.mode);
+    // The seed decides what unidentified potions and scrolls look like this run
+    let seed = options
+        .seed
+        .unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    gs.ecs.insert(IdentificationRegistry::new(seed));
+    gs.ecs.insert(Point::new(player_start.x, player_start.y));
+    spawner::starting_kit(&mut gs.ecs, player_entity);
 
-    // Create some monster entities
-    builder.spawn_entities(&mut gs.ecs);
+    // The player's hound starts at their side
+    spawner::hound(&mut gs.ecs, player_start.x, player_start.y);
+    gs.place_party(&player_start);
 
-    gs.ecs.insert(Point::new(player_start.x, player_start.y));
-    gs.ecs.insert(RunState::PreRun);
+    let first_state = gs.play_mapgen_then(RunState::PreRun);
+    gs.ecs.insert(first_state);
     gs.ecs.insert(GameLog {
         entries: vec!["Welcome to Rusty Roguelike".to_string()],
     });
-    gs.ecs.insert(map);
 
+    loot::register_rarity_colors();
     bracket_lib::terminal::main_loop(context, gs)
 }
@@ -1,18 +1,16 @@
-use crate::MAP_COUNT;
+use std::collections::HashSet;
+
+use crate::TileType;
 
-use super::{MAP_HEIGHT, MAP_WIDTH};
 use bracket_lib::{
-    pathfinding::{Algorithm2D, BaseMap},
-    prelude::Point,
-    terminal::{BTerm, RGB},
+    pathfinding::{a_star_search, Algorithm2D, BaseMap},
+    prelude::{line2d, LineAlg, Point},
+    terminal::{DistanceAlg, RGB},
 };
-use specs::{Entity, World};
+use specs::Entity;
 
-#[derive(PartialEq, Copy, Clone)]
-pub enum TileType {
-    Wall,
-    Floor,
-}
+/// Tiles with less light than this, in their brightest color channel, are too dark to see
+const LIGHT_THRESHOLD: f32 = 0.15;
 
 #[derive(Clone)]
 pub struct Map {
@@ -23,23 +21,38 @@ pub struct Map {
     pub visible_tiles: Vec<bool>,
     pub blocked: Vec<bool>,
     pub tile_content: Vec<Vec<Entity>>,
+    /// Tiles that something standing on them stops sight through, such as a closed door
+    pub view_blocked: HashSet<usize>,
+    /// Tiles with a closed door in them; blocked, but creatures can still path through and open it
+    pub closed_doors: HashSet<usize>,
+    /// The light falling on each tile: the ambient light plus every light source that reaches it
+    pub light: Vec<RGB>,
+    /// Light that reaches every tile; dark levels have none and rely on light sources
+    pub ambient_light: RGB,
+    pub depth: i32,
 }
 
 impl Map {
     pub fn xy_idx(&self, x: i32, y: i32) -> usize {
-        (y as usize * MAP_WIDTH as usize) + x as usize
+        (y as usize * self.width as usize) + x as usize
     }
 
-    /// Generates an empty map, consisting entirely of solid walls
-    pub fn new() -> Map {
+    /// Generates an empty map of the given size, consisting entirely of solid walls
+    pub fn new(width: i32, height: i32, depth: i32) -> Map {
+        let tile_count = (width * height) as usize;
         Map {
-            tiles: vec![TileType::Wall; MAP_COUNT],
-            width: MAP_WIDTH,
-            height: MAP_HEIGHT,
-            revealed_tiles: vec![false; MAP_COUNT],
-            visible_tiles: vec![false; MAP_COUNT],
-            blocked: vec![false; MAP_COUNT],
-            tile_content: vec![Vec::new(); MAP_COUNT],
+            tiles: vec![TileType::Wall; tile_count],
+            width,
+            height,
+            revealed_tiles: vec![false; tile_count],
+            visible_tiles: vec![false; tile_count],
+            blocked: vec![false; tile_count],
+            tile_content: vec![Vec::new(); tile_count],
+            view_blocked: HashSet::new(),
+            closed_doors: HashSet::new(),
+            light: vec![RGB::from_f32(1.0, 1.0, 1.0); tile_count],
+            ambient_light: RGB::from_f32(1.0, 1.0, 1.0),
+            depth,
         }
     }
 
@@ -48,12 +61,12 @@ impl Map {
             return false;
         }
         let idx = self.xy_idx(x, y);
-        !self.blocked[idx]
+        !self.blocked[idx] || self.closed_doors.contains(&idx)
     }
 
     pub fn populate_blocked(&mut self) {
         for (i, tile) in self.tiles.iter_mut().enumerate() {
-            self.blocked[i] = *tile == TileType::Wall;
+            self.blocked[i] = !tile.walkable();
         }
     }
 
@@ -62,6 +75,85 @@ impl Map {
             content.clear();
         }
     }
+
+    /// Returns the first step of an A* path between two tiles. The destination is treated as
+    /// open while searching, so a creature can path up to whatever is standing there.
+    pub fn next_step_towards(&mut self, start_idx: usize, end_idx: usize) -> Option<usize> {
+        let end_blocked = self.blocked[end_idx];
+        self.blocked[end_idx] = false;
+        let path = a_star_search(start_idx, end_idx, &*self);
+        self.blocked[end_idx] = end_blocked;
+
+        if path.success && path.steps.len() > 1 {
+            Some(path.steps[1])
+        } else {
+            None
+        }
+    }
+
+    /// True if there is enough light on the tile to see it by
+    pub fn is_lit(&self, idx: usize) -> bool {
+        let light = self.light[idx];
+        f32::max(light.r, f32::max(light.g, light.b)) >= LIGHT_THRESHOLD
+    }
+
+    /// True if the level has no ambient light to see by
+    pub fn is_dark(&self) -> bool {
+        self.ambient_light.r < LIGHT_THRESHOLD
+            && self.ambient_light.g < LIGHT_THRESHOLD
+            && self.ambient_light.b < LIGHT_THRESHOLD
+    }
+
+    /// True if any of the four tiles next to (x, y) hasn't been seen yet
+    pub fn borders_unexplored(&self, x: i32, y: i32) -> bool {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)].iter().any(|(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            nx >= 0
+                && nx < self.width
+                && ny >= 0
+                && ny < self.height
+                && !self.revealed_tiles[self.xy_idx(nx, ny)]
+        })
+    }
+
+    /// True if nothing that blocks sight lies along the Bresenham line from `from` to `to`,
+    /// whoever might be standing on it
+    pub fn has_clear_path(&self, from: Point, to: Point) -> bool {
+        line2d(LineAlg::Bresenham, from, to)
+            .iter()
+            .filter(|point| **point != from && **point != to)
+            .all(|point| !self.is_opaque(self.xy_idx(point.x, point.y)))
+    }
+
+    /// True if a missile could fly straight from `from` to `to`: along the Bresenham line
+    /// between them, nothing blocks sight and nobody is standing in the way. Terrain that only
+    /// stops walking, like deep water, can be shot across.
+    pub fn has_line_of_fire(&self, from: Point, to: Point) -> bool {
+        self.has_clear_path(from, to)
+            && line2d(LineAlg::Bresenham, from, to)
+                .iter()
+                .filter(|point| **point != from && **point != to)
+                .all(|point| {
+                    let idx = self.xy_idx(point.x, point.y);
+                    !(self.blocked[idx] && self.tiles[idx].walkable())
+                })
+    }
+
+    /// Lists every open, unblocked tile other than (x, y), nearest first
+    pub fn open_tiles_near(&self, x: i32, y: i32) -> Vec<Point> {
+        let origin = Point::new(x, y);
+        let mut open_tiles: Vec<Point> = (0..self.tiles.len())
+            .filter(|idx| self.tiles[*idx].walkable() && !self.blocked[*idx])
+            .map(|idx| Point::new(idx as i32 % self.width, idx as i32 / self.width))
+            .filter(|point| *point != origin)
+            .collect();
+        open_tiles.sort_by(|a, b| {
+            let distance_a = DistanceAlg::Pythagoras.distance2d(origin, *a);
+            let distance_b = DistanceAlg::Pythagoras.distance2d(origin, *b);
+            distance_a.partial_cmp(&distance_b).unwrap()
+        });
+        open_tiles
+    }
 }
 
 impl Algorithm2D for Map {
@@ -72,7 +164,7 @@ impl Algorithm2D for Map {
 
 impl BaseMap for Map {
     fn is_opaque(&self, idx: usize) -> bool {
-        self.tiles[idx] == TileType::Wall
+        self.tiles[idx].opaque() || self.view_blocked.contains(&idx)
     }
 
     fn get_available_exits(
@@ -82,32 +174,23 @@ impl BaseMap for Map {
         let mut exits = bracket_lib::prelude::SmallVec::new();
         let x = idx as i32 % self.width;
         let y = idx as i32 / self.width;
-        let w = self.width as usize;
 
-        if self.is_exit_valid(x - 1, y) {
-            exits.push((idx - 1, 1.0));
-        }
-        if self.is_exit_valid(x + 1, y) {
-            exits.push((idx + 1, 1.0));
-        }
-        if self.is_exit_valid(x, y - 1) {
-            exits.push((idx - w, 1.0));
-        }
-        if self.is_exit_valid(x, y + 1) {
-            exits.push((idx + w, 1.0));
-        }
-        if self.is_exit_valid(x - 1, y - 1) {
-            exits.push((idx - 1 - w, 1.0));
-        }
-        if self.is_exit_valid(x - 1, y + 1) {
-            exits.push((idx - 1 + w, 1.0));
-        }
-        if self.is_exit_valid(x + 1, y - 1) {
-            exits.push((idx + 1 - w, 1.0));
-        }
-        if self.is_exit_valid(x + 1, y + 1) {
-            exits.push((idx + 1 + w, 1.0));
-        }
+        // Stepping diagonally covers more ground; either way, rough terrain costs extra
+        let mut add_exit = |dx: i32, dy: i32| {
+            if self.is_exit_valid(x + dx, y + dy) {
+                let exit_idx = self.xy_idx(x + dx, y + dy);
+                let distance = if dx != 0 && dy != 0 { 1.45 } else { 1.0 };
+                exits.push((exit_idx, distance * self.tiles[exit_idx].cost()));
+            }
+        };
+        add_exit(-1, 0);
+        add_exit(1, 0);
+        add_exit(0, -1);
+        add_exit(0, 1);
+        add_exit(-1, -1);
+        add_exit(-1, 1);
+        add_exit(1, -1);
+        add_exit(1, 1);
 
         exits
     }
@@ -116,39 +199,6 @@ impl BaseMap for Map {
         let w = self.width as usize;
         let p1 = Point::new(idx1 % w, idx1 / w);
         let p2 = Point::new(idx2 % w, idx2 / w);
-        bracket_lib::terminal::DistanceAlg::Pythagoras.distance2d(p1, p2)
-    }
-}
-
-pub fn draw_map(ecs: &World, ctx: &mut BTerm) {
-    let map = ecs.fetch::<Map>();
-
-    let mut y = 0;
-    let mut x = 0;
-    for (idx, tile) in map.tiles.iter().enumerate() {
-        if map.revealed_tiles[idx] {
-            let glyph;
-            let mut fg;
-            match tile {
-                TileType::Floor => {
-                    fg = RGB::from_f32(0.5, 0.5, 0.5);
-                    glyph = 0x2E;
-                }
-                TileType::Wall => {
-                    fg = RGB::from_f32(0.0, 1.0, 0.0);
-                    glyph = 0x23
-                }
-            }
-            if !map.visible_tiles[idx] {
-                fg = fg.to_greyscale();
-            }
-            ctx.set(x, y, fg, RGB::from_f32(0., 0., 0.), glyph)
-        }
-
-        x += 1;
-        if x >= MAP_WIDTH {
-            x = 0;
-            y += 1;
-        }
+        DistanceAlg::Pythagoras.distance2d(p1, p2)
     }
 }
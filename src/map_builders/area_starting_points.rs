@@ -0,0 +1,73 @@
+use bracket_lib::{
+    random::RandomNumberGenerator,
+    terminal::{DistanceAlg, Point},
+};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::{Position, TileType};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum XStart {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum YStart {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Starts the player on whichever floor tile is closest to a chosen side or corner of the map
+pub struct AreaStartingPosition {
+    x: XStart,
+    y: YStart,
+}
+
+impl MetaMapBuilder for AreaStartingPosition {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl AreaStartingPosition {
+    pub fn new(x: XStart, y: YStart) -> Box<AreaStartingPosition> {
+        Box::new(AreaStartingPosition { x, y })
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        let seed_x = match self.x {
+            XStart::Left => 1,
+            XStart::Center => build_data.map.width / 2,
+            XStart::Right => build_data.map.width - 2,
+        };
+        let seed_y = match self.y {
+            YStart::Top => 1,
+            YStart::Center => build_data.map.height / 2,
+            YStart::Bottom => build_data.map.height - 2,
+        };
+        let seed = Point::new(seed_x, seed_y);
+
+        let width = build_data.map.width;
+        let closest = build_data
+            .map
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| **tile == TileType::Floor)
+            .map(|(idx, _)| {
+                let tile = Point::new(idx as i32 % width, idx as i32 / width);
+                (idx, DistanceAlg::PythagorasSquared.distance2d(seed, tile))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(idx, _)| idx)
+            .expect("No floor to start the player on");
+
+        build_data.starting_position = Some(Position {
+            x: closest as i32 % width,
+            y: closest as i32 / width,
+        });
+    }
+}
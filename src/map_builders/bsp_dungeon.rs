@@ -1,87 +1,57 @@
 use bracket_lib::random::RandomNumberGenerator;
 
-use crate::{spawner, Map, Position, Rect, TileType};
+use crate::{Map, Rect, TileType};
 
-use super::{common::apply_room_to_map, MapBuilder};
+use super::{common::apply_room_to_map, BuilderMap, InitialMapBuilder};
 
 pub const MAX_ROOM_COUNT: i32 = 240;
 
 pub struct BspDungeonBuilder {
-    map: Map,
-    starting_position: Position,
-    rooms: Vec<Rect>,
     rects: Vec<Rect>,
 }
 
-impl MapBuilder for BspDungeonBuilder {
-    fn build_map(&mut self) {
-        let mut rng = RandomNumberGenerator::new();
+impl InitialMapBuilder for BspDungeonBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl BspDungeonBuilder {
+    pub fn new() -> Box<BspDungeonBuilder> {
+        Box::new(BspDungeonBuilder { rects: Vec::new() })
+    }
+
+    /// Splits the map into ever smaller rectangles and drops a room into the ones with space;
+    /// corridors are left to a meta builder
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut rooms: Vec<Rect> = Vec::new();
 
         self.rects.clear();
-        self.rects
-            .push(Rect::new(2, 2, self.map.width - 5, self.map.height - 5));
+        self.rects.push(Rect::new(
+            2,
+            2,
+            build_data.map.width - 5,
+            build_data.map.height - 5,
+        ));
         let first_room = self.rects[0];
         self.add_subrects(first_room);
 
         let mut n_rooms = 0;
         while n_rooms < MAX_ROOM_COUNT {
-            let rect = self.get_random_rect(&mut rng);
-            let candidate = self.get_random_sub_rect(rect, &mut rng);
+            let rect = self.get_random_rect(rng);
+            let candidate = self.get_random_sub_rect(rect, rng);
 
-            if self.is_possible(candidate) {
-                apply_room_to_map(&mut self.map, &candidate);
-                self.rooms.push(candidate);
+            if self.is_possible(&build_data.map, candidate) {
+                apply_room_to_map(&mut build_data.map, &candidate);
+                build_data.take_snapshot();
+                rooms.push(candidate);
                 self.add_subrects(rect);
             }
 
             n_rooms += 1;
         }
 
-        // Add cooridors to the rooms
-        for i in 0..self.rooms.len() - 1 {
-            let room = self.rooms[i];
-            let next_room = self.rooms[i + 1];
-            let start_x = room.x1 + rng.roll_dice(1, (i32::abs(room.x1 - room.x2)) - 1);
-            let start_y = room.y1 + rng.roll_dice(1, (i32::abs(room.y1 - room.y2)) - 1);
-            let end_x =
-                next_room.x1 + (rng.roll_dice(1, i32::abs(next_room.x1 - next_room.x2)) - 1);
-            let end_y =
-                next_room.y1 + (rng.roll_dice(1, i32::abs(next_room.y1 - next_room.y2)) - 1);
-            self.draw_corridor(start_x, start_y, end_x, end_y);
-        }
-
-        let start = self.rooms[0].center();
-        self.starting_position = Position {
-            x: start.0,
-            y: start.1,
-        };
-    }
-
-    fn spawn_entities(&mut self, ecs: &mut specs::World) {
-        ecs.insert(self.get_map());
-        for room in self.rooms.iter().skip(1) {
-            spawner::spawn_room(ecs, room);
-        }
-    }
-
-    fn get_map(&self) -> crate::Map {
-        self.map.clone()
-    }
-
-    fn get_starting_position(&self) -> crate::Position {
-        self.starting_position.clone()
-    }
-}
-
-impl BspDungeonBuilder {
-    pub fn new() -> BspDungeonBuilder {
-        println!("Using BSP Dungeon Builder");
-        BspDungeonBuilder {
-            map: Map::new(),
-            starting_position: Position { x: 0, y: 0 },
-            rooms: Vec::new(),
-            rects: Vec::new(),
-        }
+        build_data.rooms = Some(rooms);
     }
 
     fn add_subrects(&mut self, rect: Rect) {
@@ -137,7 +107,7 @@ impl BspDungeonBuilder {
         result
     }
 
-    fn is_possible(&self, rect: Rect) -> bool {
+    fn is_possible(&self, map: &Map, rect: Rect) -> bool {
         let mut expanded = rect;
         expanded.x1 -= 2;
         expanded.x2 += 2;
@@ -148,16 +118,16 @@ impl BspDungeonBuilder {
 
         for y in expanded.y1..=expanded.y2 {
             for x in expanded.x1..=expanded.x2 {
-                if x > self.map.width - 2 || x < 1 {
+                if x > map.width - 2 || x < 1 {
                     can_build = false;
                 }
-                if y > self.map.height - 2 || y < 1 {
+                if y > map.height - 2 || y < 1 {
                     can_build = false;
                 }
 
                 if can_build {
-                    let idx = self.map.xy_idx(x, y);
-                    if self.map.tiles[idx] != TileType::Wall {
+                    let idx = map.xy_idx(x, y);
+                    if map.tiles[idx] != TileType::Wall {
                         can_build = false;
                     }
                 }
@@ -166,24 +136,4 @@ impl BspDungeonBuilder {
 
         can_build
     }
-
-    fn draw_corridor(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
-        let mut x = x1;
-        let mut y = y1;
-
-        while x != x2 || y != y2 {
-            if x < x2 {
-                x += 1;
-            } else if x > x2 {
-                x -= 1;
-            } else if y < y2 {
-                y += 1;
-            } else if y > y2 {
-                y -= 1;
-            }
-
-            let idx = self.map.xy_idx(x, y);
-            self.map.tiles[idx] = TileType::Floor;
-        }
-    }
 }
@@ -1,5 +1,13 @@
-use crate::{Map, Rect, TileType, MAP_HEIGHT, MAP_WIDTH};
+use crate::{Map, Rect, TileType};
+use bracket_lib::random::RandomNumberGenerator;
 use std::cmp::{max, min};
+use std::collections::HashMap;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum Symmetry {
+    None,
+    Both,
+}
 
 pub fn apply_room_to_map(map: &mut Map, room: &Rect) {
     for y in room.y1 + 1..=room.y2 {
@@ -10,20 +18,112 @@ pub fn apply_room_to_map(map: &mut Map, room: &Rect) {
     }
 }
 
-pub fn apply_horizontal_tunnel(map: &mut Map, x1: i32, x2: i32, y: i32) {
+/// Digs a horizontal tunnel, returning the tiles that were solid before it went through
+pub fn apply_horizontal_tunnel(map: &mut Map, x1: i32, x2: i32, y: i32) -> Vec<usize> {
+    let mut corridor = Vec::new();
     for x in min(x1, x2)..=max(x1, x2) {
         let idx = map.xy_idx(x, y);
-        if idx > 0 && idx < (MAP_HEIGHT * MAP_WIDTH) as usize {
-            map.tiles[idx as usize] = TileType::Floor;
+        if idx > 0 && idx < map.tiles.len() && map.tiles[idx] != TileType::Floor {
+            corridor.push(idx);
+            map.tiles[idx] = TileType::Floor;
         }
     }
+    corridor
 }
 
-pub fn apply_vertical_tunnel(map: &mut Map, y1: i32, y2: i32, x: i32) {
+/// Digs a vertical tunnel, returning the tiles that were solid before it went through
+pub fn apply_vertical_tunnel(map: &mut Map, y1: i32, y2: i32, x: i32) -> Vec<usize> {
+    let mut corridor = Vec::new();
     for y in min(y1, y2)..=max(y1, y2) {
         let idx = map.xy_idx(x, y);
-        if idx > 0 && idx < (MAP_HEIGHT * MAP_WIDTH) as usize {
-            map.tiles[idx as usize] = TileType::Floor;
+        if idx > 0 && idx < map.tiles.len() && map.tiles[idx] != TileType::Floor {
+            corridor.push(idx);
+            map.tiles[idx] = TileType::Floor;
         }
     }
+    corridor
+}
+
+/// Walks from (x1, y1) to (x2, y2) one axis at a time, returning the tiles that were dug out
+pub fn draw_corridor(map: &mut Map, x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<usize> {
+    let mut corridor = Vec::new();
+    let mut x = x1;
+    let mut y = y1;
+
+    while x != x2 || y != y2 {
+        if x < x2 {
+            x += 1;
+        } else if x > x2 {
+            x -= 1;
+        } else if y < y2 {
+            y += 1;
+        } else if y > y2 {
+            y -= 1;
+        }
+
+        let idx = map.xy_idx(x, y);
+        if map.tiles[idx] != TileType::Floor {
+            corridor.push(idx);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+
+    corridor
+}
+
+/// Digs out a brush-sized patch of floor at (x, y), mirrored across both axes if `mode` asks
+pub fn paint(map: &mut Map, mode: Symmetry, brush_size: i32, x: i32, y: i32) {
+    let center_x = map.width / 2;
+    let center_y = map.height / 2;
+    match mode {
+        Symmetry::None => apply_paint(map, brush_size, x, y),
+        Symmetry::Both => {
+            apply_paint(map, brush_size, x, y);
+            apply_paint(map, brush_size, center_x - (x - center_x), y);
+            apply_paint(map, brush_size, x, center_y - (y - center_y));
+            apply_paint(
+                map,
+                brush_size,
+                center_x - (x - center_x),
+                center_y - (y - center_y),
+            );
+        }
+    }
+}
+
+fn apply_paint(map: &mut Map, brush_size: i32, x: i32, y: i32) {
+    let half_brush = brush_size / 2;
+    for brush_y in y - half_brush..y - half_brush + brush_size {
+        for brush_x in x - half_brush..x - half_brush + brush_size {
+            if brush_x > 1 && brush_x < map.width - 1 && brush_y > 1 && brush_y < map.height - 1 {
+                let idx = map.xy_idx(brush_x, brush_y);
+                map.tiles[idx] = TileType::Floor;
+            }
+        }
+    }
+}
+
+/// Splits the map's floor into cellular noise regions, which make for natural-looking spawn areas
+pub fn generate_voronoi_spawn_regions(
+    map: &Map,
+    rng: &mut RandomNumberGenerator,
+) -> HashMap<i32, Vec<usize>> {
+    let mut noise_areas: HashMap<i32, Vec<usize>> = HashMap::new();
+    let mut noise = bracket_lib::noise::FastNoise::seeded(rng.roll_dice(1, 65536) as u64);
+    noise.set_noise_type(bracket_lib::noise::NoiseType::Cellular);
+    noise.set_frequency(0.08);
+    noise.set_cellular_distance_function(bracket_lib::noise::CellularDistanceFunction::Manhattan);
+
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let idx = map.xy_idx(x, y);
+            if map.tiles[idx] == TileType::Floor {
+                let cell_value_f = noise.get_noise(x as f32, y as f32) * 10240.0;
+                let cell_value = cell_value_f as i32;
+                noise_areas.entry(cell_value).or_default().push(idx);
+            }
+        }
+    }
+
+    noise_areas
 }
@@ -0,0 +1,47 @@
+use bracket_lib::{pathfinding::DijkstraMap, random::RandomNumberGenerator};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Fills in any ground the player could never walk to from the starting position, along with
+/// anything already set to spawn there
+pub struct CullUnreachable {}
+
+impl MetaMapBuilder for CullUnreachable {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl CullUnreachable {
+    pub fn new() -> Box<CullUnreachable> {
+        Box::new(CullUnreachable {})
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        let start = build_data
+            .starting_position
+            .as_ref()
+            .expect("Culling unreachable areas needs a starting position");
+        let start_idx = build_data.map.xy_idx(start.x, start.y);
+        build_data.map.populate_blocked();
+        // A maze can wind on for longer than any fixed search depth, so allow for every tile
+        let dijkstra_map = DijkstraMap::new(
+            build_data.map.width,
+            build_data.map.height,
+            &[start_idx],
+            &build_data.map,
+            build_data.map.tiles.len() as f32,
+        );
+        for (i, tile) in build_data.map.tiles.iter_mut().enumerate() {
+            if tile.walkable() && dijkstra_map.map[i] == f32::MAX {
+                *tile = TileType::Wall;
+            }
+        }
+
+        let tiles = &build_data.map.tiles;
+        build_data
+            .spawn_list
+            .retain(|(idx, _)| tiles[*idx].walkable());
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use bracket_lib::{color::RGB, random::RandomNumberGenerator};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Roughly one glowing fungus for every this many floor tiles in a dark cave
+const FLOOR_PER_FUNGUS: usize = 150;
+
+/// Takes away the level's ambient light, so only what the player carries and the light sources
+/// placed here light the way. Rooms may get a brazier in the middle or a torch by the wall, and
+/// some are left dark; levels without rooms get glowing fungus scattered over the floor. Run it
+/// after the start, stairs and spawns are placed, so the lights stay out of their way.
+pub struct DarkLevel {}
+
+impl MetaMapBuilder for DarkLevel {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl DarkLevel {
+    pub fn new() -> Box<DarkLevel> {
+        Box::new(DarkLevel {})
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        build_data.map.ambient_light = RGB::from_f32(0.0, 0.0, 0.0);
+
+        let mut taken: HashSet<usize> = build_data.spawn_list.iter().map(|s| s.0).collect();
+        if let Some(start) = &build_data.starting_position {
+            taken.insert(build_data.map.xy_idx(start.x, start.y));
+        }
+        let map = &build_data.map;
+        let free_floor = |idx: usize, taken: &HashSet<usize>| {
+            map.tiles[idx] == TileType::Floor && !taken.contains(&idx)
+        };
+        let by_a_wall = |x: i32, y: i32| {
+            [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .iter()
+                .any(|(dx, dy)| map.tiles[map.xy_idx(x + dx, y + dy)] == TileType::Wall)
+        };
+
+        let mut lights: Vec<(usize, &str)> = Vec::new();
+        match &build_data.rooms {
+            Some(rooms) => {
+                for room in rooms.iter() {
+                    match rng.roll_dice(1, 3) {
+                        1 => {
+                            let (x, y) = room.center();
+                            let idx = map.xy_idx(x, y);
+                            if free_floor(idx, &taken) {
+                                taken.insert(idx);
+                                lights.push((idx, "Brazier"));
+                            }
+                        }
+                        2 => {
+                            // Torches hang just inside the walls
+                            let mut by_wall: Vec<usize> = Vec::new();
+                            for y in room.y1 + 1..=room.y2 {
+                                for x in room.x1 + 1..=room.x2 {
+                                    let idx = map.xy_idx(x, y);
+                                    if free_floor(idx, &taken) && by_a_wall(x, y) {
+                                        by_wall.push(idx);
+                                    }
+                                }
+                            }
+                            if !by_wall.is_empty() {
+                                let pick = (rng.roll_dice(1, by_wall.len() as i32) - 1) as usize;
+                                taken.insert(by_wall[pick]);
+                                lights.push((by_wall[pick], "Torch"));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            None => {
+                let mut floor: Vec<usize> = (0..map.tiles.len())
+                    .filter(|idx| free_floor(*idx, &taken))
+                    .collect();
+                let n_fungi = floor.len() / FLOOR_PER_FUNGUS;
+                for _ in 0..n_fungi {
+                    if floor.is_empty() {
+                        break;
+                    }
+                    let pick = (rng.roll_dice(1, floor.len() as i32) - 1) as usize;
+                    lights.push((floor.remove(pick), "Glowing Fungus"));
+                }
+            }
+        }
+
+        for (idx, name) in lights {
+            build_data.spawn_list.push((idx, name.to_string()));
+        }
+    }
+}
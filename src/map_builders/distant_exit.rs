@@ -0,0 +1,47 @@
+use bracket_lib::{pathfinding::DijkstraMap, random::RandomNumberGenerator};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Puts the down stairs on the reachable tile furthest from the starting position
+pub struct DistantExit {}
+
+impl MetaMapBuilder for DistantExit {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl DistantExit {
+    pub fn new() -> Box<DistantExit> {
+        Box::new(DistantExit {})
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        let start = build_data
+            .starting_position
+            .as_ref()
+            .expect("A distant exit needs a starting position");
+        let start_idx = build_data.map.xy_idx(start.x, start.y);
+        build_data.map.populate_blocked();
+        let dijkstra_map = DijkstraMap::new(
+            build_data.map.width,
+            build_data.map.height,
+            &[start_idx],
+            &build_data.map,
+            build_data.map.tiles.len() as f32,
+        );
+
+        let mut exit_tile = (start_idx, 0.0f32);
+        for (i, tile) in build_data.map.tiles.iter().enumerate() {
+            if *tile == TileType::Floor {
+                let distance_to_start = dijkstra_map.map[i];
+                if distance_to_start != f32::MAX && distance_to_start > exit_tile.1 {
+                    exit_tile = (i, distance_to_start);
+                }
+            }
+        }
+
+        build_data.map.tiles[exit_tile.0] = TileType::DownStairs;
+    }
+}
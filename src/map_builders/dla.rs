@@ -0,0 +1,188 @@
+use bracket_lib::{prelude::line2d, random::RandomNumberGenerator, terminal::Point};
+
+use super::{
+    common::{paint, Symmetry},
+    BuilderMap, InitialMapBuilder,
+};
+use crate::{Map, TileType};
+
+const DIGGERS_PER_SNAPSHOT: i32 = 20;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum DLAAlgorithm {
+    WalkInwards,
+    WalkOutwards,
+    CentralAttractor,
+}
+
+pub struct DLABuilder {
+    algorithm: DLAAlgorithm,
+    brush_size: i32,
+    floor_percent: f32,
+}
+
+impl InitialMapBuilder for DLABuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl DLABuilder {
+    fn new(algorithm: DLAAlgorithm, brush_size: i32) -> Box<DLABuilder> {
+        Box::new(DLABuilder {
+            algorithm,
+            brush_size,
+            floor_percent: 0.25,
+        })
+    }
+
+    /// Diggers start at random points and wander until they bump into the existing cave
+    pub fn walk_inwards() -> Box<DLABuilder> {
+        DLABuilder::new(DLAAlgorithm::WalkInwards, 1)
+    }
+
+    /// Diggers start in the middle and wander until they break out into solid rock
+    pub fn walk_outwards() -> Box<DLABuilder> {
+        DLABuilder::new(DLAAlgorithm::WalkOutwards, 2)
+    }
+
+    /// Diggers start at random points and head straight for the middle
+    pub fn central_attractor() -> Box<DLABuilder> {
+        DLABuilder::new(DLAAlgorithm::CentralAttractor, 2)
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        // Carve a starting seed in the middle of the map
+        let map = &mut build_data.map;
+        let start = Point::new(map.width / 2, map.height / 2);
+        let start_idx = map.xy_idx(start.x, start.y);
+        let width = map.width as usize;
+        for idx in [
+            start_idx,
+            start_idx - 1,
+            start_idx + 1,
+            start_idx - width,
+            start_idx + width,
+        ] {
+            map.tiles[idx] = TileType::Floor;
+        }
+
+        let total_tiles = map.width * map.height;
+        let desired_floor_tiles = (self.floor_percent * total_tiles as f32) as usize;
+        let mut floor_tile_count = count_floor(map);
+        let mut digger_count = 0;
+
+        while floor_tile_count < desired_floor_tiles {
+            let map = &mut build_data.map;
+            match self.algorithm {
+                DLAAlgorithm::WalkInwards => self.walk_inwards_digger(rng, map),
+                DLAAlgorithm::WalkOutwards => self.walk_outwards_digger(rng, map, start),
+                DLAAlgorithm::CentralAttractor => self.central_attractor_digger(rng, map, start),
+            }
+            floor_tile_count = count_floor(map);
+
+            // Each digger only adds a tile or two, so only keep every so often
+            digger_count += 1;
+            if digger_count % DIGGERS_PER_SNAPSHOT == 0 {
+                build_data.take_snapshot();
+            }
+        }
+    }
+
+    fn random_point(&self, rng: &mut RandomNumberGenerator, map: &Map) -> (i32, i32) {
+        (
+            rng.roll_dice(1, map.width - 3) + 1,
+            rng.roll_dice(1, map.height - 3) + 1,
+        )
+    }
+
+    fn stagger(&self, rng: &mut RandomNumberGenerator, map: &Map, x: &mut i32, y: &mut i32) {
+        match rng.roll_dice(1, 4) {
+            1 => {
+                if *x > 2 {
+                    *x -= 1;
+                }
+            }
+            2 => {
+                if *x < map.width - 2 {
+                    *x += 1;
+                }
+            }
+            3 => {
+                if *y > 2 {
+                    *y -= 1;
+                }
+            }
+            _ => {
+                if *y < map.height - 2 {
+                    *y += 1;
+                }
+            }
+        }
+    }
+
+    fn walk_inwards_digger(&mut self, rng: &mut RandomNumberGenerator, map: &mut Map) {
+        let (mut digger_x, mut digger_y) = self.random_point(rng, map);
+        let mut prev_x = digger_x;
+        let mut prev_y = digger_y;
+        let mut digger_idx = map.xy_idx(digger_x, digger_y);
+        while map.tiles[digger_idx] == TileType::Wall {
+            prev_x = digger_x;
+            prev_y = digger_y;
+            self.stagger(rng, map, &mut digger_x, &mut digger_y);
+            digger_idx = map.xy_idx(digger_x, digger_y);
+        }
+        paint(map, Symmetry::None, self.brush_size, prev_x, prev_y);
+    }
+
+    fn walk_outwards_digger(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        map: &mut Map,
+        start: Point,
+    ) {
+        let mut digger_x = start.x;
+        let mut digger_y = start.y;
+        let mut digger_idx = map.xy_idx(digger_x, digger_y);
+        while map.tiles[digger_idx] == TileType::Floor {
+            self.stagger(rng, map, &mut digger_x, &mut digger_y);
+            digger_idx = map.xy_idx(digger_x, digger_y);
+        }
+        paint(map, Symmetry::None, self.brush_size, digger_x, digger_y);
+    }
+
+    fn central_attractor_digger(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        map: &mut Map,
+        start: Point,
+    ) {
+        let (mut digger_x, mut digger_y) = self.random_point(rng, map);
+        let mut prev_x = digger_x;
+        let mut prev_y = digger_y;
+        let mut digger_idx = map.xy_idx(digger_x, digger_y);
+
+        let mut path = line2d(
+            bracket_lib::prelude::LineAlg::Bresenham,
+            Point::new(digger_x, digger_y),
+            start,
+        );
+
+        while map.tiles[digger_idx] == TileType::Wall && !path.is_empty() {
+            prev_x = digger_x;
+            prev_y = digger_y;
+            digger_x = path[0].x;
+            digger_y = path[0].y;
+            path.remove(0);
+            digger_idx = map.xy_idx(digger_x, digger_y);
+        }
+        paint(map, Symmetry::None, self.brush_size, prev_x, prev_y);
+    }
+}
+
+fn count_floor(map: &Map) -> usize {
+    map.tiles
+        .iter()
+        .filter(|tile| **tile == TileType::Floor)
+        .count()
+}
@@ -0,0 +1,62 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Hangs a door at both ends of each corridor, where it breaks through a room's wall, if the walls
+/// either side can hold one
+pub struct DoorPlacement {}
+
+impl MetaMapBuilder for DoorPlacement {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.doors(build_data);
+    }
+}
+
+impl DoorPlacement {
+    pub fn new() -> Box<DoorPlacement> {
+        Box::new(DoorPlacement {})
+    }
+
+    fn door_possible(&self, build_data: &BuilderMap, idx: usize) -> bool {
+        let map = &build_data.map;
+        let x = idx as i32 % map.width;
+        let y = idx as i32 / map.width;
+        if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+            return false;
+        }
+
+        if map.tiles[idx] != TileType::Floor {
+            return false;
+        }
+
+        // Don't put two doors on top of each other
+        if build_data.spawn_list.iter().any(|spawn| spawn.0 == idx) {
+            return false;
+        }
+
+        let width = map.width as usize;
+        let floor = |i: usize| map.tiles[i] == TileType::Floor;
+        let wall = |i: usize| map.tiles[i] == TileType::Wall;
+
+        // East-west passage through a north-south wall, or the other way around
+        (floor(idx - 1) && floor(idx + 1) && wall(idx - width) && wall(idx + width))
+            || (wall(idx - 1) && wall(idx + 1) && floor(idx - width) && floor(idx + width))
+    }
+
+    fn doors(&mut self, build_data: &mut BuilderMap) {
+        let corridors = match build_data.corridors.as_ref() {
+            Some(corridors) => corridors.clone(),
+            None => return,
+        };
+        for corridor in corridors.iter() {
+            // Corridors only record the tiles they dug, so each end is where one met a room
+            for &idx in [corridor.first(), corridor.last()].into_iter().flatten() {
+                if self.door_possible(build_data, idx) {
+                    build_data.map.tiles[idx] = TileType::Door;
+                    build_data.spawn_list.push((idx, "Door".to_string()));
+                }
+            }
+        }
+    }
+}
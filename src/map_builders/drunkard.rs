@@ -0,0 +1,187 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{
+    common::{paint, Symmetry},
+    BuilderMap, InitialMapBuilder,
+};
+use crate::TileType;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum DrunkSpawnMode {
+    StartingPoint,
+    Random,
+}
+
+pub struct DrunkardSettings {
+    pub spawn_mode: DrunkSpawnMode,
+    pub drunken_lifetime: i32,
+    pub floor_percent: f32,
+    pub brush_size: i32,
+    pub symmetry: Symmetry,
+}
+
+pub struct DrunkardsWalkBuilder {
+    settings: DrunkardSettings,
+}
+
+impl InitialMapBuilder for DrunkardsWalkBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl DrunkardsWalkBuilder {
+    pub fn new(settings: DrunkardSettings) -> Box<DrunkardsWalkBuilder> {
+        Box::new(DrunkardsWalkBuilder { settings })
+    }
+
+    /// Diggers all start in the middle and wander far, carving one big cavern
+    pub fn open_area() -> Box<DrunkardsWalkBuilder> {
+        DrunkardsWalkBuilder::new(DrunkardSettings {
+            spawn_mode: DrunkSpawnMode::StartingPoint,
+            drunken_lifetime: 400,
+            floor_percent: 0.5,
+            brush_size: 1,
+            symmetry: Symmetry::None,
+        })
+    }
+
+    /// Diggers start anywhere already dug and wander far, leaving broad halls
+    pub fn open_halls() -> Box<DrunkardsWalkBuilder> {
+        DrunkardsWalkBuilder::new(DrunkardSettings {
+            spawn_mode: DrunkSpawnMode::Random,
+            drunken_lifetime: 400,
+            floor_percent: 0.5,
+            brush_size: 1,
+            symmetry: Symmetry::None,
+        })
+    }
+
+    /// Short-lived diggers with a low floor target leave narrow, twisting passages
+    pub fn winding_passages() -> Box<DrunkardsWalkBuilder> {
+        DrunkardsWalkBuilder::new(DrunkardSettings {
+            spawn_mode: DrunkSpawnMode::Random,
+            drunken_lifetime: 100,
+            floor_percent: 0.4,
+            brush_size: 1,
+            symmetry: Symmetry::None,
+        })
+    }
+
+    /// Winding passages dug with a wider brush
+    pub fn fat_passages() -> Box<DrunkardsWalkBuilder> {
+        DrunkardsWalkBuilder::new(DrunkardSettings {
+            spawn_mode: DrunkSpawnMode::Random,
+            drunken_lifetime: 100,
+            floor_percent: 0.4,
+            brush_size: 2,
+            symmetry: Symmetry::None,
+        })
+    }
+
+    /// Every step is mirrored across both axes, giving an eerily regular cave
+    pub fn fearful_symmetry() -> Box<DrunkardsWalkBuilder> {
+        DrunkardsWalkBuilder::new(DrunkardSettings {
+            spawn_mode: DrunkSpawnMode::Random,
+            drunken_lifetime: 100,
+            floor_percent: 0.4,
+            brush_size: 1,
+            symmetry: Symmetry::Both,
+        })
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        // Every digger sets out from the middle of the map
+        let start_x = build_data.map.width / 2;
+        let start_y = build_data.map.height / 2;
+        let start_idx = build_data.map.xy_idx(start_x, start_y);
+        build_data.map.tiles[start_idx] = TileType::Floor;
+
+        let total_tiles = build_data.map.width * build_data.map.height;
+        let desired_floor_tiles = (self.settings.floor_percent * total_tiles as f32) as usize;
+        let mut floor_tile_count = build_data
+            .map
+            .tiles
+            .iter()
+            .filter(|tile| **tile == TileType::Floor)
+            .count();
+        let mut digger_count = 0;
+
+        while floor_tile_count < desired_floor_tiles {
+            let mut drunk_x;
+            let mut drunk_y;
+            match self.settings.spawn_mode {
+                DrunkSpawnMode::StartingPoint => {
+                    drunk_x = start_x;
+                    drunk_y = start_y;
+                }
+                DrunkSpawnMode::Random => {
+                    if digger_count == 0 {
+                        drunk_x = start_x;
+                        drunk_y = start_y;
+                    } else {
+                        // Start from somewhere already dug, so every digger joins up with the rest
+                        let floor_tiles: Vec<usize> = build_data
+                            .map
+                            .tiles
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, tile)| **tile == TileType::Floor)
+                            .map(|(idx, _)| idx)
+                            .collect();
+                        let idx =
+                            floor_tiles[(rng.roll_dice(1, floor_tiles.len() as i32) - 1) as usize];
+                        drunk_x = idx as i32 % build_data.map.width;
+                        drunk_y = idx as i32 / build_data.map.width;
+                    }
+                }
+            }
+
+            let mut drunk_life = self.settings.drunken_lifetime;
+            while drunk_life > 0 {
+                paint(
+                    &mut build_data.map,
+                    self.settings.symmetry,
+                    self.settings.brush_size,
+                    drunk_x,
+                    drunk_y,
+                );
+
+                let stagger_direction = rng.roll_dice(1, 4);
+                match stagger_direction {
+                    1 => {
+                        if drunk_x > 2 {
+                            drunk_x -= 1;
+                        }
+                    }
+                    2 => {
+                        if drunk_x < build_data.map.width - 2 {
+                            drunk_x += 1;
+                        }
+                    }
+                    3 => {
+                        if drunk_y > 2 {
+                            drunk_y -= 1;
+                        }
+                    }
+                    _ => {
+                        if drunk_y < build_data.map.height - 2 {
+                            drunk_y += 1;
+                        }
+                    }
+                }
+
+                drunk_life -= 1;
+            }
+
+            digger_count += 1;
+            build_data.take_snapshot();
+            floor_tile_count = build_data
+                .map
+                .tiles
+                .iter()
+                .filter(|tile| **tile == TileType::Floor)
+                .count();
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, InitialMapBuilder};
+use crate::{Map, TileType};
+
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+
+/// How many carving steps to take between snapshots
+const STEPS_PER_SNAPSHOT: i32 = 25;
+
+/// A perfect maze: every cell can be reached from every other, so any exit is always solvable
+pub struct MazeBuilder {}
+
+impl InitialMapBuilder for MazeBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        // Each maze cell takes up two map tiles: the cell itself, and the wall or gap beside it
+        let mut grid = Grid::new(
+            (build_data.map.width / 2) - 2,
+            (build_data.map.height / 2) - 2,
+            rng,
+        );
+        grid.generate_maze(build_data);
+    }
+}
+
+impl MazeBuilder {
+    pub fn new() -> Box<MazeBuilder> {
+        Box::new(MazeBuilder {})
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Cell {
+    row: i32,
+    column: i32,
+    walls: [bool; 4],
+    visited: bool,
+}
+
+impl Cell {
+    fn new(row: i32, column: i32) -> Cell {
+        Cell {
+            row,
+            column,
+            walls: [true, true, true, true],
+            visited: false,
+        }
+    }
+
+    fn remove_walls(&mut self, next: &mut Cell) {
+        let x = self.column - next.column;
+        let y = self.row - next.row;
+
+        if x == 1 {
+            self.walls[LEFT] = false;
+            next.walls[RIGHT] = false;
+        } else if x == -1 {
+            self.walls[RIGHT] = false;
+            next.walls[LEFT] = false;
+        } else if y == 1 {
+            self.walls[TOP] = false;
+            next.walls[BOTTOM] = false;
+        } else if y == -1 {
+            self.walls[BOTTOM] = false;
+            next.walls[TOP] = false;
+        }
+    }
+}
+
+struct Grid<'a> {
+    width: i32,
+    height: i32,
+    cells: Vec<Cell>,
+    backtrace: Vec<usize>,
+    current: usize,
+    rng: &'a mut RandomNumberGenerator,
+}
+
+impl<'a> Grid<'a> {
+    fn new(width: i32, height: i32, rng: &'a mut RandomNumberGenerator) -> Grid<'a> {
+        let mut cells = Vec::new();
+        for row in 0..height {
+            for column in 0..width {
+                cells.push(Cell::new(row, column));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+            backtrace: Vec::new(),
+            current: 0,
+            rng,
+        }
+    }
+
+    fn calculate_index(&self, row: i32, column: i32) -> Option<usize> {
+        if row < 0 || column < 0 || column > self.width - 1 || row > self.height - 1 {
+            None
+        } else {
+            Some((column + (row * self.width)) as usize)
+        }
+    }
+
+    fn get_available_neighbors(&self) -> Vec<usize> {
+        let current_row = self.cells[self.current].row;
+        let current_column = self.cells[self.current].column;
+
+        [
+            (current_row - 1, current_column),
+            (current_row, current_column + 1),
+            (current_row + 1, current_column),
+            (current_row, current_column - 1),
+        ]
+        .iter()
+        .filter_map(|(row, column)| self.calculate_index(*row, *column))
+        .filter(|idx| !self.cells[*idx].visited)
+        .collect()
+    }
+
+    fn find_next_cell(&mut self) -> Option<usize> {
+        let neighbors = self.get_available_neighbors();
+        if neighbors.is_empty() {
+            return None;
+        }
+        let roll = (self.rng.roll_dice(1, neighbors.len() as i32) - 1) as usize;
+        Some(neighbors[roll])
+    }
+
+    /// Carves the maze with a recursive backtracker, then copies it onto the map
+    fn generate_maze(&mut self, build_data: &mut BuilderMap) {
+        let mut steps = 0;
+        loop {
+            self.cells[self.current].visited = true;
+            match self.find_next_cell() {
+                Some(next) => {
+                    self.cells[next].visited = true;
+                    self.backtrace.push(self.current);
+
+                    let (lower, higher) = self.cells.split_at_mut(usize::max(self.current, next));
+                    let (current_cell, next_cell) = if self.current < next {
+                        (&mut lower[self.current], &mut higher[0])
+                    } else {
+                        (&mut higher[0], &mut lower[next])
+                    };
+                    current_cell.remove_walls(next_cell);
+                    self.current = next;
+                }
+                None => match self.backtrace.pop() {
+                    Some(previous) => self.current = previous,
+                    None => break,
+                },
+            }
+
+            steps += 1;
+            if steps % STEPS_PER_SNAPSHOT == 0 {
+                self.copy_to_map(&mut build_data.map);
+                build_data.take_snapshot();
+            }
+        }
+
+        self.copy_to_map(&mut build_data.map);
+    }
+
+    fn copy_to_map(&self, map: &mut Map) {
+        for cell in self.cells.iter().filter(|cell| cell.visited) {
+            let x = (cell.column + 1) * 2;
+            let y = (cell.row + 1) * 2;
+            let idx = map.xy_idx(x, y);
+
+            map.tiles[idx] = TileType::Floor;
+            if !cell.walls[TOP] {
+                map.tiles[idx - map.width as usize] = TileType::Floor;
+            }
+            if !cell.walls[RIGHT] {
+                map.tiles[idx + 1] = TileType::Floor;
+            }
+            if !cell.walls[BOTTOM] {
+                map.tiles[idx + map.width as usize] = TileType::Floor;
+            }
+            if !cell.walls[LEFT] {
+                map.tiles[idx - 1] = TileType::Floor;
+            }
+        }
+    }
+}
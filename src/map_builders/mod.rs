@@ -1,29 +1,450 @@
-use crate::Position;
+use crate::{spawner, Position, Rect};
 
 use super::Map;
-mod simple_map;
 use bracket_lib::random::RandomNumberGenerator;
+use specs::World;
+mod simple_map;
 use simple_map::*;
 mod bsp_dungeon;
 use bsp_dungeon::*;
 mod cellular_automata;
 use cellular_automata::*;
-use specs::World;
+mod drunkard;
+use drunkard::*;
+mod maze;
+use maze::*;
+mod dla;
+use dla::*;
+mod voronoi;
+use voronoi::*;
+mod waveform_collapse;
+use waveform_collapse::*;
+mod room_based_spawner;
+use room_based_spawner::*;
+mod room_based_starting_position;
+use room_based_starting_position::*;
+mod room_based_stairs;
+use room_based_stairs::*;
+mod area_starting_points;
+use area_starting_points::*;
+mod cull_unreachable;
+use cull_unreachable::*;
+mod distant_exit;
+use distant_exit::*;
+mod voronoi_spawning;
+use voronoi_spawning::*;
+mod door_placement;
+use door_placement::*;
+mod room_corner_rounding;
+use room_corner_rounding::*;
+mod rooms_corridors_dogleg;
+use rooms_corridors_dogleg::*;
+mod rooms_corridors_bsp;
+use rooms_corridors_bsp::*;
+mod rooms_corridors_nearest;
+use rooms_corridors_nearest::*;
+mod prefab_builder;
+use prefab_builder::*;
+mod pool_placement;
+use pool_placement::*;
+mod terrain_decorator;
+use terrain_decorator::*;
+mod dark_level;
+use dark_level::*;
+mod shrine_placement;
+use shrine_placement::*;
 mod common;
 
-pub trait MapBuilder {
-    fn build_map(&mut self);
-    fn spawn_entities(&mut self, ecs: &mut World);
-    fn get_map(&self) -> Map;
-    fn get_starting_position(&self) -> Position;
+/// Everything a builder chain knows about the level it is building, handed from one builder to
+/// the next
+pub struct BuilderMap {
+    pub spawn_list: Vec<(usize, String)>,
+    pub map: Map,
+    pub starting_position: Option<Position>,
+    pub rooms: Option<Vec<Rect>>,
+    pub corridors: Option<Vec<Vec<usize>>>,
+    pub history: Vec<MapSnapshot>,
+    /// Builders that couldn't produce a level of their own and fell back on something else
+    pub fallbacks: Vec<&'static str>,
+    current_builder: &'static str,
+}
+
+impl BuilderMap {
+    /// Records the map as it stands, fully revealed, so the build can be played back later
+    pub fn take_snapshot(&mut self) {
+        let mut map = self.map.clone();
+        for revealed in map.revealed_tiles.iter_mut() {
+            *revealed = true;
+        }
+        for visible in map.visible_tiles.iter_mut() {
+            *visible = true;
+        }
+        self.history.push(MapSnapshot {
+            builder: self.current_builder,
+            map,
+        });
+    }
+
+    /// Notes that the builder at work gave up on its own approach, so batch runs can report it
+    pub fn record_fallback(&mut self) {
+        self.fallbacks.push(self.current_builder);
+    }
+}
+
+/// A copy of the map part way through generation, and the builder that had just worked on it
+#[derive(Clone)]
+pub struct MapSnapshot {
+    pub builder: &'static str,
+    pub map: Map,
+}
+
+impl MapSnapshot {
+    /// Renders the snapshot as plain text, one line per map row
+    pub fn to_text(&self) -> String {
+        let mut text = String::with_capacity(self.map.tiles.len() + self.map.height as usize);
+        for row in self.map.tiles.chunks(self.map.width as usize) {
+            for tile in row.iter() {
+                text.push(tile.info().ascii);
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// One initial builder lays out the level, then each meta builder in turn modifies it
+pub struct BuilderChain {
+    starter: Option<Box<dyn InitialMapBuilder>>,
+    builders: Vec<Box<dyn MetaMapBuilder>>,
+    pub build_data: BuilderMap,
+}
+
+impl BuilderChain {
+    pub fn new(new_depth: i32, width: i32, height: i32) -> BuilderChain {
+        BuilderChain {
+            starter: None,
+            builders: Vec::new(),
+            build_data: BuilderMap {
+                spawn_list: Vec::new(),
+                map: Map::new(width, height, new_depth),
+                starting_position: None,
+                rooms: None,
+                corridors: None,
+                history: Vec::new(),
+                fallbacks: Vec::new(),
+                current_builder: "",
+            },
+        }
+    }
+
+    pub fn start_with(&mut self, starter: Box<dyn InitialMapBuilder>) {
+        match self.starter {
+            None => self.starter = Some(starter),
+            Some(_) => panic!("You can only have one starting builder."),
+        };
+    }
+
+    pub fn with(&mut self, metabuilder: Box<dyn MetaMapBuilder>) {
+        self.builders.push(metabuilder);
+    }
+
+    pub fn build_map(&mut self, rng: &mut RandomNumberGenerator) {
+        match &mut self.starter {
+            None => panic!("Cannot run a map builder chain without a starting build system"),
+            Some(starter) => {
+                self.build_data.current_builder = starter.name();
+                starter.build_map(rng, &mut self.build_data);
+                self.build_data.take_snapshot();
+            }
+        }
+
+        for metabuilder in self.builders.iter_mut() {
+            self.build_data.current_builder = metabuilder.name();
+            metabuilder.build_map(rng, &mut self.build_data);
+            self.build_data.take_snapshot();
+        }
+    }
+
+    pub fn spawn_entities(&self, ecs: &mut World) {
+        for entity in self.build_data.spawn_list.iter() {
+            spawner::spawn_entity(ecs, entity);
+        }
+    }
+}
+
+pub trait InitialMapBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap);
+
+    /// Shown alongside this builder's snapshots
+    fn name(&self) -> &'static str {
+        short_type_name::<Self>()
+    }
+}
+
+pub trait MetaMapBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap);
+
+    /// Shown alongside this builder's snapshots
+    fn name(&self) -> &'static str {
+        short_type_name::<Self>()
+    }
+}
+
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let full_name = std::any::type_name::<T>();
+    full_name.rsplit("::").next().unwrap_or(full_name)
+}
+
+/// Rectangular rooms joined by corridors, with the stairs and spawns worked out per room
+fn random_room_builder(rng: &mut RandomNumberGenerator, builder: &mut BuilderChain) {
+    let bsp = rng.roll_dice(1, 2) == 1;
+    room_builder(rng, builder, bsp);
+}
+
+fn room_builder(rng: &mut RandomNumberGenerator, builder: &mut BuilderChain, bsp: bool) {
+    if bsp {
+        builder.start_with(BspDungeonBuilder::new());
+    } else {
+        builder.start_with(SimpleMapBuilder::new());
+    }
+
+    // Sometimes rough up the rooms before they are joined up
+    match rng.roll_dice(1, 4) {
+        1 => builder.with(CellularAutomataBuilder::new()),
+        2 => builder.with(RoomCornerRounder::new()),
+        _ => {}
+    }
+
+    match rng.roll_dice(1, 3) {
+        1 => builder.with(DoglegCorridors::new()),
+        2 => builder.with(NearestCorridors::new()),
+        _ => {
+            if bsp {
+                builder.with(BspCorridors::new())
+            } else {
+                builder.with(DoglegCorridors::new())
+            }
+        }
+    }
+
+    match rng.roll_dice(1, 2) {
+        1 => builder.with(RoomBasedStartingPosition::new()),
+        _ => {
+            let (start_x, start_y) = random_start_position(rng);
+            builder.with(AreaStartingPosition::new(start_x, start_y));
+        }
+    }
+
+    if rng.roll_dice(1, 4) == 1 {
+        builder.with(PoolPlacement::new());
+    }
+
+    // Corridors and cellular smoothing can both leave rooms cut off, so drop anything the player
+    // can't reach before deciding where the doors, stairs and monsters go
+    builder.with(CullUnreachable::new());
+
+    if rng.roll_dice(1, 2) == 1 {
+        builder.with(DoorPlacement::new());
+    }
+
+    match rng.roll_dice(1, 2) {
+        1 => builder.with(RoomBasedStairs::new()),
+        _ => builder.with(DistantExit::new()),
+    }
+
+    if rng.roll_dice(1, 3) == 1 {
+        builder.with(PrefabBuilder::vaults());
+    }
+
+    match rng.roll_dice(1, 2) {
+        1 => builder.with(RoomBasedSpawner::new()),
+        _ => builder.with(VoronoiSpawning::new()),
+    }
+
+    // Deeper down, some levels go dark
+    if builder.build_data.map.depth >= 3 && rng.roll_dice(1, 4) == 1 {
+        builder.with(DarkLevel::new());
+    }
+
+    // Decorating goes last, so everything above still finds plain floor to work with
+    if rng.roll_dice(1, 3) == 1 {
+        builder.with(TerrainDecorator::new());
+    }
+}
+
+/// Caves, mazes and other shapes without rooms; everything past the layout is area based
+fn random_shape_builder(rng: &mut RandomNumberGenerator, builder: &mut BuilderChain) {
+    let roll = (rng.roll_dice(1, SHAPE_STARTERS.len() as i32) - 1) as usize;
+    (SHAPE_STARTERS[roll].1)(builder);
+    finish_shape_builder(rng, builder);
 }
 
-pub fn random_builder() -> Box<dyn MapBuilder> {
-    let mut rng = RandomNumberGenerator::new();
-    let builder_idx = rng.roll_dice(1, 3);
-    match builder_idx {
-        1 => Box::new(SimpleMapBuilder::new()),
-        2 => Box::new(BspDungeonBuilder::new()),
-        _ => Box::new(CellularAutomataBuilder::new()),
+type ShapeStarter = fn(&mut BuilderChain);
+
+/// The layouts a shape builder can start from, by the name the map generation harness uses
+const SHAPE_STARTERS: [(&str, ShapeStarter); 15] = [
+    ("cellular", |b| b.start_with(CellularAutomataBuilder::new())),
+    ("open-area", |b| {
+        b.start_with(DrunkardsWalkBuilder::open_area())
+    }),
+    ("open-halls", |b| {
+        b.start_with(DrunkardsWalkBuilder::open_halls())
+    }),
+    ("winding-passages", |b| {
+        b.start_with(DrunkardsWalkBuilder::winding_passages())
+    }),
+    ("fat-passages", |b| {
+        b.start_with(DrunkardsWalkBuilder::fat_passages())
+    }),
+    ("fearful-symmetry", |b| {
+        b.start_with(DrunkardsWalkBuilder::fearful_symmetry())
+    }),
+    ("maze", |b| b.start_with(MazeBuilder::new())),
+    ("dla-walk-inwards", |b| {
+        b.start_with(DLABuilder::walk_inwards())
+    }),
+    ("dla-walk-outwards", |b| {
+        b.start_with(DLABuilder::walk_outwards())
+    }),
+    ("dla-central-attractor", |b| {
+        b.start_with(DLABuilder::central_attractor())
+    }),
+    ("voronoi-pythagoras", |b| {
+        b.start_with(VoronoiCellBuilder::pythagoras())
+    }),
+    ("voronoi-manhattan", |b| {
+        b.start_with(VoronoiCellBuilder::manhattan())
+    }),
+    ("voronoi-chebyshev", |b| {
+        b.start_with(VoronoiCellBuilder::chebyshev())
+    }),
+    ("wfc-template", |b| {
+        b.start_with(WaveformCollapseBuilder::from_template(include_str!(
+            "../../resources/wfc/ruined_halls.txt"
+        )))
+    }),
+    ("wfc-derived", |b| {
+        // Learn the patterns of a cave, then build a new one out of them
+        b.start_with(CellularAutomataBuilder::new());
+        b.with(WaveformCollapseBuilder::derived_map());
+    }),
+];
+
+/// Everything a shape builder does after laying out the level
+fn finish_shape_builder(rng: &mut RandomNumberGenerator, builder: &mut BuilderChain) {
+    // Sometimes stamp a hand-built section against one edge of the map
+    match rng.roll_dice(1, 8) {
+        1 => builder.with(PrefabBuilder::sectional(ORC_FORT)),
+        2 => builder.with(PrefabBuilder::sectional(WATCHTOWER)),
+        3 => builder.with(PrefabBuilder::sectional(BARRACKS)),
+        _ => {}
     }
+
+    builder.with(AreaStartingPosition::new(XStart::Center, YStart::Center));
+    if rng.roll_dice(1, 3) == 1 {
+        builder.with(PoolPlacement::new());
+    }
+    builder.with(CullUnreachable::new());
+    builder.with(DistantExit::new());
+    if rng.roll_dice(1, 3) == 1 {
+        builder.with(PrefabBuilder::vaults());
+    }
+    builder.with(VoronoiSpawning::new());
+    if builder.build_data.map.depth >= 2 && rng.roll_dice(1, 3) == 1 {
+        builder.with(DarkLevel::new());
+    }
+    if rng.roll_dice(1, 2) == 1 {
+        builder.with(TerrainDecorator::new());
+    }
+}
+
+/// A hand-authored level; its monsters and stairs all come from the template
+fn prefab_level_builder(builder: &mut BuilderChain) {
+    builder.start_with(PrefabBuilder::constant(ORC_KEEP));
+    builder.with(AreaStartingPosition::new(XStart::Left, YStart::Center));
+    builder.with(CullUnreachable::new());
+}
+
+fn random_start_position(rng: &mut RandomNumberGenerator) -> (XStart, YStart) {
+    let x = match rng.roll_dice(1, 3) {
+        1 => XStart::Left,
+        2 => XStart::Center,
+        _ => XStart::Right,
+    };
+    let y = match rng.roll_dice(1, 3) {
+        1 => YStart::Top,
+        2 => YStart::Center,
+        _ => YStart::Bottom,
+    };
+    (x, y)
+}
+
+/// The names `named_builder` understands, besides those of the shape starters
+const CHAIN_NAMES: [&str; 6] = ["random", "rooms", "simple", "bsp", "shapes", "keep"];
+
+/// Every builder name the map generation harness accepts
+pub fn builder_names() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = CHAIN_NAMES.to_vec();
+    names.extend(SHAPE_STARTERS.iter().map(|(name, _)| *name));
+    names
+}
+
+/// Sets up a chain by name, so a single kind of level can be generated over and over; the
+/// random choices within that kind still come from `rng`
+pub fn named_builder(
+    name: &str,
+    new_depth: i32,
+    width: i32,
+    height: i32,
+    rng: &mut RandomNumberGenerator,
+) -> Option<BuilderChain> {
+    let mut builder = BuilderChain::new(new_depth, width, height);
+    match name {
+        "random" => return Some(random_builder(new_depth, width, height, rng)),
+        "rooms" => random_room_builder(rng, &mut builder),
+        "simple" => room_builder(rng, &mut builder, false),
+        "bsp" => room_builder(rng, &mut builder, true),
+        "shapes" => random_shape_builder(rng, &mut builder),
+        "keep" => prefab_level_builder(&mut builder),
+        _ => {
+            let (_, starter) = SHAPE_STARTERS.iter().find(|(shape, _)| *shape == name)?;
+            starter(&mut builder);
+            finish_shape_builder(rng, &mut builder);
+        }
+    }
+    builder.with(ShrinePlacement::new());
+    Some(builder)
+}
+
+/// How big a level is at a given depth: the first fits on one screen, and they grow from there
+pub fn level_size(depth: i32) -> (i32, i32) {
+    let growth = i32::max(depth - 1, 0);
+    (
+        i32::min(80 + growth * 8, 160),
+        i32::min(43 + growth * 4, 86),
+    )
 }
+
+pub fn random_builder(
+    new_depth: i32,
+    width: i32,
+    height: i32,
+    rng: &mut RandomNumberGenerator,
+) -> BuilderChain {
+    let mut builder = BuilderChain::new(new_depth, width, height);
+    if rng.roll_dice(1, 20) == 1 {
+        prefab_level_builder(&mut builder);
+    } else {
+        match rng.roll_dice(1, 2) {
+            1 => random_room_builder(rng, &mut builder),
+            _ => random_shape_builder(rng, &mut builder),
+        }
+    }
+
+    // Every level gets somewhere to rest
+    builder.with(ShrinePlacement::new());
+    builder
+}
+
+#[cfg(test)]
+mod tests;
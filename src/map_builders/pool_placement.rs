@@ -0,0 +1,86 @@
+use bracket_lib::{
+    random::RandomNumberGenerator,
+    terminal::{DistanceAlg, Point},
+};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Lava replaces water from this depth down
+const LAVA_DEPTH: i32 = 4;
+
+/// Floods a few patches of open floor: deep water ringed by shallows, or lava ringed by gravel on
+/// deeper levels, with a bridge across each. Pools only go where the whole square around them is
+/// floor, so the walkable rim always leads around the middle and nothing gets cut off.
+pub struct PoolPlacement {}
+
+impl MetaMapBuilder for PoolPlacement {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl PoolPlacement {
+    pub fn new() -> Box<PoolPlacement> {
+        Box::new(PoolPlacement {})
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let lava = build_data.map.depth >= LAVA_DEPTH && rng.roll_dice(1, 2) == 1;
+        let (core, edge) = if lava {
+            (TileType::Lava, TileType::Gravel)
+        } else {
+            (TileType::DeepWater, TileType::ShallowWater)
+        };
+
+        let n_pools = rng.roll_dice(1, 3);
+        for _ in 0..n_pools {
+            let radius = rng.range(2, 5);
+            let centers = self.open_squares(build_data, radius);
+            if centers.is_empty() {
+                continue;
+            }
+            let center = centers[(rng.roll_dice(1, centers.len() as i32) - 1) as usize];
+
+            for y in center.y - radius..=center.y + radius {
+                for x in center.x - radius..=center.x + radius {
+                    let idx = build_data.map.xy_idx(x, y);
+                    let distance = DistanceAlg::Pythagoras.distance2d(center, Point::new(x, y));
+                    if x == center.x || y == center.y {
+                        // A bridge runs straight across in each direction
+                        if distance <= radius as f32 - 1.0 {
+                            build_data.map.tiles[idx] = TileType::Bridge;
+                        }
+                    } else if distance <= radius as f32 - 1.0 {
+                        build_data.map.tiles[idx] = core;
+                    } else if distance <= radius as f32 {
+                        build_data.map.tiles[idx] = edge;
+                    }
+                }
+            }
+            build_data.take_snapshot();
+        }
+    }
+
+    /// Centers of every square of floor `radius` tiles out in each direction, clear of the start
+    fn open_squares(&self, build_data: &BuilderMap, radius: i32) -> Vec<Point> {
+        let map = &build_data.map;
+        let start = build_data.starting_position.as_ref();
+        let mut centers = Vec::new();
+        for y in radius + 1..map.height - radius - 1 {
+            for x in radius + 1..map.width - radius - 1 {
+                let open = (y - radius..=y + radius).all(|ty| {
+                    (x - radius..=x + radius)
+                        .all(|tx| map.tiles[map.xy_idx(tx, ty)] == TileType::Floor)
+                });
+                let holds_start = start.is_some_and(|start| {
+                    (start.x - x).abs() <= radius && (start.y - y).abs() <= radius
+                });
+                if open && !holds_start {
+                    centers.push(Point::new(x, y));
+                }
+            }
+        }
+        centers
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, InitialMapBuilder, MetaMapBuilder};
+use crate::{Map, Rect, TileType};
+mod prefab_levels;
+pub use prefab_levels::*;
+mod prefab_sections;
+pub use prefab_sections::*;
+mod prefab_rooms;
+use prefab_rooms::*;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum PrefabMode {
+    Constant { level: PrefabLevel },
+    Sectional { section: PrefabSection },
+    RoomVaults,
+}
+
+/// Stamps hand-authored ASCII templates into the map. Terrain is written with each tile's `ascii`
+/// character from the tile table, and a space also counts as floor. `+` hangs a door in a doorway,
+/// `g` and `o` place goblins and orcs on floor, and `!` places a health potion.
+///
+/// A template can open with a legend of its own: `X=Name` lines, ended by a blank line, each
+/// placing whatever the spawner calls `Name` on floor wherever `X` appears below. Legend entries
+/// take precedence over the glyphs above.
+pub struct PrefabBuilder {
+    mode: PrefabMode,
+}
+
+/// Lays out a whole level from a template
+impl InitialMapBuilder for PrefabBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+/// Stamps sections or vaults over a level the rest of the chain has built
+impl MetaMapBuilder for PrefabBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl PrefabBuilder {
+    pub fn constant(level: PrefabLevel) -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder {
+            mode: PrefabMode::Constant { level },
+        })
+    }
+
+    pub fn sectional(section: PrefabSection) -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder {
+            mode: PrefabMode::Sectional { section },
+        })
+    }
+
+    pub fn vaults() -> Box<PrefabBuilder> {
+        Box::new(PrefabBuilder {
+            mode: PrefabMode::RoomVaults,
+        })
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        match self.mode {
+            PrefabMode::Constant { level } => self.load_level(level, build_data),
+            PrefabMode::Sectional { section } => self.apply_sectional(section, build_data),
+            PrefabMode::RoomVaults => self.apply_room_vaults(rng, build_data),
+        }
+    }
+
+    /// The level is exactly as big as its template, whatever size the chain asked for
+    fn load_level(&mut self, level: PrefabLevel, build_data: &mut BuilderMap) {
+        let template = Template::read(level.template);
+        build_data.map = Map::new(template.width, template.height, build_data.map.depth);
+        stamp(&template, 0, 0, build_data);
+    }
+
+    fn apply_sectional(&mut self, section: PrefabSection, build_data: &mut BuilderMap) {
+        let template = Template::read(section.template);
+        let map = &build_data.map;
+        let x = match section.placement.0 {
+            HorizontalPlacement::Left => 1,
+            HorizontalPlacement::Center => (map.width / 2) - (template.width / 2),
+            HorizontalPlacement::Right => (map.width - 1) - template.width,
+        };
+        let y = match section.placement.1 {
+            VerticalPlacement::Top => 1,
+            VerticalPlacement::Center => (map.height / 2) - (template.height / 2),
+            VerticalPlacement::Bottom => (map.height - 1) - template.height,
+        };
+
+        // Whatever was going to spawn under the section is replaced by what the section holds
+        let footprint = Rect::new(x, y, template.width - 1, template.height - 1);
+        let width = map.width;
+        build_data.spawn_list.retain(|(idx, _)| {
+            let (spawn_x, spawn_y) = (*idx as i32 % width, *idx as i32 / width);
+            spawn_x < footprint.x1
+                || spawn_x > footprint.x2
+                || spawn_y < footprint.y1
+                || spawn_y > footprint.y2
+        });
+
+        stamp(&template, x, y, build_data);
+    }
+
+    fn apply_room_vaults(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let depth = build_data.map.depth;
+        let mut possible_vaults: Vec<PrefabRoom> = MASTER_VAULT_LIST
+            .iter()
+            .filter(|vault| depth >= vault.first_depth && depth <= vault.last_depth)
+            .cloned()
+            .collect();
+        if possible_vaults.is_empty() {
+            return;
+        }
+
+        let n_vaults = i32::min(rng.roll_dice(1, 3), possible_vaults.len() as i32);
+        let mut used_tiles: HashSet<usize> = HashSet::new();
+        for _ in 0..n_vaults {
+            let vault_index = (rng.roll_dice(1, possible_vaults.len() as i32) - 1) as usize;
+            let vault = possible_vaults.remove(vault_index);
+            let template = Template::read(vault.template);
+
+            let candidates = self.vault_positions(&template, build_data, &used_tiles);
+            if candidates.is_empty() {
+                continue;
+            }
+            let (x, y) = candidates[(rng.roll_dice(1, candidates.len() as i32) - 1) as usize];
+            for ty in 0..template.height {
+                for tx in 0..template.width {
+                    used_tiles.insert(build_data.map.xy_idx(x + tx, y + ty));
+                }
+            }
+            stamp(&template, x, y, build_data);
+            build_data.take_snapshot();
+        }
+    }
+
+    /// Every top-left corner where the vault would sit entirely on open floor, clear of the start,
+    /// existing spawns and other vaults. When the level has rooms, vaults only go inside them.
+    fn vault_positions(
+        &self,
+        template: &Template,
+        build_data: &BuilderMap,
+        used_tiles: &HashSet<usize>,
+    ) -> Vec<(i32, i32)> {
+        let map = &build_data.map;
+        let areas: Vec<Rect> = match &build_data.rooms {
+            Some(rooms) => rooms
+                .iter()
+                .map(|room| {
+                    Rect::new(
+                        room.x1 + 1,
+                        room.y1 + 1,
+                        room.x2 - room.x1 - 1,
+                        room.y2 - room.y1 - 1,
+                    )
+                })
+                .collect(),
+            None => vec![Rect::new(1, 1, map.width - 3, map.height - 3)],
+        };
+        let start_idx = build_data
+            .starting_position
+            .as_ref()
+            .map(|start| map.xy_idx(start.x, start.y));
+        let spawn_tiles: HashSet<usize> =
+            build_data.spawn_list.iter().map(|spawn| spawn.0).collect();
+
+        let fits = |x: i32, y: i32| {
+            (0..template.height).all(|ty| {
+                (0..template.width).all(|tx| {
+                    let idx = map.xy_idx(x + tx, y + ty);
+                    map.tiles[idx] == TileType::Floor
+                        && Some(idx) != start_idx
+                        && !spawn_tiles.contains(&idx)
+                        && !used_tiles.contains(&idx)
+                })
+            })
+        };
+
+        let mut candidates = Vec::new();
+        for area in areas.iter() {
+            for y in area.y1..=area.y2 - template.height + 1 {
+                for x in area.x1..=area.x2 - template.width + 1 {
+                    if fits(x, y) {
+                        candidates.push((x, y));
+                    }
+                }
+            }
+        }
+        candidates
+    }
+}
+
+/// Entity glyphs every template understands without a legend
+const DEFAULT_LEGEND: [(char, &str); 3] = [('g', "Goblin"), ('o', "Orc"), ('!', "Health Potion")];
+
+/// A rectangular block of template glyphs; short lines are padded out with floor
+struct Template {
+    width: i32,
+    height: i32,
+    glyphs: Vec<char>,
+    /// Glyphs this template spawns things with, and the spawner names they stand for
+    legend: Vec<(char, String)>,
+}
+
+impl Template {
+    fn read(text: &str) -> Template {
+        let mut lines: Vec<&str> = text.lines().collect();
+        let mut legend = Vec::new();
+        if let Some(blank) = lines.iter().position(|line| line.trim().is_empty()) {
+            for line in lines.drain(..=blank).take(blank) {
+                let mut chars = line.chars();
+                match (chars.next(), chars.next(), chars.as_str().trim()) {
+                    (Some(glyph), Some('='), name) if !name.is_empty() => {
+                        legend.push((glyph, name.to_string()))
+                    }
+                    _ => panic!("Bad legend line '{}' in a prefab template", line),
+                }
+            }
+        }
+
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut glyphs = Vec::with_capacity(width * lines.len());
+        for line in lines.iter() {
+            let mut row: Vec<char> = line.chars().collect();
+            row.resize(width, ' ');
+            glyphs.append(&mut row);
+        }
+
+        Template {
+            width: width as i32,
+            height: lines.len() as i32,
+            glyphs,
+            legend,
+        }
+    }
+}
+
+/// Copies a template onto the map with its top-left corner at (x, y), queueing up any entities it
+/// names for the spawner. Anything falling off the map is ignored.
+fn stamp(template: &Template, x: i32, y: i32, build_data: &mut BuilderMap) {
+    for ty in 0..template.height {
+        for tx in 0..template.width {
+            let (map_x, map_y) = (x + tx, y + ty);
+            if map_x < 0
+                || map_x >= build_data.map.width
+                || map_y < 0
+                || map_y >= build_data.map.height
+            {
+                continue;
+            }
+            let idx = build_data.map.xy_idx(map_x, map_y);
+            let glyph = template.glyphs[(ty * template.width + tx) as usize];
+            char_to_map(glyph, idx, &template.legend, build_data);
+        }
+    }
+}
+
+fn char_to_map(glyph: char, idx: usize, legend: &[(char, String)], build_data: &mut BuilderMap) {
+    let entity = legend
+        .iter()
+        .map(|(key, name)| (*key, name.as_str()))
+        .chain(DEFAULT_LEGEND)
+        .find(|(key, _)| *key == glyph)
+        .map(|(_, name)| name);
+    let (tile, spawn) = match (glyph, entity) {
+        (_, Some(name)) => (TileType::Floor, Some(name)),
+        (' ', None) => (TileType::Floor, None),
+        ('+', None) => (TileType::Door, Some("Door")),
+        (_, None) => match TileType::from_ascii(glyph) {
+            Some(tile) => (tile, None),
+            None => panic!("Unknown glyph '{}' in a prefab template", glyph),
+        },
+    };
+    build_data.map.tiles[idx] = tile;
+    if let Some(name) = spawn {
+        build_data.spawn_list.push((idx, name.to_string()));
+    }
+}
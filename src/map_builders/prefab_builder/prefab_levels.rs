@@ -0,0 +1,10 @@
+/// A hand-authored level that fills the whole map
+#[derive(PartialEq, Copy, Clone)]
+pub struct PrefabLevel {
+    pub template: &'static str,
+}
+
+/// An orc stronghold ringed by a courtyard, with the stairs in its north-east hall
+pub const ORC_KEEP: PrefabLevel = PrefabLevel {
+    template: include_str!("../../../resources/prefabs/orc_keep.txt"),
+};
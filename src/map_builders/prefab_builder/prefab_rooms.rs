@@ -0,0 +1,29 @@
+/// A small hand-authored vault, dropped into open floor on levels between `first_depth` and
+/// `last_depth`. Vaults keep a ring of floor around their edge, so placing one never cuts off the
+/// tiles around it.
+#[derive(PartialEq, Copy, Clone)]
+pub struct PrefabRoom {
+    pub template: &'static str,
+    pub first_depth: i32,
+    pub last_depth: i32,
+}
+
+pub const GOBLIN_DEN: PrefabRoom = PrefabRoom {
+    template: include_str!("../../../resources/prefabs/goblin_den.txt"),
+    first_depth: 1,
+    last_depth: 6,
+};
+
+pub const GUARD_POST: PrefabRoom = PrefabRoom {
+    template: include_str!("../../../resources/prefabs/guard_post.txt"),
+    first_depth: 2,
+    last_depth: 100,
+};
+
+pub const PILLARED_CACHE: PrefabRoom = PrefabRoom {
+    template: include_str!("../../../resources/prefabs/pillared_cache.txt"),
+    first_depth: 1,
+    last_depth: 100,
+};
+
+pub const MASTER_VAULT_LIST: [PrefabRoom; 3] = [GOBLIN_DEN, GUARD_POST, PILLARED_CACHE];
@@ -0,0 +1,38 @@
+#[derive(PartialEq, Copy, Clone)]
+pub enum HorizontalPlacement {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum VerticalPlacement {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// A hand-authored chunk stamped over part of a generated map, anchored to one of its edges
+#[derive(PartialEq, Copy, Clone)]
+pub struct PrefabSection {
+    pub template: &'static str,
+    pub placement: (HorizontalPlacement, VerticalPlacement),
+}
+
+/// A palisaded fort against the east edge of the map
+pub const ORC_FORT: PrefabSection = PrefabSection {
+    template: include_str!("../../../resources/prefabs/orc_fort.txt"),
+    placement: (HorizontalPlacement::Right, VerticalPlacement::Center),
+};
+
+/// A lookout in the north-west corner
+pub const WATCHTOWER: PrefabSection = PrefabSection {
+    template: include_str!("../../../resources/prefabs/watchtower.txt"),
+    placement: (HorizontalPlacement::Left, VerticalPlacement::Top),
+};
+
+/// Goblin barracks against the south edge of the map
+pub const BARRACKS: PrefabSection = PrefabSection {
+    template: include_str!("../../../resources/prefabs/barracks.txt"),
+    placement: (HorizontalPlacement::Center, VerticalPlacement::Bottom),
+};
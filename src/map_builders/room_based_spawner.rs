@@ -0,0 +1,29 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::spawner;
+
+/// Spawns a handful of monsters in every room except the first, where the player usually starts
+pub struct RoomBasedSpawner {}
+
+impl MetaMapBuilder for RoomBasedSpawner {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl RoomBasedSpawner {
+    pub fn new() -> Box<RoomBasedSpawner> {
+        Box::new(RoomBasedSpawner {})
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let rooms = build_data
+            .rooms
+            .as_ref()
+            .expect("Room based spawning needs rooms");
+        for room in rooms.iter().skip(1) {
+            spawner::spawn_room(&build_data.map, rng, room, &mut build_data.spawn_list);
+        }
+    }
+}
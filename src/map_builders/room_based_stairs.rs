@@ -0,0 +1,37 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Puts the down stairs in the middle of the last room that is still open floor
+pub struct RoomBasedStairs {}
+
+impl MetaMapBuilder for RoomBasedStairs {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl RoomBasedStairs {
+    pub fn new() -> Box<RoomBasedStairs> {
+        Box::new(RoomBasedStairs {})
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        let rooms = build_data
+            .rooms
+            .as_ref()
+            .expect("Room based stairs need rooms");
+        // Culling may have filled in the last few rooms, so work back to one the player can reach
+        let stairs_idx = rooms
+            .iter()
+            .rev()
+            .map(|room| {
+                let (x, y) = room.center();
+                build_data.map.xy_idx(x, y)
+            })
+            .find(|idx| build_data.map.tiles[*idx] == TileType::Floor)
+            .expect("No room left to put the stairs in");
+        build_data.map.tiles[stairs_idx] = TileType::DownStairs;
+    }
+}
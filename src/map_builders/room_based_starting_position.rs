@@ -0,0 +1,28 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::Position;
+
+/// Starts the player in the middle of the first room
+pub struct RoomBasedStartingPosition {}
+
+impl MetaMapBuilder for RoomBasedStartingPosition {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl RoomBasedStartingPosition {
+    pub fn new() -> Box<RoomBasedStartingPosition> {
+        Box::new(RoomBasedStartingPosition {})
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        let rooms = build_data
+            .rooms
+            .as_ref()
+            .expect("Room based starting position needs rooms");
+        let (x, y) = rooms[0].center();
+        build_data.starting_position = Some(Position { x, y });
+    }
+}
@@ -0,0 +1,50 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Fills in the corners of each room so they read as rounded chambers rather than boxes
+pub struct RoomCornerRounder {}
+
+impl MetaMapBuilder for RoomCornerRounder {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl RoomCornerRounder {
+    pub fn new() -> Box<RoomCornerRounder> {
+        Box::new(RoomCornerRounder {})
+    }
+
+    fn fill_if_corner(&mut self, x: i32, y: i32, build_data: &mut BuilderMap) {
+        let map = &mut build_data.map;
+        if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 {
+            return;
+        }
+        let idx = map.xy_idx(x, y);
+        let width = map.width as usize;
+        let neighbor_walls = [idx - 1, idx + 1, idx - width, idx + width]
+            .iter()
+            .filter(|i| map.tiles[**i] == TileType::Wall)
+            .count();
+
+        if neighbor_walls == 2 {
+            map.tiles[idx] = TileType::Wall;
+        }
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        let rooms = build_data
+            .rooms
+            .clone()
+            .expect("Room corner rounding needs rooms");
+
+        for room in rooms.iter() {
+            self.fill_if_corner(room.x1 + 1, room.y1 + 1, build_data);
+            self.fill_if_corner(room.x2, room.y1 + 1, build_data);
+            self.fill_if_corner(room.x1 + 1, room.y2, build_data);
+            self.fill_if_corner(room.x2, room.y2, build_data);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{
+    common::{apply_horizontal_tunnel, apply_vertical_tunnel},
+    BuilderMap, MetaMapBuilder,
+};
+
+/// Joins each room to the one built before it with an L-shaped pair of tunnels
+pub struct DoglegCorridors {}
+
+impl MetaMapBuilder for DoglegCorridors {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.corridors(rng, build_data);
+    }
+}
+
+impl DoglegCorridors {
+    pub fn new() -> Box<DoglegCorridors> {
+        Box::new(DoglegCorridors {})
+    }
+
+    fn corridors(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let rooms = build_data
+            .rooms
+            .clone()
+            .expect("Dogleg corridors need rooms");
+
+        let mut corridors: Vec<Vec<usize>> = Vec::new();
+        for (room, previous) in rooms.iter().skip(1).zip(rooms.iter()) {
+            let (new_x, new_y) = room.center();
+            let (prev_x, prev_y) = previous.center();
+            let mut corridor;
+            if rng.range(0, 2) == 1 {
+                corridor = apply_horizontal_tunnel(&mut build_data.map, prev_x, new_x, prev_y);
+                corridor.append(&mut apply_vertical_tunnel(
+                    &mut build_data.map,
+                    prev_y,
+                    new_y,
+                    new_x,
+                ));
+            } else {
+                corridor = apply_vertical_tunnel(&mut build_data.map, prev_y, new_y, prev_x);
+                corridor.append(&mut apply_horizontal_tunnel(
+                    &mut build_data.map,
+                    prev_x,
+                    new_x,
+                    new_y,
+                ));
+            }
+            corridors.push(corridor);
+            build_data.take_snapshot();
+        }
+
+        build_data.corridors = Some(corridors);
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use bracket_lib::{
+    random::RandomNumberGenerator,
+    terminal::{DistanceAlg, Point},
+};
+
+use super::{common::draw_corridor, BuilderMap, MetaMapBuilder};
+
+/// Joins each room to its nearest neighbours, skipping pairs that are already connected
+pub struct NearestCorridors {}
+
+impl MetaMapBuilder for NearestCorridors {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.corridors(build_data);
+    }
+}
+
+impl NearestCorridors {
+    pub fn new() -> Box<NearestCorridors> {
+        Box::new(NearestCorridors {})
+    }
+
+    fn corridors(&mut self, build_data: &mut BuilderMap) {
+        const MAX_NEIGHBORS: usize = 3;
+        let rooms = build_data
+            .rooms
+            .clone()
+            .expect("Nearest corridors need rooms");
+
+        let mut connected: HashSet<usize> = HashSet::new();
+        let mut corridors: Vec<Vec<usize>> = Vec::new();
+        for (i, room) in rooms.iter().enumerate() {
+            let room_center = room.center();
+            let room_center_pt = Point::new(room_center.0, room_center.1);
+
+            let mut room_distance: Vec<(usize, f32)> = rooms
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i != *j && !connected.contains(j))
+                .map(|(j, other_room)| {
+                    let other_center = other_room.center();
+                    let other_center_pt = Point::new(other_center.0, other_center.1);
+                    (
+                        j,
+                        DistanceAlg::Pythagoras.distance2d(room_center_pt, other_center_pt),
+                    )
+                })
+                .collect();
+            room_distance.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+            for (j, _) in room_distance.iter().take(MAX_NEIGHBORS) {
+                let dest_center = rooms[*j].center();
+                let corridor = draw_corridor(
+                    &mut build_data.map,
+                    room_center.0,
+                    room_center.1,
+                    dest_center.0,
+                    dest_center.1,
+                );
+                corridors.push(corridor);
+                build_data.take_snapshot();
+            }
+            connected.insert(i);
+        }
+
+        build_data.corridors = Some(corridors);
+    }
+}
@@ -0,0 +1,72 @@
+use bracket_lib::{pathfinding::DijkstraMap, random::RandomNumberGenerator};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Sets a shrine down about halfway between the starting position and the furthest reaches of the
+/// level, somewhere nothing else is spawning. Levels whose template already placed a shrine by hand
+/// are left as they are.
+pub struct ShrinePlacement {}
+
+impl MetaMapBuilder for ShrinePlacement {
+    fn build_map(&mut self, _rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(build_data);
+    }
+}
+
+impl ShrinePlacement {
+    pub fn new() -> Box<ShrinePlacement> {
+        Box::new(ShrinePlacement {})
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap) {
+        if build_data
+            .spawn_list
+            .iter()
+            .any(|(_, name)| name == "Shrine")
+        {
+            return;
+        }
+        let start = build_data
+            .starting_position
+            .as_ref()
+            .expect("Placing a shrine needs a starting position");
+        let start_idx = build_data.map.xy_idx(start.x, start.y);
+        build_data.map.populate_blocked();
+        let dijkstra_map = DijkstraMap::new(
+            build_data.map.width,
+            build_data.map.height,
+            &[start_idx],
+            &build_data.map,
+            build_data.map.tiles.len() as f32,
+        );
+
+        let candidates: Vec<(usize, f32)> = build_data
+            .map
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(i, tile)| {
+                tile.walkable()
+                    && **tile != TileType::DownStairs
+                    && *i != start_idx
+                    && dijkstra_map.map[*i] != f32::MAX
+                    && !build_data.spawn_list.iter().any(|(idx, _)| idx == i)
+            })
+            .map(|(i, _)| (i, dijkstra_map.map[i]))
+            .collect();
+        let furthest = candidates
+            .iter()
+            .map(|(_, distance)| *distance)
+            .fold(0.0f32, f32::max);
+
+        let halfway = candidates.iter().min_by(|(_, a), (_, b)| {
+            let off_a = (a - furthest / 2.0).abs();
+            let off_b = (b - furthest / 2.0).abs();
+            off_a.partial_cmp(&off_b).unwrap()
+        });
+        if let Some((idx, _)) = halfway {
+            build_data.spawn_list.push((*idx, "Shrine".to_string()));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use bracket_lib::{noise::FastNoise, noise::NoiseType, random::RandomNumberGenerator};
+
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+
+/// Scatters rough ground over plain floor: rubble and gravel against the walls, patches of grass
+/// and tall grass, and the odd tree or stalactite. Trees and stalactites only go where every tile
+/// around them stays walkable, so they never cut a path off.
+pub struct TerrainDecorator {}
+
+impl MetaMapBuilder for TerrainDecorator {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl TerrainDecorator {
+    pub fn new() -> Box<TerrainDecorator> {
+        Box::new(TerrainDecorator {})
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let mut noise = FastNoise::seeded(rng.roll_dice(1, 65536) as u64);
+        noise.set_noise_type(NoiseType::Simplex);
+        noise.set_frequency(0.1);
+
+        let map = &mut build_data.map;
+        let width = map.width as usize;
+        for y in 1..map.height - 1 {
+            for x in 1..map.width - 1 {
+                let idx = map.xy_idx(x, y);
+                if map.tiles[idx] != TileType::Floor {
+                    continue;
+                }
+
+                let against_wall = [idx - 1, idx + 1, idx - width, idx + width]
+                    .iter()
+                    .any(|i| map.tiles[*i] == TileType::Wall);
+                let growth = noise.get_noise(x as f32, y as f32);
+                map.tiles[idx] = if against_wall && rng.roll_dice(1, 8) == 1 {
+                    TileType::Rubble
+                } else if growth > 0.55 {
+                    TileType::TallGrass
+                } else if growth > 0.25 {
+                    TileType::Grass
+                } else if growth < -0.6 {
+                    TileType::Gravel
+                } else {
+                    TileType::Floor
+                };
+            }
+        }
+        build_data.take_snapshot();
+
+        for y in 2..build_data.map.height - 2 {
+            for x in 2..build_data.map.width - 2 {
+                let idx = build_data.map.xy_idx(x, y);
+                let blocker = match build_data.map.tiles[idx] {
+                    TileType::Grass if rng.roll_dice(1, 10) == 1 => TileType::Tree,
+                    TileType::Floor if rng.roll_dice(1, 40) == 1 => TileType::Stalactite,
+                    _ => continue,
+                };
+                if self.surrounded_by_open_ground(build_data, x, y) {
+                    build_data.map.tiles[idx] = blocker;
+                }
+            }
+        }
+    }
+
+    /// True if all eight neighbors can be walked on, and nothing is set to spawn in the middle
+    fn surrounded_by_open_ground(&self, build_data: &BuilderMap, x: i32, y: i32) -> bool {
+        let map = &build_data.map;
+        let idx = map.xy_idx(x, y);
+        if build_data.spawn_list.iter().any(|spawn| spawn.0 == idx) {
+            return false;
+        }
+        if let Some(start) = &build_data.starting_position {
+            if start.x == x && start.y == y {
+                return false;
+            }
+        }
+        (-1..=1).all(|dy| {
+            (-1..=1)
+                .all(|dx| (dx == 0 && dy == 0) || map.tiles[map.xy_idx(x + dx, y + dy)].walkable())
+        })
+    }
+}
@@ -0,0 +1,303 @@
+use bracket_lib::{pathfinding::DijkstraMap, random::RandomNumberGenerator};
+
+use super::*;
+use crate::TileType;
+
+const SEEDS: u64 = 20;
+
+type Initial = fn() -> Box<dyn InitialMapBuilder>;
+type Meta = fn() -> Box<dyn MetaMapBuilder>;
+
+/// Builds the chain and checks that every walkable tile, the stairs and every spawn can be walked to
+/// from the starting position
+fn assert_connected(mut chain: BuilderChain, rng: &mut RandomNumberGenerator, label: &str) {
+    chain.build_map(rng);
+    let build_data = &mut chain.build_data;
+    let start = build_data
+        .starting_position
+        .clone()
+        .unwrap_or_else(|| panic!("{label}: no starting position"));
+    let start_idx = build_data.map.xy_idx(start.x, start.y);
+    assert!(
+        build_data.map.tiles[start_idx].walkable(),
+        "{label}: the player starts inside a wall"
+    );
+
+    build_data.map.populate_blocked();
+    let dijkstra_map = DijkstraMap::new(
+        build_data.map.width,
+        build_data.map.height,
+        &[start_idx],
+        &build_data.map,
+        build_data.map.tiles.len() as f32,
+    );
+    let reachable = |idx: usize| dijkstra_map.map[idx] < f32::MAX;
+
+    for (idx, tile) in build_data.map.tiles.iter().enumerate() {
+        if tile.walkable() {
+            assert!(reachable(idx), "{label}: {tile:?} at {idx} is cut off");
+        }
+    }
+
+    let stairs = build_data
+        .map
+        .tiles
+        .iter()
+        .filter(|tile| **tile == TileType::DownStairs)
+        .count();
+    assert_eq!(stairs, 1, "{label}: expected exactly one set of stairs");
+
+    for (idx, name) in build_data.spawn_list.iter() {
+        assert!(reachable(*idx), "{label}: {name} at {idx} is cut off");
+    }
+}
+
+/// The same finishing steps `random_shape_builder` puts after its layout
+fn shape_chain(starter: Box<dyn InitialMapBuilder>) -> BuilderChain {
+    let mut chain = BuilderChain::new(1, 80, 43);
+    chain.start_with(starter);
+    chain.with(AreaStartingPosition::new(XStart::Center, YStart::Center));
+    chain.with(CullUnreachable::new());
+    chain.with(DistantExit::new());
+    chain.with(VoronoiSpawning::new());
+    chain
+}
+
+#[test]
+fn shape_builders_are_connected() {
+    let starters: [(&str, Initial); 14] = [
+        ("cellular automata", || CellularAutomataBuilder::new()),
+        ("open area", || DrunkardsWalkBuilder::open_area()),
+        ("open halls", || DrunkardsWalkBuilder::open_halls()),
+        ("winding passages", || {
+            DrunkardsWalkBuilder::winding_passages()
+        }),
+        ("fat passages", || DrunkardsWalkBuilder::fat_passages()),
+        ("fearful symmetry", || {
+            DrunkardsWalkBuilder::fearful_symmetry()
+        }),
+        ("maze", || MazeBuilder::new()),
+        ("dla walk inwards", || DLABuilder::walk_inwards()),
+        ("dla walk outwards", || DLABuilder::walk_outwards()),
+        ("dla central attractor", || DLABuilder::central_attractor()),
+        ("voronoi pythagoras", || VoronoiCellBuilder::pythagoras()),
+        ("voronoi manhattan", || VoronoiCellBuilder::manhattan()),
+        ("voronoi chebyshev", || VoronoiCellBuilder::chebyshev()),
+        ("wfc template", || {
+            WaveformCollapseBuilder::from_template(include_str!(
+                "../../resources/wfc/ruined_halls.txt"
+            ))
+        }),
+    ];
+
+    for (name, starter) in starters.iter() {
+        for seed in 0..SEEDS {
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            assert_connected(
+                shape_chain(starter()),
+                &mut rng,
+                &format!("{name}, seed {seed}"),
+            );
+        }
+    }
+}
+
+#[test]
+fn derived_wfc_is_connected() {
+    for seed in 0..SEEDS {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let mut chain = shape_chain(CellularAutomataBuilder::new());
+        chain
+            .builders
+            .insert(0, WaveformCollapseBuilder::derived_map());
+        assert_connected(chain, &mut rng, &format!("derived wfc, seed {seed}"));
+    }
+}
+
+#[test]
+fn room_builders_are_connected() {
+    let corridors: [(&str, Meta); 3] = [
+        ("dogleg", || DoglegCorridors::new()),
+        ("nearest", || NearestCorridors::new()),
+        ("bsp", || BspCorridors::new()),
+    ];
+    let roughening: [(&str, Option<Meta>); 3] = [
+        ("plain", None),
+        ("cellular", Some(|| CellularAutomataBuilder::new())),
+        ("rounded", Some(|| RoomCornerRounder::new())),
+    ];
+
+    for bsp in [false, true] {
+        for (corridor_name, corridor) in corridors.iter() {
+            for (rough_name, rough) in roughening.iter() {
+                for seed in 0..SEEDS {
+                    let mut rng = RandomNumberGenerator::seeded(seed);
+                    let mut chain = BuilderChain::new(1, 80, 43);
+                    if bsp {
+                        chain.start_with(BspDungeonBuilder::new());
+                    } else {
+                        chain.start_with(SimpleMapBuilder::new());
+                    }
+                    if let Some(rough) = rough {
+                        chain.with(rough());
+                    }
+                    chain.with(corridor());
+                    chain.with(RoomBasedStartingPosition::new());
+                    chain.with(CullUnreachable::new());
+                    chain.with(DoorPlacement::new());
+                    chain.with(RoomBasedStairs::new());
+                    chain.with(RoomBasedSpawner::new());
+                    let label =
+                        format!("rooms (bsp: {bsp}), {rough_name}, {corridor_name}, seed {seed}");
+                    assert_connected(chain, &mut rng, &label);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn random_builders_are_connected() {
+    for seed in 0..SEEDS * 10 {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let chain = random_builder(1, 80, 43, &mut rng);
+        assert_connected(chain, &mut rng, &format!("random builder, seed {seed}"));
+    }
+}
+
+#[test]
+fn random_builders_place_one_shrine() {
+    for seed in 0..SEEDS {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let mut chain = random_builder(1, 80, 43, &mut rng);
+        chain.build_map(&mut rng);
+        let shrines: Vec<usize> = chain
+            .build_data
+            .spawn_list
+            .iter()
+            .filter(|(_, name)| name == "Shrine")
+            .map(|(idx, _)| *idx)
+            .collect();
+        assert_eq!(shrines.len(), 1, "seed {seed}: expected exactly one shrine");
+        assert!(
+            chain.build_data.map.tiles[shrines[0]].walkable(),
+            "seed {seed}: the shrine is inside a wall"
+        );
+    }
+}
+
+#[test]
+fn prefab_level_is_connected() {
+    for seed in 0..SEEDS {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let mut chain = BuilderChain::new(1, 80, 43);
+        prefab_level_builder(&mut chain);
+        assert_connected(chain, &mut rng, &format!("orc keep, seed {seed}"));
+    }
+}
+
+#[test]
+fn prefab_legends_spawn_what_they_name() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut chain = named_builder("keep", 1, 80, 43, &mut rng).unwrap();
+    chain.build_map(&mut rng);
+    let build_data = &chain.build_data;
+
+    // The keep's legend has `S=Shrine`, which stands in for the shrine the chain would place, and
+    // `B=Orc Warlord` for its boss; its goblins still come from the built-in glyphs
+    let shrines: Vec<usize> = build_data
+        .spawn_list
+        .iter()
+        .filter(|(_, name)| name == "Shrine")
+        .map(|(idx, _)| *idx)
+        .collect();
+    assert_eq!(shrines, vec![build_data.map.xy_idx(27, 24)]);
+    assert_eq!(build_data.map.tiles[shrines[0]], TileType::Floor);
+    assert!(build_data
+        .spawn_list
+        .iter()
+        .any(|(_, name)| name == "Goblin"));
+    assert!(build_data
+        .spawn_list
+        .iter()
+        .any(|(_, name)| name == "Orc Warlord"));
+}
+
+#[test]
+fn prefab_sections_and_vaults_are_connected() {
+    let sections = [
+        ("orc fort", ORC_FORT),
+        ("watchtower", WATCHTOWER),
+        ("barracks", BARRACKS),
+    ];
+    for (name, section) in sections.iter() {
+        for seed in 0..SEEDS {
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let mut chain = shape_chain(CellularAutomataBuilder::new());
+            chain.builders.insert(0, PrefabBuilder::sectional(*section));
+            assert_connected(chain, &mut rng, &format!("{name}, seed {seed}"));
+        }
+    }
+
+    for depth in [1, 5, 10] {
+        for seed in 0..SEEDS {
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let mut chain = shape_chain(DrunkardsWalkBuilder::open_area());
+            chain.build_data.map.depth = depth;
+            chain.builders.insert(3, PrefabBuilder::vaults());
+            assert_connected(chain, &mut rng, &format!("cave vaults, seed {seed}"));
+
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let mut chain = BuilderChain::new(depth, 80, 43);
+            chain.start_with(SimpleMapBuilder::new());
+            chain.with(DoglegCorridors::new());
+            chain.with(RoomBasedStartingPosition::new());
+            chain.with(CullUnreachable::new());
+            chain.with(RoomBasedStairs::new());
+            chain.with(PrefabBuilder::vaults());
+            chain.with(RoomBasedSpawner::new());
+            assert_connected(chain, &mut rng, &format!("room vaults, seed {seed}"));
+        }
+    }
+}
+
+#[test]
+fn pools_terrain_and_lights_are_connected() {
+    for depth in [1, 6] {
+        for seed in 0..SEEDS {
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let mut chain = shape_chain(CellularAutomataBuilder::new());
+            chain.build_data.map.depth = depth;
+            chain.builders.insert(1, PoolPlacement::new());
+            chain.with(DarkLevel::new());
+            chain.with(TerrainDecorator::new());
+            assert_connected(chain, &mut rng, &format!("cave terrain, seed {seed}"));
+
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let mut chain = BuilderChain::new(depth, 80, 43);
+            chain.start_with(BspDungeonBuilder::new());
+            chain.with(BspCorridors::new());
+            chain.with(RoomBasedStartingPosition::new());
+            chain.with(PoolPlacement::new());
+            chain.with(CullUnreachable::new());
+            chain.with(DoorPlacement::new());
+            chain.with(RoomBasedStairs::new());
+            chain.with(RoomBasedSpawner::new());
+            chain.with(DarkLevel::new());
+            chain.with(TerrainDecorator::new());
+            assert_connected(chain, &mut rng, &format!("room terrain, seed {seed}"));
+        }
+    }
+}
+
+#[test]
+fn random_builders_fit_any_size() {
+    for (width, height) in [(50, 30), (120, 40), (60, 90), level_size(12)] {
+        for seed in 0..SEEDS {
+            let mut rng = RandomNumberGenerator::seeded(seed);
+            let chain = random_builder(3, width, height, &mut rng);
+            let label = format!("random builder at {width}x{height}, seed {seed}");
+            assert_connected(chain, &mut rng, &label);
+        }
+    }
+}
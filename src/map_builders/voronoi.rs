@@ -0,0 +1,103 @@
+use bracket_lib::{
+    random::RandomNumberGenerator,
+    terminal::{DistanceAlg, Point},
+};
+
+use super::{BuilderMap, InitialMapBuilder};
+use crate::{Map, TileType};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum DistanceAlgorithm {
+    Pythagoras,
+    Manhattan,
+    Chebyshev,
+}
+
+pub struct VoronoiCellBuilder {
+    n_seeds: usize,
+    distance_algorithm: DistanceAlgorithm,
+}
+
+impl InitialMapBuilder for VoronoiCellBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, &mut build_data.map);
+    }
+}
+
+impl VoronoiCellBuilder {
+    fn new(distance_algorithm: DistanceAlgorithm) -> Box<VoronoiCellBuilder> {
+        Box::new(VoronoiCellBuilder {
+            n_seeds: 64,
+            distance_algorithm,
+        })
+    }
+
+    /// Round, organic looking cells
+    pub fn pythagoras() -> Box<VoronoiCellBuilder> {
+        VoronoiCellBuilder::new(DistanceAlgorithm::Pythagoras)
+    }
+
+    /// Diamond shaped cells with long diagonal walls
+    pub fn manhattan() -> Box<VoronoiCellBuilder> {
+        VoronoiCellBuilder::new(DistanceAlgorithm::Manhattan)
+    }
+
+    /// Boxy cells that look more like built rooms
+    pub fn chebyshev() -> Box<VoronoiCellBuilder> {
+        VoronoiCellBuilder::new(DistanceAlgorithm::Chebyshev)
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, map: &mut Map) {
+        // Scatter the seeds that each cell grows out of
+        let mut voronoi_seeds: Vec<Point> = Vec::new();
+        while voronoi_seeds.len() < self.n_seeds {
+            let vx = rng.roll_dice(1, map.width - 1);
+            let vy = rng.roll_dice(1, map.height - 1);
+            let candidate = Point::new(vx, vy);
+            if !voronoi_seeds.contains(&candidate) {
+                voronoi_seeds.push(candidate);
+            }
+        }
+
+        // Every tile belongs to whichever seed is closest
+        let distance = match self.distance_algorithm {
+            DistanceAlgorithm::Pythagoras => DistanceAlg::PythagorasSquared,
+            DistanceAlgorithm::Manhattan => DistanceAlg::Manhattan,
+            DistanceAlgorithm::Chebyshev => DistanceAlg::Chebyshev,
+        };
+        let mut voronoi_membership: Vec<usize> = vec![0; map.tiles.len()];
+        for (i, membership) in voronoi_membership.iter_mut().enumerate() {
+            let x = i as i32 % map.width;
+            let y = i as i32 / map.width;
+            let tile = Point::new(x, y);
+            *membership = voronoi_seeds
+                .iter()
+                .enumerate()
+                .map(|(seed, pos)| (seed, distance.distance2d(tile, *pos)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .map(|(seed, _)| seed)
+                .unwrap();
+        }
+
+        // Walls go wherever two cells meet
+        for y in 1..map.height - 1 {
+            for x in 1..map.width - 1 {
+                let my_idx = map.xy_idx(x, y);
+                let my_seed = voronoi_membership[my_idx];
+                let neighbors = [
+                    map.xy_idx(x - 1, y),
+                    map.xy_idx(x + 1, y),
+                    map.xy_idx(x, y - 1),
+                    map.xy_idx(x, y + 1),
+                ]
+                .iter()
+                .filter(|idx| voronoi_membership[**idx] != my_seed)
+                .count();
+
+                if neighbors < 2 {
+                    map.tiles[my_idx] = TileType::Floor;
+                }
+            }
+        }
+    }
+}
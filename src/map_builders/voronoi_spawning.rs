@@ -0,0 +1,26 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{common::generate_voronoi_spawn_regions, BuilderMap, MetaMapBuilder};
+use crate::spawner;
+
+/// Splits the floor into noise regions and spawns a group in each, for maps without rooms
+pub struct VoronoiSpawning {}
+
+impl MetaMapBuilder for VoronoiSpawning {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        self.build(rng, build_data);
+    }
+}
+
+impl VoronoiSpawning {
+    pub fn new() -> Box<VoronoiSpawning> {
+        Box::new(VoronoiSpawning {})
+    }
+
+    fn build(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let noise_areas = generate_voronoi_spawn_regions(&build_data.map, rng);
+        for area in noise_areas.iter() {
+            spawner::spawn_region(rng, area.1, &mut build_data.spawn_list);
+        }
+    }
+}
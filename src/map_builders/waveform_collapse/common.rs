@@ -0,0 +1,29 @@
+use crate::TileType;
+
+/// A chunk-sized pattern along with what it can sit beside. Directions are indexed north, south,
+/// west and east.
+#[derive(Clone)]
+pub struct MapChunk {
+    pub pattern: Vec<TileType>,
+    pub exits: [Vec<bool>; 4],
+    pub has_exits: bool,
+    pub compatible_with: [Vec<usize>; 4],
+}
+
+pub const NORTH: usize = 0;
+pub const SOUTH: usize = 1;
+pub const WEST: usize = 2;
+pub const EAST: usize = 3;
+
+pub fn opposite(direction: usize) -> usize {
+    match direction {
+        NORTH => SOUTH,
+        SOUTH => NORTH,
+        WEST => EAST,
+        _ => WEST,
+    }
+}
+
+pub fn tile_idx_in_chunk(chunk_size: i32, x: i32, y: i32) -> usize {
+    ((y * chunk_size) + x) as usize
+}
@@ -0,0 +1,159 @@
+use super::common::{opposite, tile_idx_in_chunk, MapChunk, EAST, NORTH, SOUTH, WEST};
+use crate::{Map, TileType};
+
+/// Cuts the map into chunk_size squares and returns every distinct pattern found, optionally
+/// along with their rotations and reflections
+pub fn build_patterns(
+    map: &Map,
+    chunk_size: i32,
+    include_rotation: bool,
+    include_reflection: bool,
+) -> Vec<Vec<TileType>> {
+    let chunks_x = map.width / chunk_size;
+    let chunks_y = map.height / chunk_size;
+    let mut patterns: Vec<Vec<TileType>> = Vec::new();
+
+    for cy in 0..chunks_y {
+        for cx in 0..chunks_x {
+            let start_x = cx * chunk_size;
+            let start_y = cy * chunk_size;
+
+            let mut base = Vec::new();
+            for y in start_y..start_y + chunk_size {
+                for x in start_x..start_x + chunk_size {
+                    // Only the shape matters here; anything walkable becomes plain floor
+                    let idx = map.xy_idx(x, y);
+                    base.push(match map.tiles[idx] {
+                        TileType::Wall => TileType::Wall,
+                        _ => TileType::Floor,
+                    });
+                }
+            }
+
+            let mut variants = vec![base];
+            if include_rotation {
+                for _ in 0..3 {
+                    let rotated = rotate(variants.last().unwrap(), chunk_size);
+                    variants.push(rotated);
+                }
+            }
+            if include_reflection {
+                let reflected: Vec<Vec<TileType>> = variants
+                    .iter()
+                    .flat_map(|pattern| {
+                        let horizontal = flip_horizontal(pattern, chunk_size);
+                        let both = flip_vertical(&horizontal, chunk_size);
+                        [horizontal, flip_vertical(pattern, chunk_size), both]
+                    })
+                    .collect();
+                variants.extend(reflected);
+            }
+
+            for pattern in variants {
+                if !patterns.contains(&pattern) {
+                    patterns.push(pattern);
+                }
+            }
+        }
+    }
+
+    patterns
+}
+
+fn rotate(pattern: &[TileType], chunk_size: i32) -> Vec<TileType> {
+    let mut rotated = Vec::with_capacity(pattern.len());
+    for y in 0..chunk_size {
+        for x in 0..chunk_size {
+            rotated.push(pattern[tile_idx_in_chunk(chunk_size, y, chunk_size - 1 - x)]);
+        }
+    }
+    rotated
+}
+
+fn flip_horizontal(pattern: &[TileType], chunk_size: i32) -> Vec<TileType> {
+    let mut flipped = Vec::with_capacity(pattern.len());
+    for y in 0..chunk_size {
+        for x in 0..chunk_size {
+            flipped.push(pattern[tile_idx_in_chunk(chunk_size, chunk_size - 1 - x, y)]);
+        }
+    }
+    flipped
+}
+
+fn flip_vertical(pattern: &[TileType], chunk_size: i32) -> Vec<TileType> {
+    let mut flipped = Vec::with_capacity(pattern.len());
+    for y in 0..chunk_size {
+        for x in 0..chunk_size {
+            flipped.push(pattern[tile_idx_in_chunk(chunk_size, x, chunk_size - 1 - y)]);
+        }
+    }
+    flipped
+}
+
+/// Works out each pattern's open edges, and which patterns can sit next to it in every direction
+pub fn patterns_to_constraints(patterns: Vec<Vec<TileType>>, chunk_size: i32) -> Vec<MapChunk> {
+    let mut constraints: Vec<MapChunk> = patterns
+        .into_iter()
+        .map(|pattern| {
+            let mut exits: [Vec<bool>; 4] = [
+                vec![false; chunk_size as usize],
+                vec![false; chunk_size as usize],
+                vec![false; chunk_size as usize],
+                vec![false; chunk_size as usize],
+            ];
+            for i in 0..chunk_size {
+                let slot = i as usize;
+                exits[NORTH][slot] =
+                    pattern[tile_idx_in_chunk(chunk_size, i, 0)] == TileType::Floor;
+                exits[SOUTH][slot] =
+                    pattern[tile_idx_in_chunk(chunk_size, i, chunk_size - 1)] == TileType::Floor;
+                exits[WEST][slot] = pattern[tile_idx_in_chunk(chunk_size, 0, i)] == TileType::Floor;
+                exits[EAST][slot] =
+                    pattern[tile_idx_in_chunk(chunk_size, chunk_size - 1, i)] == TileType::Floor;
+            }
+            let has_exits = exits.iter().any(|side| side.iter().any(|open| *open));
+
+            MapChunk {
+                pattern,
+                exits,
+                has_exits,
+                compatible_with: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            }
+        })
+        .collect();
+
+    let all_exits: Vec<[Vec<bool>; 4]> = constraints.iter().map(|c| c.exits.clone()).collect();
+    for chunk in constraints.iter_mut() {
+        for (j, potential) in all_exits.iter().enumerate() {
+            if !chunk.has_exits {
+                // Solid rock can go beside anything
+                for compatible in chunk.compatible_with.iter_mut() {
+                    compatible.push(j);
+                }
+                continue;
+            }
+
+            for direction in 0..4 {
+                let facing = &potential[opposite(direction)];
+                let exits = &chunk.exits[direction];
+                let any_open = exits.iter().any(|open| *open);
+
+                // Open edges need at least one passage lined up on the other side; closed edges
+                // only sit against other closed edges
+                let fits = if any_open {
+                    exits
+                        .iter()
+                        .zip(facing.iter())
+                        .any(|(mine, theirs)| *mine && *theirs)
+                } else {
+                    !facing.iter().any(|open| *open)
+                };
+                if fits {
+                    chunk.compatible_with[direction].push(j);
+                }
+            }
+        }
+    }
+
+    constraints
+}
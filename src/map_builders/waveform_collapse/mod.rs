@@ -0,0 +1,142 @@
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::{BuilderMap, InitialMapBuilder, MetaMapBuilder};
+use crate::{Map, TileType};
+mod common;
+mod constraints;
+use constraints::*;
+mod solver;
+use solver::*;
+
+/// How many times to restart the solver after running into a contradiction
+const MAX_ATTEMPTS: i32 = 10;
+
+pub struct WaveformCollapseBuilder {
+    template: Option<&'static str>,
+    chunk_size: i32,
+    include_rotation: bool,
+    include_reflection: bool,
+}
+
+/// Learns its patterns from a hand-drawn ASCII map
+impl InitialMapBuilder for WaveformCollapseBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let template = self
+            .template
+            .expect("A waveform collapse starting builder needs a template");
+        let source_map = load_template(template, build_data.map.depth);
+        self.build(rng, build_data, source_map);
+    }
+}
+
+/// Learns its patterns from whatever the earlier builders in the chain produced
+impl MetaMapBuilder for WaveformCollapseBuilder {
+    fn build_map(&mut self, rng: &mut RandomNumberGenerator, build_data: &mut BuilderMap) {
+        let source_map = build_data.map.clone();
+        self.build(rng, build_data, source_map);
+    }
+}
+
+impl WaveformCollapseBuilder {
+    pub fn new(
+        template: Option<&'static str>,
+        chunk_size: i32,
+        include_rotation: bool,
+        include_reflection: bool,
+    ) -> Box<WaveformCollapseBuilder> {
+        Box::new(WaveformCollapseBuilder {
+            template,
+            chunk_size,
+            include_rotation,
+            include_reflection,
+        })
+    }
+
+    pub fn derived_map() -> Box<WaveformCollapseBuilder> {
+        WaveformCollapseBuilder::new(None, 8, false, true)
+    }
+
+    pub fn from_template(template: &'static str) -> Box<WaveformCollapseBuilder> {
+        WaveformCollapseBuilder::new(Some(template), 6, true, true)
+    }
+
+    fn build(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        build_data: &mut BuilderMap,
+        source_map: Map,
+    ) {
+        let patterns = build_patterns(
+            &source_map,
+            self.chunk_size,
+            self.include_rotation,
+            self.include_reflection,
+        );
+        let constraints = patterns_to_constraints(patterns, self.chunk_size);
+
+        let mut solved = false;
+        for _attempt in 0..MAX_ATTEMPTS {
+            build_data.map = Map::new(
+                build_data.map.width,
+                build_data.map.height,
+                build_data.map.depth,
+            );
+            let mut solver = Solver::new(constraints.clone(), self.chunk_size, &build_data.map);
+            while !solver.iteration(&mut build_data.map, rng) {
+                build_data.take_snapshot();
+            }
+            if solver.possible && build_data.map.tiles.contains(&TileType::Floor) {
+                solved = true;
+                break;
+            }
+        }
+        if !solved {
+            // Nothing fit together; fall back to the map we learned from
+            build_data.map = source_map;
+            build_data.record_fallback();
+            build_data.take_snapshot();
+        }
+
+        // Seal the edges, which the chunks may not have covered
+        for x in 0..build_data.map.width {
+            let top = build_data.map.xy_idx(x, 0);
+            let bottom = build_data.map.xy_idx(x, build_data.map.height - 1);
+            build_data.map.tiles[top] = TileType::Wall;
+            build_data.map.tiles[bottom] = TileType::Wall;
+        }
+        for y in 0..build_data.map.height {
+            let left = build_data.map.xy_idx(0, y);
+            let right = build_data.map.xy_idx(build_data.map.width - 1, y);
+            build_data.map.tiles[left] = TileType::Wall;
+            build_data.map.tiles[right] = TileType::Wall;
+        }
+
+        // Anything laid out against the old map no longer applies
+        build_data.spawn_list.clear();
+        build_data.rooms = None;
+        build_data.corridors = None;
+        build_data.starting_position = None;
+    }
+}
+
+/// Reads an ASCII map into a map just big enough to hold it; `#` is wall and anything else is
+/// floor
+fn load_template(template: &str, depth: i32) -> Map {
+    let width = template
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = template.lines().count();
+    let mut map = Map::new(width as i32, height as i32, depth);
+    for (y, line) in template.lines().enumerate() {
+        for (x, glyph) in line.chars().enumerate() {
+            let idx = map.xy_idx(x as i32, y as i32);
+            map.tiles[idx] = match glyph {
+                '#' => TileType::Wall,
+                _ => TileType::Floor,
+            };
+        }
+    }
+    map
+}
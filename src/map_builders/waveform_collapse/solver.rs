@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use bracket_lib::random::RandomNumberGenerator;
+
+use super::common::{opposite, tile_idx_in_chunk, MapChunk, EAST, NORTH, SOUTH, WEST};
+use crate::Map;
+
+pub struct Solver {
+    constraints: Vec<MapChunk>,
+    chunk_size: i32,
+    chunks: Vec<Option<usize>>,
+    chunks_x: usize,
+    chunks_y: usize,
+    remaining: Vec<(usize, i32)>, // (index, # neighbors)
+    pub possible: bool,
+}
+
+impl Solver {
+    pub fn new(constraints: Vec<MapChunk>, chunk_size: i32, map: &Map) -> Solver {
+        let chunks_x = (map.width / chunk_size) as usize;
+        let chunks_y = (map.height / chunk_size) as usize;
+        let remaining: Vec<(usize, i32)> = (0..(chunks_x * chunks_y)).map(|i| (i, 0)).collect();
+
+        Solver {
+            constraints,
+            chunk_size,
+            chunks: vec![None; chunks_x * chunks_y],
+            chunks_x,
+            chunks_y,
+            remaining,
+            possible: true,
+        }
+    }
+
+    fn chunk_idx(&self, x: usize, y: usize) -> usize {
+        (y * self.chunks_x) + x
+    }
+
+    /// The resolved chunks around (x, y), paired with the direction they sit in
+    fn resolved_neighbors(&self, chunk_x: usize, chunk_y: usize) -> Vec<(usize, usize)> {
+        let mut neighbors = Vec::new();
+        if chunk_x > 0 {
+            if let Some(pattern) = self.chunks[self.chunk_idx(chunk_x - 1, chunk_y)] {
+                neighbors.push((WEST, pattern));
+            }
+        }
+        if chunk_x < self.chunks_x - 1 {
+            if let Some(pattern) = self.chunks[self.chunk_idx(chunk_x + 1, chunk_y)] {
+                neighbors.push((EAST, pattern));
+            }
+        }
+        if chunk_y > 0 {
+            if let Some(pattern) = self.chunks[self.chunk_idx(chunk_x, chunk_y - 1)] {
+                neighbors.push((NORTH, pattern));
+            }
+        }
+        if chunk_y < self.chunks_y - 1 {
+            if let Some(pattern) = self.chunks[self.chunk_idx(chunk_x, chunk_y + 1)] {
+                neighbors.push((SOUTH, pattern));
+            }
+        }
+        neighbors
+    }
+
+    /// Collapses one chunk, returning true once there is nothing left to do. A contradiction
+    /// also ends the run, with `possible` set to false.
+    pub fn iteration(&mut self, map: &mut Map, rng: &mut RandomNumberGenerator) -> bool {
+        if self.remaining.is_empty() {
+            return true;
+        }
+
+        // Work on the most constrained chunks first
+        let mut neighbors_exist = false;
+        for i in 0..self.remaining.len() {
+            let chunk_index = self.remaining[i].0;
+            let chunk_x = chunk_index % self.chunks_x;
+            let chunk_y = chunk_index / self.chunks_x;
+            let neighbor_count = self.resolved_neighbors(chunk_x, chunk_y).len() as i32;
+            if neighbor_count > 0 {
+                neighbors_exist = true;
+            }
+            self.remaining[i].1 = neighbor_count;
+        }
+        self.remaining.sort_by_key(|r| std::cmp::Reverse(r.1));
+
+        let remaining_index = if !neighbors_exist {
+            (rng.roll_dice(1, self.remaining.len() as i32) - 1) as usize
+        } else {
+            0usize
+        };
+        let chunk_index = self.remaining.remove(remaining_index).0;
+        let chunk_x = chunk_index % self.chunks_x;
+        let chunk_y = chunk_index / self.chunks_x;
+
+        // Each resolved neighbor limits us to the patterns it will sit beside
+        let options: Vec<&Vec<usize>> = self
+            .resolved_neighbors(chunk_x, chunk_y)
+            .into_iter()
+            .map(|(direction, pattern)| {
+                let facing_back = opposite(direction);
+                &self.constraints[pattern].compatible_with[facing_back]
+            })
+            .collect();
+
+        let new_chunk_idx = if options.is_empty() {
+            (rng.roll_dice(1, self.constraints.len() as i32) - 1) as usize
+        } else {
+            let candidates: HashSet<usize> =
+                options.iter().flat_map(|o| o.iter()).copied().collect();
+            let mut possible_options: Vec<usize> = candidates
+                .into_iter()
+                .filter(|candidate| options.iter().all(|o| o.contains(candidate)))
+                .collect();
+            possible_options.sort();
+
+            if possible_options.is_empty() {
+                self.possible = false;
+                return true;
+            }
+            possible_options[(rng.roll_dice(1, possible_options.len() as i32) - 1) as usize]
+        };
+
+        self.chunks[chunk_index] = Some(new_chunk_idx);
+        let left_x = chunk_x as i32 * self.chunk_size;
+        let top_y = chunk_y as i32 * self.chunk_size;
+        for y in 0..self.chunk_size {
+            for x in 0..self.chunk_size {
+                let map_idx = map.xy_idx(left_x + x, top_y + y);
+                map.tiles[map_idx] = self.constraints[new_chunk_idx].pattern
+                    [tile_idx_in_chunk(self.chunk_size, x, y)];
+            }
+        }
+
+        false
+    }
+}
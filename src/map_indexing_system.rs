@@ -1,6 +1,6 @@
 use specs::{Entities, Join, ReadStorage, System, WriteExpect};
 
-use crate::{BlocksTile, Map, Position};
+use crate::{BlocksTile, BlocksVisibility, Door, Map, Position};
 
 pub struct MapIndexingSystem {}
 
@@ -9,14 +9,18 @@ impl<'a> System<'a> for MapIndexingSystem {
         WriteExpect<'a, Map>,
         ReadStorage<'a, Position>,
         ReadStorage<'a, BlocksTile>,
+        ReadStorage<'a, BlocksVisibility>,
+        ReadStorage<'a, Door>,
         Entities<'a>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut map, position, blockers, entities) = data;
+        let (mut map, position, blockers, view_blockers, doors, entities) = data;
 
         map.populate_blocked();
         map.clear_content_index();
+        map.view_blocked.clear();
+        map.closed_doors.clear();
         for (entity, position) in (&entities, &position).join() {
             let idx = map.xy_idx(position.x, position.y);
 
@@ -24,6 +28,12 @@ impl<'a> System<'a> for MapIndexingSystem {
             if let Some(_p) = _p {
                 map.blocked[idx] = true;
             }
+            if view_blockers.get(entity).is_some() {
+                map.view_blocked.insert(idx);
+            }
+            if doors.get(entity).is_some_and(|door| !door.open) {
+                map.closed_doors.insert(idx);
+            }
 
             map.tile_content[idx].push(entity);
         }
@@ -0,0 +1,409 @@
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+
+use bracket_lib::{pathfinding::DijkstraMap, random::RandomNumberGenerator};
+
+use crate::{map_builders, TileType};
+
+/// Builds a single level and prints every snapshot taken along the way as a text frame, so a
+/// builder can be stepped through without opening a window
+pub fn dump_snapshots(seed: Option<u64>, depth: i32) {
+    let seed = seed.unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    let mut rng = RandomNumberGenerator::seeded(seed);
+    let (width, height) = map_builders::level_size(depth);
+    let mut builder = map_builders::random_builder(depth, width, height, &mut rng);
+    builder.build_map(&mut rng);
+
+    let history = &builder.build_data.history;
+    println!("Seed {}, depth {}: {} steps", seed, depth, history.len());
+    for (i, snapshot) in history.iter().enumerate() {
+        println!();
+        println!("Step {} of {}: {}", i + 1, history.len(), snapshot.builder);
+        print!("{}", snapshot.to_text());
+    }
+}
+
+/// The map is split into a grid of this many regions when looking at how spawns are spread out
+const REGION_COLUMNS: i32 = 4;
+const REGION_ROWS: i32 = 3;
+
+/// Settings for the `mapgen` subcommand
+struct BatchOptions {
+    builder: String,
+    first_seed: u64,
+    count: u64,
+    depth: i32,
+    /// Overrides the usual size of a level at this depth
+    width: Option<i32>,
+    height: Option<i32>,
+    print_maps: bool,
+}
+
+/// What one generated level looks like, for tuning the builders
+struct MapStats {
+    chain: String,
+    floor_percent: f32,
+    rooms: Option<usize>,
+    reachable_percent: f32,
+    exit_distance: Option<f32>,
+    spawns: usize,
+    regions_used: usize,
+    busiest_region: usize,
+    dark: bool,
+    /// Builders that gave up and fell back on something else
+    fallbacks: Vec<&'static str>,
+}
+
+const BATCH_USAGE: &str = "Usage: portals_of_balor mapgen [--builder NAME] [--seed N] [--count N] \
+                           [--depth N] [--width N] [--height N] [--quiet] [--list]";
+
+fn parse_batch_options(args: impl Iterator<Item = String>) -> Result<BatchOptions, String> {
+    let mut options = BatchOptions {
+        builder: "random".to_string(),
+        first_seed: 0,
+        count: 10,
+        depth: 1,
+        width: None,
+        height: None,
+        print_maps: true,
+    };
+
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--builder" => options.builder = value("--builder")?,
+            "--seed" => options.first_seed = parse_number("--seed", value("--seed")?)?,
+            "--count" => options.count = parse_number("--count", value("--count")?)?,
+            "--depth" => options.depth = parse_number("--depth", value("--depth")?)?,
+            "--width" => options.width = Some(parse_number("--width", value("--width")?)?),
+            "--height" => options.height = Some(parse_number("--height", value("--height")?)?),
+            "--quiet" => options.print_maps = false,
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        }
+    }
+
+    if !map_builders::builder_names().contains(&options.builder.as_str()) {
+        return Err(format!(
+            "Unknown builder '{}'; try one of: {}",
+            options.builder,
+            map_builders::builder_names().join(", ")
+        ));
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} needs a number, not '{}'", name, value))
+}
+
+/// Runs the `mapgen` subcommand: generates `count` levels with consecutive seeds, printing each
+/// one and then a summary. Returns the process exit code, which is non-zero if any generation
+/// panicked.
+pub fn run_batch(args: impl Iterator<Item = String>) -> i32 {
+    let args: Vec<String> = args.collect();
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{}", BATCH_USAGE);
+        return 0;
+    }
+    if args.iter().any(|arg| arg == "--list") {
+        for name in map_builders::builder_names() {
+            println!("{}", name);
+        }
+        return 0;
+    }
+    let options = match parse_batch_options(args.into_iter()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", BATCH_USAGE);
+            return 2;
+        }
+    };
+
+    let (default_width, default_height) = map_builders::level_size(options.depth);
+    let size = (
+        options.width.unwrap_or(default_width),
+        options.height.unwrap_or(default_height),
+    );
+
+    let mut results: Vec<MapStats> = Vec::new();
+    let mut panics: Vec<(u64, String)> = Vec::new();
+    for seed in options.first_seed..options.first_seed + options.count {
+        let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            generate(&options.builder, seed, options.depth, size)
+        }));
+        match outcome {
+            Ok((text, stats)) => {
+                if options.print_maps {
+                    println!("Seed {}: {}", seed, stats.chain);
+                    print!("{}", text);
+                }
+                println!("Seed {}: {}", seed, describe(&stats));
+                results.push(stats);
+            }
+            Err(payload) => {
+                let message = panic_message(payload.as_ref());
+                eprintln!("Seed {}: PANICKED: {}", seed, message);
+                panics.push((seed, message));
+            }
+        }
+    }
+
+    print_summary(&options, &results);
+
+    if panics.is_empty() {
+        0
+    } else {
+        eprintln!();
+        eprintln!(
+            "{} of {} generations panicked:",
+            panics.len(),
+            options.count
+        );
+        for (seed, message) in panics.iter() {
+            eprintln!(
+                "  --builder {} --seed {} --depth {} --width {} --height {}: {}",
+                options.builder, seed, options.depth, size.0, size.1, message
+            );
+        }
+        1
+    }
+}
+
+fn generate(builder_name: &str, seed: u64, depth: i32, size: (i32, i32)) -> (String, MapStats) {
+    let mut rng = RandomNumberGenerator::seeded(seed);
+    let mut builder = map_builders::named_builder(builder_name, depth, size.0, size.1, &mut rng)
+        .expect("The builder name was checked when parsing the options");
+    builder.build_map(&mut rng);
+    let build_data = &mut builder.build_data;
+
+    // The builders that ran, in order, without the repeats from mid-build snapshots
+    let mut chain: Vec<&str> = build_data.history.iter().map(|s| s.builder).collect();
+    chain.dedup();
+    let chain = chain.join(" > ");
+
+    let map = &mut build_data.map;
+    let open_tiles: Vec<usize> = (0..map.tiles.len())
+        .filter(|idx| map.tiles[*idx].walkable())
+        .collect();
+    let floor_percent = open_tiles.len() as f32 * 100.0 / map.tiles.len() as f32;
+
+    // Walk out from the start to see how much of the level, and which stairs, can be reached
+    let start_idx = build_data
+        .starting_position
+        .as_ref()
+        .map(|start| map.xy_idx(start.x, start.y));
+    let mut reachable_percent = 0.0;
+    let mut exit_distance = None;
+    if let Some(start_idx) = start_idx {
+        map.populate_blocked();
+        let dijkstra_map = DijkstraMap::new(
+            map.width,
+            map.height,
+            &[start_idx],
+            &*map,
+            map.tiles.len() as f32,
+        );
+        let reachable = open_tiles
+            .iter()
+            .filter(|idx| dijkstra_map.map[**idx] < f32::MAX)
+            .count();
+        reachable_percent = reachable as f32 * 100.0 / usize::max(open_tiles.len(), 1) as f32;
+        exit_distance = map
+            .tiles
+            .iter()
+            .position(|tile| *tile == TileType::DownStairs)
+            .map(|idx| dijkstra_map.map[idx])
+            .filter(|distance| *distance < f32::MAX);
+    }
+
+    // Doors and lights are part of the architecture, not something that was spawned in
+    let mut region_counts = vec![0; (REGION_COLUMNS * REGION_ROWS) as usize];
+    let mut spawns = 0;
+    for (idx, name) in build_data.spawn_list.iter() {
+        if matches!(
+            name.as_str(),
+            "Door" | "Torch" | "Brazier" | "Glowing Fungus"
+        ) {
+            continue;
+        }
+        let x = *idx as i32 % map.width;
+        let y = *idx as i32 / map.width;
+        let region =
+            (y * REGION_ROWS / map.height) * REGION_COLUMNS + (x * REGION_COLUMNS / map.width);
+        region_counts[region as usize] += 1;
+        spawns += 1;
+    }
+
+    let mut text: Vec<char> = map_builders::MapSnapshot {
+        builder: "",
+        map: map.clone(),
+    }
+    .to_text()
+    .chars()
+    .collect();
+    // Each row of text ends in a newline
+    let text_idx = |idx: usize| idx + idx / map.width as usize;
+    for (idx, name) in build_data.spawn_list.iter() {
+        text[text_idx(*idx)] = spawn_ascii(name);
+    }
+    if let Some(start_idx) = start_idx {
+        text[text_idx(start_idx)] = '@';
+    }
+
+    let stats = MapStats {
+        chain,
+        floor_percent,
+        rooms: build_data.rooms.as_ref().map(|rooms| rooms.len()),
+        reachable_percent,
+        exit_distance,
+        spawns,
+        regions_used: region_counts.iter().filter(|count| **count > 0).count(),
+        busiest_region: region_counts.iter().copied().max().unwrap_or(0),
+        dark: map.is_dark(),
+        fallbacks: build_data.fallbacks.clone(),
+    };
+    (text.into_iter().collect(), stats)
+}
+
+fn spawn_ascii(name: &str) -> char {
+    match name {
+        "Door" => '+',
+        "Health Potion" | "Mana Potion" => '!',
+        name if name.starts_with("Scroll") => '~',
+        name if name.starts_with("Wand") => '-',
+        "Repair Kit" => '%',
+        "Goblin Archer" => 'a',
+        "Goblin Shaman" => 's',
+        "Bog Wraith" => 'W',
+        "Orc Warlord" => 'O',
+        name if name.starts_with("Spellbook") => '?',
+        "Sling Stones" => '`',
+        "Arrows" => '|',
+        "Buckler" => '[',
+        "Dagger" | "Short Sword" | "Mace" => ')',
+        "Sling" | "Shortbow" => '}',
+        "Shrine" => '_',
+        "Torch" => '*',
+        "Brazier" => '&',
+        "Glowing Fungus" => 'f',
+        _ => name
+            .chars()
+            .next()
+            .map(|c| c.to_ascii_lowercase())
+            .unwrap_or('?'),
+    }
+}
+
+fn describe(stats: &MapStats) -> String {
+    format!(
+        "floor {:.1}%, rooms {}, reachable {:.1}%, exit {}, {} spawns in {}/{} regions (busiest {}){}{}",
+        stats.floor_percent,
+        stats
+            .rooms
+            .map(|rooms| rooms.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        stats.reachable_percent,
+        stats
+            .exit_distance
+            .map(|distance| format!("{:.1} steps away", distance))
+            .unwrap_or_else(|| "unreachable".to_string()),
+        stats.spawns,
+        stats.regions_used,
+        REGION_COLUMNS * REGION_ROWS,
+        stats.busiest_region,
+        if stats.dark { ", dark" } else { "" },
+        stats
+            .fallbacks
+            .iter()
+            .map(|builder| format!(", {} fell back", builder))
+            .collect::<String>()
+    )
+}
+
+fn print_summary(options: &BatchOptions, results: &[MapStats]) {
+    println!();
+    println!(
+        "{} levels from '{}', seeds {} to {}, depth {}",
+        results.len(),
+        options.builder,
+        options.first_seed,
+        options.first_seed + options.count.saturating_sub(1),
+        options.depth
+    );
+    if results.is_empty() {
+        return;
+    }
+
+    let rooms: Vec<f32> = results
+        .iter()
+        .filter_map(|stats| stats.rooms.map(|rooms| rooms as f32))
+        .collect();
+    let exits: Vec<f32> = results
+        .iter()
+        .filter_map(|stats| stats.exit_distance)
+        .collect();
+    let connected = results
+        .iter()
+        .filter(|stats| stats.reachable_percent >= 100.0)
+        .count();
+
+    println!(
+        "  floor %:         {}",
+        spread(results.iter().map(|stats| stats.floor_percent))
+    );
+    println!("  rooms:           {}", spread(rooms.into_iter()));
+    println!("  fully connected: {}/{}", connected, results.len());
+    println!("  exit reachable:  {}/{}", exits.len(), results.len());
+    println!(
+        "  dark:            {}/{}",
+        results.iter().filter(|stats| stats.dark).count(),
+        results.len()
+    );
+    println!(
+        "  fell back:       {}/{}",
+        results
+            .iter()
+            .filter(|stats| !stats.fallbacks.is_empty())
+            .count(),
+        results.len()
+    );
+    println!("  exit distance:   {}", spread(exits.into_iter()));
+    println!(
+        "  spawns:          {}",
+        spread(results.iter().map(|stats| stats.spawns as f32))
+    );
+    println!(
+        "  regions used:    {}",
+        spread(results.iter().map(|stats| stats.regions_used as f32))
+    );
+    println!(
+        "  busiest region:  {}",
+        spread(results.iter().map(|stats| stats.busiest_region as f32))
+    );
+}
+
+/// Sums up a set of values as their minimum, mean and maximum
+fn spread(values: impl Iterator<Item = f32>) -> String {
+    let values: Vec<f32> = values.collect();
+    if values.is_empty() {
+        return "-".to_string();
+    }
+    let min = values.iter().copied().fold(f32::MAX, f32::min);
+    let max = values.iter().copied().fold(f32::MIN, f32::max);
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    format!("min {:.1}, mean {:.1}, max {:.1}", min, mean, max)
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
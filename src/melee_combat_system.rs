@@ -1,31 +1,128 @@
+use bracket_lib::random::RandomNumberGenerator;
 use specs::prelude::*;
 
-use crate::{CombatStats, GameLog, Name, SufferDamage, WantsToMelee};
+use crate::{
+    item_name, Awareness, AwarenessState, CombatStats, DamageType, Durability, Effect, EffectQueue,
+    EmitsNoise, EquipmentBonus, EquipmentSlot, Equipped, GameLog, IdentificationRegistry,
+    MeleeAttack, MeleeWeapon, Name, Pools, StatusProc, Targets, WantsToMelee, NOISE_FIGHTING,
+};
+
+/// Attacks against a target that hasn't noticed its attacker deal this many times the damage
+pub const SNEAK_ATTACK_MULTIPLIER: i32 = 2;
+
+/// What a melee attack or a shot costs in stamina
+pub const ATTACK_STAMINA_COST: i32 = 2;
+
+/// Rolls each of a weapon's status procs against a target it just hit
+pub fn roll_procs(
+    rng: &mut RandomNumberGenerator,
+    effects: &mut EffectQueue,
+    attacker: Entity,
+    target: Entity,
+    procs: &[StatusProc],
+) {
+    for proc in procs {
+        if rng.roll_dice(1, 100) <= proc.chance {
+            effects.add_effect(
+                Some(attacker),
+                Effect::ApplyStatus {
+                    status: proc.status,
+                    turns: proc.turns,
+                },
+                Targets::Single { target },
+            );
+        }
+    }
+}
 
 pub struct MeleeCombatSystem {}
 
 impl<'a> System<'a> for MeleeCombatSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         Entities<'a>,
         WriteExpect<'a, GameLog>,
+        ReadExpect<'a, IdentificationRegistry>,
         WriteStorage<'a, WantsToMelee>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, CombatStats>,
-        WriteStorage<'a, SufferDamage>,
+        ReadStorage<'a, MeleeAttack>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, MeleeWeapon>,
+        WriteStorage<'a, Durability>,
+        ReadStorage<'a, EquipmentBonus>,
+        WriteStorage<'a, Pools>,
+        Write<'a, EffectQueue>,
+        WriteStorage<'a, Awareness>,
+        WriteStorage<'a, EmitsNoise>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut log, mut wants_melee, names, combat_stats, mut inflict_damage) = data;
+        let (
+            entities,
+            mut log,
+            registry,
+            mut wants_melee,
+            names,
+            combat_stats,
+            melee_attacks,
+            equipped,
+            melee_weapons,
+            mut durability,
+            bonuses,
+            mut pools,
+            mut effects,
+            mut awareness,
+            mut noises,
+        ) = data;
 
-        for (_entity, wants_melee, name, stats) in
+        let mut rng = RandomNumberGenerator::new();
+        for (entity, wants_melee, name, stats) in
             (&entities, &wants_melee, &names, &combat_stats).join()
         {
             if stats.hp > 0 {
                 let target_stats = combat_stats.get(wants_melee.target).unwrap();
                 if target_stats.hp > 0 {
                     let target_name = names.get(wants_melee.target).unwrap();
+                    if let Some(pools) = pools.get_mut(entity) {
+                        pools.spend_stamina(ATTACK_STAMINA_COST);
+                    }
+
+                    // A wielded weapon adds its power, and its own kind of damage, to the blow;
+                    // a broken one is no better than a bare fist
+                    let weapon = (&entities, &equipped, &melee_weapons)
+                        .join()
+                        .find(|(_, e, _)| e.owner == entity)
+                        .filter(|(item, _, _)| durability.get(*item).is_none_or(|d| !d.is_broken()))
+                        .map(|(item, _, weapon)| (item, *weapon));
+                    let bonus = weapon.and_then(|_| {
+                        EquipmentBonus::in_slot(entity, EquipmentSlot::Melee, &equipped, &bonuses)
+                    });
+                    let power = stats.power
+                        + weapon.map_or(0, |(_, weapon)| weapon.power)
+                        + bonus.map_or(0, |bonus| bonus.power);
+                    let kind = bonus
+                        .and_then(|bonus| bonus.damage_type)
+                        .or(weapon.map(|(_, weapon)| weapon.kind))
+                        .or(melee_attacks.get(entity).map(|attack| attack.kind))
+                        .unwrap_or(DamageType::Bludgeoning);
+                    let defense = target_stats.defense
+                        + EquipmentBonus::defense_of(wants_melee.target, &equipped, &bonuses);
+
+                    let mut damage = i32::max(0, power - defense);
 
-                    let damage = i32::max(0, stats.power - target_stats.defense);
+                    // Anything caught unawares takes extra damage, and is certainly aware now
+                    if let Some(target_awareness) = awareness.get_mut(wants_melee.target) {
+                        if target_awareness.state != AwarenessState::Aware {
+                            damage *= SNEAK_ATTACK_MULTIPLIER;
+                            log.entries.push(format!(
+                                "{} catches {} unawares!",
+                                &name.name, &target_name.name
+                            ));
+                        }
+                        target_awareness.state = AwarenessState::Aware;
+                        target_awareness.investigating = None;
+                    }
 
                     if damage == 0 {
                         log.entries.push(format!(
@@ -33,12 +130,36 @@ impl<'a> System<'a> for MeleeCombatSystem {
                             &name.name, &target_name.name
                         ));
                     } else {
-                        log.entries.push(format!(
-                            "{} hits {}, for {} hp.",
-                            &name.name, &target_name.name, damage
-                        ));
-                        SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage);
+                        effects.add_effect(
+                            Some(entity),
+                            Effect::Damage {
+                                amount: damage,
+                                kind,
+                            },
+                            Targets::Single {
+                                target: wants_melee.target,
+                            },
+                        );
+                        if let Some((item, _)) = weapon {
+                            if durability.get_mut(item).is_some_and(|d| d.wear()) {
+                                log.entries.push(format!(
+                                    "{}'s {} breaks!",
+                                    &name.name,
+                                    item_name(item, &names, &registry)
+                                ));
+                            }
+                        }
+                        if let Some(bonus) = bonus {
+                            roll_procs(
+                                &mut rng,
+                                &mut effects,
+                                entity,
+                                wants_melee.target,
+                                &bonus.procs,
+                            );
+                        }
                     }
+                    EmitsNoise::new_noise(&mut noises, entity, NOISE_FIGHTING);
                 }
             }
         }
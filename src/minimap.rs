@@ -0,0 +1,179 @@
+use bracket_lib::{
+    color::{BLACK, CYAN, GREY, RGB, WHITE, YELLOW},
+    terminal::{BTerm, Point},
+};
+use specs::prelude::*;
+
+use crate::{Map, TileType, VIEW_HEIGHT, VIEW_WIDTH};
+
+/// The minimap's size in console cells, not counting its border. Each cell is split into a top
+/// and bottom half, so it shows twice as many rows of blocks as it has cells.
+const MINIMAP_WIDTH: i32 = 20;
+const MINIMAP_HEIGHT: i32 = 11;
+
+/// The upper half block in code page 437; its foreground and background color the two halves
+const HALF_BLOCK: u16 = 0xDF;
+
+/// What a block of tiles shows up as. When a block holds several things, the one listed last
+/// wins.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum Feature {
+    Unknown,
+    Wall,
+    Floor,
+    Frontier,
+    Door,
+    Stairs,
+    Player,
+}
+
+impl Feature {
+    fn color(self) -> RGB {
+        match self {
+            Feature::Unknown => RGB::named(BLACK),
+            Feature::Wall => RGB::from_f32(0.0, 0.35, 0.0),
+            Feature::Floor => RGB::named(GREY),
+            Feature::Frontier => RGB::from_f32(1.0, 0.5, 0.0),
+            Feature::Door => RGB::from_f32(0.55, 0.35, 0.1),
+            Feature::Stairs => RGB::named(CYAN),
+            Feature::Player => RGB::named(YELLOW),
+        }
+    }
+}
+
+/// The revealed level shrunk down so each block of `scale` by `scale` tiles becomes one entry
+struct ScaledMap {
+    width: i32,
+    height: i32,
+    features: Vec<Feature>,
+}
+
+impl ScaledMap {
+    /// Frontier tiles are explored floor next to tiles that haven't been seen yet, which is
+    /// where there is still something to find
+    fn new(map: &Map, player_pos: Point, scale: i32, show_frontier: bool) -> ScaledMap {
+        let width = (map.width + scale - 1) / scale;
+        let height = (map.height + scale - 1) / scale;
+        let mut features = vec![Feature::Unknown; (width * height) as usize];
+
+        for y in 0..map.height {
+            for x in 0..map.width {
+                let idx = map.xy_idx(x, y);
+                if !map.revealed_tiles[idx] {
+                    continue;
+                }
+                let feature = match map.tiles[idx] {
+                    TileType::DownStairs => Feature::Stairs,
+                    TileType::Door => Feature::Door,
+                    tile if !tile.walkable() => Feature::Wall,
+                    _ if show_frontier && map.borders_unexplored(x, y) => Feature::Frontier,
+                    _ => Feature::Floor,
+                };
+                let block = ((y / scale) * width + x / scale) as usize;
+                features[block] = features[block].max(feature);
+            }
+        }
+        let player_block = ((player_pos.y / scale) * width + player_pos.x / scale) as usize;
+        features[player_block] = Feature::Player;
+
+        ScaledMap {
+            width,
+            height,
+            features,
+        }
+    }
+
+    fn feature(&self, x: i32, y: i32) -> Feature {
+        if x < 0 || x >= self.width || y < 0 || y >= self.height {
+            Feature::Unknown
+        } else {
+            self.features[(y * self.width + x) as usize]
+        }
+    }
+
+    /// Draws two rows of blocks per console row, with its top left cell at (x, y)
+    fn draw(&self, x: i32, y: i32, ctx: &mut BTerm) {
+        for cell_y in 0..(self.height + 1) / 2 {
+            for cell_x in 0..self.width {
+                let top = self.feature(cell_x, cell_y * 2);
+                let bottom = self.feature(cell_x, cell_y * 2 + 1);
+                ctx.set(
+                    x + cell_x,
+                    y + cell_y,
+                    top.color(),
+                    bottom.color(),
+                    HALF_BLOCK,
+                );
+            }
+        }
+    }
+}
+
+/// The smallest whole number of tiles per block that fits the map into the given number of cells
+fn fit_scale(map: &Map, cells_wide: i32, cells_high: i32) -> i32 {
+    let across = (map.width + cells_wide - 1) / cells_wide;
+    let down = (map.height + cells_high * 2 - 1) / (cells_high * 2);
+    i32::max(1, i32::max(across, down))
+}
+
+/// Draws the explored level in the top right corner of the map view. Levels that fit on screen
+/// have nothing more to show, so they get no minimap.
+pub fn draw_minimap(ecs: &World, ctx: &mut BTerm) {
+    let map = ecs.fetch::<Map>();
+    if map.width <= VIEW_WIDTH && map.height <= VIEW_HEIGHT {
+        return;
+    }
+    let player_pos = ecs.fetch::<Point>();
+
+    let scale = fit_scale(&map, MINIMAP_WIDTH, MINIMAP_HEIGHT);
+    let minimap = ScaledMap::new(&map, *player_pos, scale, false);
+    let cells_high = (minimap.height + 1) / 2;
+    let left = VIEW_WIDTH - minimap.width - 2;
+    ctx.draw_box(
+        left,
+        0,
+        minimap.width + 1,
+        cells_high + 1,
+        RGB::named(WHITE),
+        RGB::named(BLACK),
+    );
+    minimap.draw(left + 1, 1, ctx);
+}
+
+/// Fills the screen with the whole level scaled down, marking where the unexplored frontier is
+pub fn draw_overview(ecs: &World, ctx: &mut BTerm) {
+    let map = ecs.fetch::<Map>();
+    let player_pos = ecs.fetch::<Point>();
+    let (screen_width, screen_height) = ctx.get_char_size();
+    let (screen_width, screen_height) = (screen_width as i32, screen_height as i32);
+
+    ctx.print_color(
+        1,
+        0,
+        RGB::named(YELLOW),
+        RGB::named(BLACK),
+        format!("Overview of depth {}", map.depth),
+    );
+    ctx.print_color(
+        1,
+        screen_height - 1,
+        Feature::Frontier.color(),
+        RGB::named(BLACK),
+        "Orange: unexplored edges",
+    );
+    ctx.print_color(
+        screen_width - 28,
+        screen_height - 1,
+        RGB::named(GREY),
+        RGB::named(BLACK),
+        "Press M or Escape to return",
+    );
+
+    let cells_wide = screen_width;
+    let cells_high = screen_height - 2;
+    let scale = fit_scale(&map, cells_wide, cells_high);
+    let overview = ScaledMap::new(&map, *player_pos, scale, true);
+    let left = (cells_wide - overview.width) / 2;
+    let top = 1 + (cells_high - (overview.height + 1) / 2) / 2;
+    overview.draw(left, top, ctx);
+}
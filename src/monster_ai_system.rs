@@ -1,9 +1,20 @@
-use crate::{Map, Position, RunState, WantsToMelee};
+use crate::{
+    closed_door_at, ready_ranged_weapon, Ally, Ammunition, Awareness, AwarenessState, CombatStats,
+    Door, Durability, Effect, Equipped, GameLog, InBackpack, KnownSpells, Map, Name, Pools,
+    Position, RangedAI, RangedWeapon, RunState, Spell, SpellShape, StatusEffects, Stealth,
+    WantsToCastSpell, WantsToMelee, WantsToOpenDoor, WantsToShoot,
+};
 
 use super::{Monster, Viewshed};
-use bracket_lib::terminal::Point;
+use bracket_lib::{
+    random::RandomNumberGenerator,
+    terminal::{DistanceAlg, Point},
+};
 use specs::prelude::*;
 
+/// What an unaware monster rolls against (plus the target's stealth) to notice someone it can see
+const NOTICE_DIFFICULTY: i32 = 12;
+
 pub struct MonsterAI {}
 
 impl<'a> System<'a> for MonsterAI {
@@ -13,11 +24,32 @@ impl<'a> System<'a> for MonsterAI {
         ReadExpect<'a, Point>,
         ReadExpect<'a, Entity>,
         ReadExpect<'a, RunState>,
+        WriteExpect<'a, GameLog>,
         Entities<'a>,
         WriteStorage<'a, Viewshed>,
         ReadStorage<'a, Monster>,
+        ReadStorage<'a, Ally>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Stealth>,
+        WriteStorage<'a, Awareness>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, WantsToMelee>,
+        ReadStorage<'a, Door>,
+        WriteStorage<'a, WantsToOpenDoor>,
+        (
+            ReadStorage<'a, RangedAI>,
+            ReadStorage<'a, Equipped>,
+            ReadStorage<'a, RangedWeapon>,
+            ReadStorage<'a, Durability>,
+            ReadStorage<'a, InBackpack>,
+            ReadStorage<'a, Ammunition>,
+        ),
+        WriteStorage<'a, WantsToShoot>,
+        ReadStorage<'a, KnownSpells>,
+        ReadStorage<'a, Pools>,
+        ReadStorage<'a, CombatStats>,
+        WriteStorage<'a, WantsToCastSpell>,
+        ReadStorage<'a, StatusEffects>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -26,54 +58,302 @@ impl<'a> System<'a> for MonsterAI {
             player_pos,
             player_entity,
             runstate,
+            mut log,
             entities,
             mut viewshed,
             monster,
+            allies,
+            names,
+            stealth,
+            mut awareness,
             mut position,
             mut wants_to_melee,
+            doors,
+            mut wants_to_open,
+            (ranged_ai, equipped, ranged_weapons, durability, backpack, ammunition),
+            mut wants_to_shoot,
+            known_spells,
+            pools,
+            combat_stats,
+            mut wants_to_cast,
+            statuses,
         ) = data;
 
         if *runstate != RunState::MonsterTurn {
             return;
         }
-        for (entity, viewshed, _monster, pos) in
-            (&entities, &mut viewshed, &monster, &mut position).join()
+        let mut rng = RandomNumberGenerator::new();
+
+        // The player and anything fighting alongside them are fair game
+        let mut enemies: Vec<(Entity, Point)> = vec![(*player_entity, *player_pos)];
+        for (entity, _ally, pos) in (&entities, &allies, &position).join() {
+            enemies.push((entity, Point::new(pos.x, pos.y)));
+        }
+
+        for (entity, viewshed, _monster, pos, awareness) in (
+            &entities,
+            &mut viewshed,
+            &monster,
+            &mut position,
+            (&mut awareness).maybe(),
+        )
+            .join()
         {
+            let monster_pos = Point::new(pos.x, pos.y);
+
+            if statuses
+                .get(entity)
+                .is_some_and(|active| active.helpless().is_some())
             {
-                let distance = bracket_lib::terminal::DistanceAlg::Pythagoras
-                    .distance2d(Point::new(pos.x, pos.y), *player_pos);
-                if distance < 1.5 {
-                    wants_to_melee
+                continue;
+            }
+
+            if let Some(awareness) = awareness {
+                match awareness.state {
+                    AwarenessState::Asleep => continue,
+                    AwarenessState::Unaware => {
+                        let spotted = enemies
+                            .iter()
+                            .filter(|(_, enemy_pos)| viewshed.visible_tiles.contains(enemy_pos))
+                            .find(|(enemy, _)| {
+                                let skill = stealth.get(*enemy).map_or(0, |s| s.skill);
+                                rng.roll_dice(1, 20) + awareness.perception
+                                    >= NOTICE_DIFFICULTY + skill
+                            });
+
+                        match spotted {
+                            Some((enemy, _)) => {
+                                awareness.state = AwarenessState::Aware;
+                                awareness.investigating = None;
+                                if let (Some(name), Some(enemy_name)) =
+                                    (names.get(entity), names.get(*enemy))
+                                {
+                                    log.entries.push(format!(
+                                        "{} notices {}!",
+                                        &name.name, &enemy_name.name
+                                    ));
+                                }
+                            }
+                            None => {
+                                // Wander over to whatever was last heard, then lose interest
+                                if let Some(noise_pos) = awareness.investigating {
+                                    if noise_pos == monster_pos
+                                        || !step_towards(
+                                            &mut map,
+                                            entity,
+                                            pos,
+                                            viewshed,
+                                            noise_pos,
+                                            &doors,
+                                            &mut wants_to_open,
+                                        )
+                                    {
+                                        awareness.investigating = None;
+                                    }
+                                }
+                                continue;
+                            }
+                        }
+                    }
+                    AwarenessState::Aware => {}
+                }
+            }
+
+            let target = enemies
+                .iter()
+                .map(|(enemy, enemy_pos)| {
+                    let distance = DistanceAlg::Pythagoras.distance2d(monster_pos, *enemy_pos);
+                    (*enemy, *enemy_pos, distance)
+                })
+                .filter(|(_, enemy_pos, distance)| {
+                    *distance < 1.5 || viewshed.visible_tiles.contains(enemy_pos)
+                })
+                .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+
+            if let Some((target, target_pos, distance)) = target {
+                // Casters use a spell whenever they have one that helps and the mana for it
+                let spell = known_spells.get(entity).and_then(|known| {
+                    choose_spell(
+                        &map,
+                        known,
+                        pools.get(entity)?,
+                        combat_stats.get(entity)?,
+                        monster_pos,
+                        target_pos,
+                        viewshed.visible_tiles.contains(&target_pos),
+                    )
+                });
+                if let Some((spell, spell_target)) = spell {
+                    wants_to_cast
                         .insert(
                             entity,
-                            WantsToMelee {
-                                target: *player_entity,
+                            WantsToCastSpell {
+                                spell,
+                                target: spell_target,
+                                wand: None,
                             },
                         )
-                        .expect("Unable to insert attack");
-                } else if viewshed.visible_tiles.contains(&*player_pos) {
-                    let path = bracket_lib::pathfinding::a_star_search(
-                        map.xy_idx(pos.x, pos.y) as i32,
-                        map.xy_idx(player_pos.x, player_pos.y) as i32,
-                        &mut *map,
-                    );
-
-                    if path.success && path.steps.len() > 1 {
-                        // Clear the 'blocked' status from the old spot
-                        let old_idx = map.xy_idx(pos.x, pos.y);
-                        map.blocked[old_idx] = false;
-
-                        // Update the position and viewshed of the monster
-                        pos.x = path.steps[1] as i32 % map.width;
-                        pos.y = path.steps[1] as i32 / map.width;
-                        viewshed.dirty = true;
-
-                        // Update the 'blocked' map with the new position
-                        let new_idx = map.xy_idx(pos.x, pos.y);
-                        map.blocked[new_idx] = true;
+                        .expect("Unable to insert spell");
+                    continue;
+                }
+
+                // Archers back off from anyone too close, and shoot whatever they can see in range
+                let ranged = ranged_ai.get(entity).and_then(|ai| {
+                    ready_ranged_weapon(
+                        &entities,
+                        entity,
+                        &equipped,
+                        &ranged_weapons,
+                        &durability,
+                        &backpack,
+                        &ammunition,
+                    )
+                    .map(|(weapon, _)| (ai.min_distance, weapon.range as f32))
+                });
+                match ranged {
+                    Some((min_distance, _))
+                        if distance < min_distance
+                            && step_away(&mut map, pos, viewshed, target_pos) => {}
+                    Some((_, range))
+                        if distance <= range
+                            && viewshed.visible_tiles.contains(&target_pos)
+                            && map.has_line_of_fire(monster_pos, target_pos) =>
+                    {
+                        wants_to_shoot
+                            .insert(entity, WantsToShoot { target })
+                            .expect("Unable to insert shot");
+                    }
+                    _ if distance < 1.5 => {
+                        wants_to_melee
+                            .insert(entity, WantsToMelee { target })
+                            .expect("Unable to insert attack");
+                    }
+                    _ => {
+                        step_towards(
+                            &mut map,
+                            entity,
+                            pos,
+                            viewshed,
+                            target_pos,
+                            &doors,
+                            &mut wants_to_open,
+                        );
                     }
                 }
             }
         }
     }
 }
+
+/// Picks the first spell a monster knows that it can afford and that is worth casting: healing
+/// spells once it is down to half health, and attacks on a target it can see, in range and with a
+/// clear line to, that wouldn't catch the caster in the blast. Returns the spell along with where
+/// to cast it.
+fn choose_spell(
+    map: &Map,
+    known: &KnownSpells,
+    pools: &Pools,
+    stats: &CombatStats,
+    caster_pos: Point,
+    target_pos: Point,
+    target_visible: bool,
+) -> Option<(Spell, Point)> {
+    known.spells.iter().find_map(|spell| {
+        let info = spell.info();
+        if info.mana_cost > pools.mana.current {
+            return None;
+        }
+        let heals = info
+            .effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::Heal { .. }));
+        match info.shape {
+            SpellShape::Caster if heals && stats.hp * 2 <= stats.max_hp => {
+                Some((*spell, caster_pos))
+            }
+            SpellShape::Caster => None,
+            _ if spell.is_hostile()
+                && target_visible
+                && spell.can_reach(map, caster_pos, target_pos)
+                && !spell.catches_caster(caster_pos, target_pos) =>
+            {
+                Some((*spell, target_pos))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Moves a monster one step along the path to `destination`, returning false if there is no way
+/// there. A closed door in the way takes the turn to open instead.
+fn step_towards(
+    map: &mut Map,
+    entity: Entity,
+    pos: &mut Position,
+    viewshed: &mut Viewshed,
+    destination: Point,
+    doors: &ReadStorage<Door>,
+    wants_to_open: &mut WriteStorage<WantsToOpenDoor>,
+) -> bool {
+    let old_idx = map.xy_idx(pos.x, pos.y);
+    let destination_idx = map.xy_idx(destination.x, destination.y);
+    match map.next_step_towards(old_idx, destination_idx) {
+        Some(step) => {
+            if let Some(door) = closed_door_at(map, step, doors) {
+                wants_to_open
+                    .insert(entity, WantsToOpenDoor { door })
+                    .expect("Unable to insert door opening");
+                return true;
+            }
+
+            move_to(map, pos, viewshed, step);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Moves a monster to whichever open tile next to it is furthest from `threat`, returning false
+/// if none of them gets it any further away
+fn step_away(map: &mut Map, pos: &mut Position, viewshed: &mut Viewshed, threat: Point) -> bool {
+    let current = DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), threat);
+    let mut best: Option<(usize, f32)> = None;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let (x, y) = (pos.x + dx, pos.y + dy);
+            if x < 1 || x > map.width - 1 || y < 1 || y > map.height - 1 {
+                continue;
+            }
+            let idx = map.xy_idx(x, y);
+            if map.blocked[idx] {
+                continue;
+            }
+            let distance = DistanceAlg::Pythagoras.distance2d(Point::new(x, y), threat);
+            if distance > best.map_or(current, |(_, furthest)| furthest) {
+                best = Some((idx, distance));
+            }
+        }
+    }
+
+    match best {
+        Some((step, _)) => {
+            move_to(map, pos, viewshed, step);
+            true
+        }
+        None => false,
+    }
+}
+
+fn move_to(map: &mut Map, pos: &mut Position, viewshed: &mut Viewshed, step: usize) {
+    // Clear the 'blocked' status from the old spot
+    let old_idx = map.xy_idx(pos.x, pos.y);
+    map.blocked[old_idx] = false;
+
+    // Update the position and viewshed of the monster
+    pos.x = step as i32 % map.width;
+    pos.y = step as i32 / map.width;
+    viewshed.dirty = true;
+
+    // Update the 'blocked' map with the new position
+    map.blocked[step] = true;
+}
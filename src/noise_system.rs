@@ -0,0 +1,104 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use bracket_lib::{random::RandomNumberGenerator, terminal::Point};
+use specs::prelude::*;
+
+use crate::{Awareness, AwarenessState, EmitsNoise, GameLog, Map, Name, Position, TileType};
+
+pub const NOISE_FIGHTING: i32 = 10;
+pub const NOISE_WALKING: i32 = 3;
+
+/// Extra volume lost for every wall a noise has to pass through
+const WALL_ATTENUATION: i32 = 4;
+
+/// How hard a sleeping monster is to wake; rolled against with perception plus the volume heard
+const WAKE_DIFFICULTY: i32 = 18;
+
+pub struct NoiseSystem {}
+
+impl<'a> System<'a> for NoiseSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, GameLog>,
+        Entities<'a>,
+        WriteStorage<'a, EmitsNoise>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Awareness>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (map, mut log, entities, mut noises, positions, names, mut awareness) = data;
+        let mut rng = RandomNumberGenerator::new();
+
+        for (source, noise, source_pos) in (&entities, &noises, &positions).join() {
+            let heard = noise_field(&map, map.xy_idx(source_pos.x, source_pos.y), noise.volume);
+
+            for (listener, listener_pos, awareness) in
+                (&entities, &positions, &mut awareness).join()
+            {
+                if listener == source {
+                    continue;
+                }
+                let volume = match heard.get(&map.xy_idx(listener_pos.x, listener_pos.y)) {
+                    Some(volume) => *volume,
+                    None => continue,
+                };
+
+                match awareness.state {
+                    AwarenessState::Asleep => {
+                        if rng.roll_dice(1, 20) + awareness.perception + volume >= WAKE_DIFFICULTY {
+                            awareness.state = AwarenessState::Unaware;
+                            awareness.investigating = Some(Point::new(source_pos.x, source_pos.y));
+                            if let Some(name) = names.get(listener) {
+                                log.entries
+                                    .push(format!("{} stirs from its sleep.", &name.name));
+                            }
+                        }
+                    }
+                    AwarenessState::Unaware => {
+                        awareness.investigating = Some(Point::new(source_pos.x, source_pos.y));
+                    }
+                    AwarenessState::Aware => {}
+                }
+            }
+        }
+
+        noises.clear();
+    }
+}
+
+/// Floods a noise outwards from `origin`, returning the volume still audible at each tile it
+/// reaches. Every step costs one point of volume, and walls soak up more on top of that.
+fn noise_field(map: &Map, origin: usize, volume: i32) -> HashMap<usize, i32> {
+    let mut heard: HashMap<usize, i32> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    open.push((volume, origin));
+
+    while let Some((remaining, idx)) = open.pop() {
+        if heard.contains_key(&idx) {
+            continue;
+        }
+        heard.insert(idx, remaining);
+
+        let x = idx as i32 % map.width;
+        let y = idx as i32 / map.width;
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || nx >= map.width || ny < 0 || ny >= map.height {
+                continue;
+            }
+            let neighbor = map.xy_idx(nx, ny);
+            let mut cost = 1;
+            if map.tiles[neighbor] == TileType::Wall {
+                cost += WALL_ATTENUATION;
+            }
+            if remaining - cost > 0 && !heard.contains_key(&neighbor) {
+                open.push((remaining - cost, neighbor));
+            }
+        }
+    }
+
+    heard
+}
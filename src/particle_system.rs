@@ -0,0 +1,20 @@
+use specs::prelude::*;
+
+use crate::ParticleLifetime;
+
+/// Ages particles by the time the last frame took, removing the ones that have run out
+pub fn cull_dead_particles(ecs: &mut World, frame_time_ms: f32) {
+    let mut dead: Vec<Entity> = Vec::new();
+    {
+        let entities = ecs.entities();
+        let mut particles = ecs.write_storage::<ParticleLifetime>();
+        for (entity, particle) in (&entities, &mut particles).join() {
+            particle.lifetime_ms -= frame_time_ms;
+            if particle.lifetime_ms < 0.0 {
+                dead.push(entity);
+            }
+        }
+    }
+    ecs.delete_entities(&dead)
+        .expect("Unable to delete particles");
+}
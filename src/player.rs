@@ -1,18 +1,40 @@
-use crate::{CombatStats, RunState, Viewshed, WantsToMelee};
+use crate::{
+    gui, ready_ranged_weapon, shrine, Ally, AllyOrder, Ammunition, CombatStats, Door, Durability,
+    Effect, EffectQueue, EmitsNoise, Encumbrance, Equipped, GameLog, InBackpack, Item, KnownSpells,
+    Monster, Pools, RangedWeapon, RunState, SpellShape, Status, StatusEffects, Summoned,
+    TargetingAction, Targets, TileType, Viewshed, WantsToCastSpell, WantsToCloseDoor, WantsToMelee,
+    WantsToOpenDoor, WantsToPickupItem, NOISE_WALKING,
+};
 
-use super::{Map, Player, Position, State, MAP_HEIGHT, MAP_WIDTH};
-use bracket_lib::terminal::{BTerm, Point, VirtualKeyCode};
+use super::{Map, Player, Position, State};
+use bracket_lib::terminal::{BTerm, DistanceAlg, Point, VirtualKeyCode};
 use specs::prelude::*;
-use std::cmp::{max, min};
 
-pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+/// What a dodge-roll costs in stamina
+const DODGE_STAMINA_COST: i32 = 4;
+/// How many tiles a dodge-roll covers, if nothing is in the way
+const DODGE_DISTANCE: i32 = 2;
+/// How many of the monsters' turns a dodge-roll keeps the player out of harm's way. There is no
+/// initiative clock, so the invulnerability is counted in whole monster turns: anything that
+/// attacks during those turns misses, however quick it is, and the next turn's blows land.
+const DODGE_MONSTER_TURNS: i32 = 2;
+
+/// Moves the player, or has them attack, open or swap places with whatever is in the way. Returns
+/// true if they actually walked.
+pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> bool {
     let mut positions = ecs.write_storage::<Position>();
     let players = ecs.write_storage::<Player>();
+    let allies = ecs.read_storage::<Ally>();
     let mut viewsheds = ecs.write_storage::<Viewshed>();
     let combat_stats = ecs.read_storage::<CombatStats>();
     let entities = ecs.entities();
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
+    let mut noises = ecs.write_storage::<EmitsNoise>();
+    let doors = ecs.read_storage::<Door>();
+    let mut wants_to_open = ecs.write_storage::<WantsToOpenDoor>();
     let map = ecs.fetch::<Map>();
+    let mut swap_entities: Vec<(Entity, i32, i32)> = Vec::new();
+    let mut walked = false;
 
     for (entity, _player, pos, viewshed) in
         (&entities, &players, &mut positions, &mut viewsheds).join()
@@ -24,7 +46,7 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             || possible_y < 1
             || possible_y > map.height - 1
         {
-            return;
+            return false;
         }
         let destination_idx = map.xy_idx(possible_x, possible_y);
 
@@ -33,6 +55,23 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             if let Some(_player_target) = player_entity {
                 continue; // We don't want to attack ourselves
             }
+            if allies.get(*potential_target).is_some() {
+                // Allies trade places with us instead of being attacked
+                swap_entities.push((*potential_target, pos.x, pos.y));
+                continue;
+            }
+            if doors.get(*potential_target).is_some_and(|door| !door.open) {
+                // Walking into a closed door opens it, which takes the turn
+                wants_to_open
+                    .insert(
+                        entity,
+                        WantsToOpenDoor {
+                            door: *potential_target,
+                        },
+                    )
+                    .expect("Add door opening failed");
+                return false;
+            }
             let target = combat_stats.get(*potential_target);
             if let Some(_target) = target {
                 wants_to_melee
@@ -43,42 +82,512 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
                         },
                     )
                     .expect("Add target failed");
-                return;
+                return false;
             }
         }
-        if !map.blocked[destination_idx] {
-            pos.x = min(MAP_WIDTH - 1, max(0, possible_x));
-            pos.y = min(MAP_HEIGHT - 1, max(0, possible_y));
+        if !map.blocked[destination_idx] || !swap_entities.is_empty() {
+            pos.x = possible_x.clamp(0, map.width - 1);
+            pos.y = possible_y.clamp(0, map.height - 1);
 
             viewshed.dirty = true;
             let mut ppos = ecs.write_resource::<Point>();
             ppos.x = pos.x;
             ppos.y = pos.y;
+            EmitsNoise::new_noise(&mut noises, entity, NOISE_WALKING);
+            walked = true;
+        } else {
+            swap_entities.clear();
+        }
+    }
+
+    for (swapped, x, y) in swap_entities {
+        if let Some(pos) = positions.get_mut(swapped) {
+            pos.x = x;
+            pos.y = y;
+        }
+        if let Some(viewshed) = viewsheds.get_mut(swapped) {
+            viewshed.dirty = true;
+        }
+    }
+    walked
+}
+
+/// A burdened player's steps take twice as long; notes down that this one did
+fn lag_if_burdened(ecs: &mut World) {
+    let player_entity = *ecs.fetch::<Entity>();
+    if let Some(encumbrance) = ecs.write_storage::<Encumbrance>().get_mut(player_entity) {
+        encumbrance.lagging = encumbrance.is_burdened();
+    }
+}
+
+/// Takes a step, which owes the monsters a second turn if the player is burdened
+pub fn walk_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
+    if try_move_player(delta_x, delta_y, ecs) {
+        lag_if_burdened(ecs);
+    }
+    RunState::PlayerTurn
+}
+
+/// True, once, after a burdened step or roll, when the monsters are owed another turn
+pub fn take_lagging_turn(ecs: &mut World) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    ecs.write_storage::<Encumbrance>()
+        .get_mut(player_entity)
+        .is_some_and(|encumbrance| std::mem::take(&mut encumbrance.lagging))
+}
+
+/// Gives every ally the same order, logging `message` if anyone was there to hear it
+fn order_allies(ecs: &mut World, order: AllyOrder, message: &str) {
+    let mut allies = ecs.write_storage::<Ally>();
+    let mut log = ecs.write_resource::<GameLog>();
+
+    if (&allies).join().next().is_none() {
+        log.entries
+            .push("There is no one to hear your command.".to_string());
+        return;
+    }
+
+    for ally in (&mut allies).join() {
+        ally.order = order;
+    }
+    log.entries.push(message.to_string());
+}
+
+/// Sets every ally on the closest monster the player can see
+fn order_allies_to_attack(ecs: &mut World) {
+    let target = {
+        let player_entity = *ecs.fetch::<Entity>();
+        let player_pos = *ecs.fetch::<Point>();
+        let entities = ecs.entities();
+        let viewsheds = ecs.read_storage::<Viewshed>();
+        let monsters = ecs.read_storage::<Monster>();
+        let positions = ecs.read_storage::<Position>();
+
+        let player_viewshed = viewsheds.get(player_entity).unwrap();
+        (&entities, &monsters, &positions)
+            .join()
+            .map(|(entity, _monster, pos)| (entity, Point::new(pos.x, pos.y)))
+            .filter(|(_, pos)| player_viewshed.visible_tiles.contains(pos))
+            .min_by(|(_, a), (_, b)| {
+                let distance_a = DistanceAlg::Pythagoras.distance2d(player_pos, *a);
+                let distance_b = DistanceAlg::Pythagoras.distance2d(player_pos, *b);
+                distance_a.partial_cmp(&distance_b).unwrap()
+            })
+    };
+
+    match target {
+        Some((target, _)) => order_allies(
+            ecs,
+            AllyOrder::Attack(target),
+            "You order your allies to attack!",
+        ),
+        None => {
+            let mut log = ecs.write_resource::<GameLog>();
+            log.entries
+                .push("There is nothing for your allies to attack.".to_string());
+        }
+    }
+}
+
+/// Calls a bound spirit onto the nearest open tile, if one isn't already serving the player
+fn summon_spirit(ecs: &mut World) {
+    let spawn_point = {
+        let summoned = ecs.read_storage::<Summoned>();
+        let mut log = ecs.write_resource::<GameLog>();
+        if (&summoned).join().next().is_some() {
+            log.entries
+                .push("You can only bind one spirit at a time.".to_string());
+            return;
+        }
+
+        let player_pos = ecs.fetch::<Point>();
+        let map = ecs.fetch::<Map>();
+        let spawn_point = map
+            .open_tiles_near(player_pos.x, player_pos.y)
+            .into_iter()
+            .find(|point| DistanceAlg::Pythagoras.distance2d(*point, *player_pos) < 1.5);
+        match spawn_point {
+            Some(spawn_point) => {
+                log.entries
+                    .push("A bound spirit answers your call.".to_string());
+                spawn_point
+            }
+            None => {
+                log.entries
+                    .push("There is no room for a spirit to appear.".to_string());
+                return;
+            }
+        }
+    };
+
+    let player_entity = *ecs.fetch::<Entity>();
+    ecs.write_resource::<EffectQueue>().add_effect(
+        Some(player_entity),
+        Effect::Spawn {
+            name: "Bound Spirit",
+        },
+        Targets::Tile { tile: spawn_point },
+    );
+}
+
+/// Shuts an open door next to the player, as long as nothing is standing in the doorway. Returns
+/// false if there was no door to close, so no turn is spent.
+fn try_close_door(ecs: &mut World) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let entities = ecs.entities();
+    let doors = ecs.read_storage::<Door>();
+    let positions = ecs.read_storage::<Position>();
+    let mut wants_to_close = ecs.write_storage::<WantsToCloseDoor>();
+    let mut log = ecs.write_resource::<GameLog>();
+
+    let adjacent: Vec<(Entity, usize)> = (&entities, &doors, &positions)
+        .join()
+        .filter(|(_, door, pos)| {
+            door.open
+                && DistanceAlg::Chebyshev.distance2d(player_pos, Point::new(pos.x, pos.y)) < 1.5
+        })
+        .map(|(entity, _, pos)| (entity, map.xy_idx(pos.x, pos.y)))
+        .collect();
+    if adjacent.is_empty() {
+        log.entries
+            .push("There is no open door next to you.".to_string());
+        return false;
+    }
+
+    match adjacent.iter().find(|(_, idx)| !map.blocked[*idx]) {
+        Some((door, _)) => {
+            wants_to_close
+                .insert(player_entity, WantsToCloseDoor { door: *door })
+                .expect("Add door closing failed");
+            true
+        }
+        None => {
+            log.entries
+                .push("Something is standing in the doorway.".to_string());
+            false
         }
     }
 }
 
+/// Picks up something lying at the player's feet. Returns false if there was nothing there, so
+/// no turn is spent.
+fn try_pickup(ecs: &mut World) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
+    let entities = ecs.entities();
+    let items = ecs.read_storage::<Item>();
+    let positions = ecs.read_storage::<Position>();
+    let mut wants_pickup = ecs.write_storage::<WantsToPickupItem>();
+
+    let item = (&entities, &items, &positions)
+        .join()
+        .find(|(_, _, pos)| pos.x == player_pos.x && pos.y == player_pos.y)
+        .map(|(item, _, _)| item);
+    match item {
+        Some(item) => {
+            wants_pickup
+                .insert(player_entity, WantsToPickupItem { item })
+                .expect("Unable to insert pickup");
+            true
+        }
+        None => {
+            let mut log = ecs.write_resource::<GameLog>();
+            log.entries
+                .push("There is nothing here to pick up.".to_string());
+            false
+        }
+    }
+}
+
+/// Starts aiming the player's ranged weapon, if they have one with ammunition and something in
+/// range to shoot at
+fn start_targeting(ecs: &mut World) -> RunState {
+    let range = {
+        let player_entity = *ecs.fetch::<Entity>();
+        let entities = ecs.entities();
+        let equipped = ecs.read_storage::<Equipped>();
+        let weapons = ecs.read_storage::<RangedWeapon>();
+        let durability = ecs.read_storage::<Durability>();
+        let backpack = ecs.read_storage::<InBackpack>();
+        let ammunition = ecs.read_storage::<Ammunition>();
+        let mut log = ecs.write_resource::<GameLog>();
+
+        let ready = ready_ranged_weapon(
+            &entities,
+            player_entity,
+            &equipped,
+            &weapons,
+            &durability,
+            &backpack,
+            &ammunition,
+        );
+        match ready {
+            Some((weapon, _)) => weapon.range,
+            None => {
+                let wielded = (&entities, &equipped, &weapons)
+                    .join()
+                    .find(|(_, equipped, _)| equipped.owner == player_entity);
+                let message = match wielded {
+                    Some((item, _, _)) if durability.get(item).is_some_and(|d| d.is_broken()) => {
+                        "Your weapon is broken.".to_string()
+                    }
+                    Some((_, _, weapon)) => format!("You are out of {}.", weapon.ammo.plural()),
+                    None => "You have nothing to shoot with.".to_string(),
+                };
+                log.entries.push(message);
+                return RunState::AwaitingInput;
+            }
+        }
+    };
+
+    if gui::targets_in_range(ecs, range, TargetingAction::Shoot).is_empty() {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push("There is nothing in range to shoot at.".to_string());
+        return RunState::AwaitingInput;
+    }
+    RunState::ShowTargeting {
+        range,
+        action: TargetingAction::Shoot,
+    }
+}
+
+/// Casts the spell in the given hotbar slot, or starts aiming it if it needs a target
+fn start_casting(ecs: &mut World, slot: usize) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let spell = ecs
+        .read_storage::<KnownSpells>()
+        .get(player_entity)
+        .and_then(|known| known.spells.get(slot).copied());
+    let Some(spell) = spell else {
+        return RunState::AwaitingInput;
+    };
+    let info = spell.info();
+
+    let mana = ecs
+        .read_storage::<Pools>()
+        .get(player_entity)
+        .map_or(0, |pools| pools.mana.current);
+    if mana < info.mana_cost {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push(format!("You don't have enough mana for {}.", info.name));
+        return RunState::AwaitingInput;
+    }
+
+    match info.shape {
+        SpellShape::Caster => {
+            let player_pos = *ecs.fetch::<Point>();
+            ecs.write_storage::<WantsToCastSpell>()
+                .insert(
+                    player_entity,
+                    WantsToCastSpell {
+                        spell,
+                        target: player_pos,
+                        wand: None,
+                    },
+                )
+                .expect("Unable to insert spell");
+            RunState::PlayerTurn
+        }
+        SpellShape::Single
+            if gui::targets_in_range(ecs, info.range, TargetingAction::Cast(spell)).is_empty() =>
+        {
+            let mut log = ecs.write_resource::<GameLog>();
+            log.entries
+                .push(format!("There is nothing in range of {}.", info.name));
+            RunState::AwaitingInput
+        }
+        _ => RunState::ShowTargeting {
+            range: info.range,
+            action: TargetingAction::Cast(spell),
+        },
+    }
+}
+
+fn try_next_level(ecs: &mut World) -> bool {
+    let player_pos = ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let player_idx = map.xy_idx(player_pos.x, player_pos.y);
+    if map.tiles[player_idx] == TileType::DownStairs {
+        true
+    } else {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push("There is no way down from here.".to_string());
+        false
+    }
+}
+
+/// A stunned or staggered player loses their turn, whatever key they press
+fn lose_turn_if_helpless(ecs: &mut World) -> bool {
+    let player_entity = *ecs.fetch::<Entity>();
+    let helpless = ecs
+        .read_storage::<StatusEffects>()
+        .get(player_entity)
+        .and_then(|active| active.helpless());
+    if let Some(status) = helpless {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push(format!("You are {} and can't act!", status.name()));
+    }
+    helpless.is_some()
+}
+
+/// Which way a movement key points, if it is one
+fn movement_delta(key: VirtualKeyCode) -> Option<(i32, i32)> {
+    match key {
+        VirtualKeyCode::Left | VirtualKeyCode::H | VirtualKeyCode::Numpad4 => Some((-1, 0)),
+        VirtualKeyCode::Up | VirtualKeyCode::K | VirtualKeyCode::Numpad8 => Some((0, -1)),
+        VirtualKeyCode::Right | VirtualKeyCode::L | VirtualKeyCode::Numpad6 => Some((1, 0)),
+        VirtualKeyCode::Down | VirtualKeyCode::J | VirtualKeyCode::Numpad2 => Some((0, 1)),
+        VirtualKeyCode::Y | VirtualKeyCode::Numpad7 => Some((-1, -1)),
+        VirtualKeyCode::U | VirtualKeyCode::Numpad9 => Some((1, -1)),
+        VirtualKeyCode::B | VirtualKeyCode::Numpad1 => Some((-1, 1)),
+        VirtualKeyCode::N | VirtualKeyCode::Numpad3 => Some((1, 1)),
+        VirtualKeyCode::Numpad5 => Some((0, 0)),
+        _ => None,
+    }
+}
+
+/// Rolls up to DODGE_DISTANCE tiles in the given direction, out of harm's way for the next
+/// DODGE_MONSTER_TURNS monster turns. A burdened roll, like a burdened step, gives the monsters a
+/// second turn.
+pub fn try_dodge_roll(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let start = *ecs.fetch::<Point>();
+
+    let stamina = ecs
+        .read_storage::<Pools>()
+        .get(player_entity)
+        .map_or(0, |pools| pools.stamina.current);
+    if stamina < DODGE_STAMINA_COST {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries.push("You are too winded to dodge.".to_string());
+        return RunState::AwaitingInput;
+    }
+
+    let landing = {
+        let map = ecs.fetch::<Map>();
+        (1..=DODGE_DISTANCE)
+            .map(|step| Point::new(start.x + delta_x * step, start.y + delta_y * step))
+            .take_while(|tile| {
+                tile.x > 0
+                    && tile.x < map.width - 1
+                    && tile.y > 0
+                    && tile.y < map.height - 1
+                    && !map.blocked[map.xy_idx(tile.x, tile.y)]
+            })
+            .last()
+    };
+    let Some(landing) = landing else {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push("There is no room to roll that way.".to_string());
+        return RunState::AwaitingInput;
+    };
+
+    if let Some(pos) = ecs.write_storage::<Position>().get_mut(player_entity) {
+        pos.x = landing.x;
+        pos.y = landing.y;
+    }
+    if let Some(viewshed) = ecs.write_storage::<Viewshed>().get_mut(player_entity) {
+        viewshed.dirty = true;
+    }
+    *ecs.write_resource::<Point>() = landing;
+    if let Some(pools) = ecs.write_storage::<Pools>().get_mut(player_entity) {
+        pools.spend_stamina(DODGE_STAMINA_COST);
+    }
+    // Statuses tick down before the monsters' blows are resolved, so the last covered turn needs
+    // one to spare
+    StatusEffects::new_status(
+        &mut ecs.write_storage::<StatusEffects>(),
+        player_entity,
+        Status::Evading,
+        DODGE_MONSTER_TURNS + 1,
+    );
+    EmitsNoise::new_noise(
+        &mut ecs.write_storage::<EmitsNoise>(),
+        player_entity,
+        NOISE_WALKING,
+    );
+    ecs.write_resource::<GameLog>()
+        .entries
+        .push("You roll clear.".to_string());
+    lag_if_burdened(ecs);
+    RunState::PlayerTurn
+}
+
 pub fn player_input(gs: &mut State, ctx: &mut BTerm) -> RunState {
+    if ctx.key.is_some() && lose_turn_if_helpless(&mut gs.ecs) {
+        return RunState::PlayerTurn;
+    }
+
+    // Movement keys walk, or with shift held dodge-roll
+    if let Some((delta_x, delta_y)) = ctx.key.and_then(movement_delta) {
+        if ctx.shift && (delta_x, delta_y) != (0, 0) {
+            return try_dodge_roll(delta_x, delta_y, &mut gs.ecs);
+        }
+        return walk_player(delta_x, delta_y, &mut gs.ecs);
+    }
+
     match ctx.key {
         None => return RunState::AwaitingInput,
         Some(key) => match key {
-            VirtualKeyCode::Left | VirtualKeyCode::H | VirtualKeyCode::Numpad4 => {
-                try_move_player(-1, 0, &mut gs.ecs)
+            // Companion orders
+            VirtualKeyCode::F => order_allies(
+                &mut gs.ecs,
+                AllyOrder::Follow,
+                "You call your allies to follow.",
+            ),
+            VirtualKeyCode::T => order_allies(
+                &mut gs.ecs,
+                AllyOrder::Stay,
+                "You order your allies to stay.",
+            ),
+            VirtualKeyCode::A => order_allies_to_attack(&mut gs.ecs),
+            VirtualKeyCode::Z => summon_spirit(&mut gs.ecs),
+
+            VirtualKeyCode::C => {
+                if !try_close_door(&mut gs.ecs) {
+                    return RunState::AwaitingInput;
+                }
             }
-            VirtualKeyCode::Up | VirtualKeyCode::K | VirtualKeyCode::Numpad8 => {
-                try_move_player(0, -1, &mut gs.ecs)
+
+            VirtualKeyCode::M => return RunState::ShowOverview,
+
+            VirtualKeyCode::G => {
+                if !try_pickup(&mut gs.ecs) {
+                    return RunState::AwaitingInput;
+                }
             }
-            VirtualKeyCode::Right | VirtualKeyCode::L | VirtualKeyCode::Numpad6 => {
-                try_move_player(1, 0, &mut gs.ecs)
+            VirtualKeyCode::V => return start_targeting(&mut gs.ecs),
+            VirtualKeyCode::I => return RunState::ShowInventory,
+            VirtualKeyCode::R => {
+                if !shrine::try_rest(&mut gs.ecs) {
+                    return RunState::AwaitingInput;
+                }
             }
-            VirtualKeyCode::Down | VirtualKeyCode::J | VirtualKeyCode::Numpad2 => {
-                try_move_player(0, 1, &mut gs.ecs)
+
+            // The spell hotbar
+            VirtualKeyCode::Key1 => return start_casting(&mut gs.ecs, 0),
+            VirtualKeyCode::Key2 => return start_casting(&mut gs.ecs, 1),
+            VirtualKeyCode::Key3 => return start_casting(&mut gs.ecs, 2),
+            VirtualKeyCode::Key4 => return start_casting(&mut gs.ecs, 3),
+            VirtualKeyCode::Key5 => return start_casting(&mut gs.ecs, 4),
+            VirtualKeyCode::Key6 => return start_casting(&mut gs.ecs, 5),
+            VirtualKeyCode::Key7 => return start_casting(&mut gs.ecs, 6),
+            VirtualKeyCode::Key8 => return start_casting(&mut gs.ecs, 7),
+            VirtualKeyCode::Key9 => return start_casting(&mut gs.ecs, 8),
+
+            VirtualKeyCode::Period => {
+                if try_next_level(&mut gs.ecs) {
+                    return RunState::NextLevel;
+                }
+                return RunState::AwaitingInput;
             }
-            VirtualKeyCode::Y | VirtualKeyCode::Numpad7 => try_move_player(-1, -1, &mut gs.ecs),
-            VirtualKeyCode::U | VirtualKeyCode::Numpad9 => try_move_player(1, -1, &mut gs.ecs),
-            VirtualKeyCode::B | VirtualKeyCode::Numpad1 => try_move_player(-1, 1, &mut gs.ecs),
-            VirtualKeyCode::N | VirtualKeyCode::Numpad3 => try_move_player(1, 1, &mut gs.ecs),
-            VirtualKeyCode::Numpad5 => try_move_player(0, 0, &mut gs.ecs),
             _ => return RunState::AwaitingInput,
         },
     }
@@ -0,0 +1,62 @@
+use specs::prelude::*;
+
+use crate::{GameLog, Name, Pools, RunState, Status, StatusEffects};
+
+/// How many turns it takes to get back one point of mana
+const MANA_REGEN_TURNS: i32 = 4;
+/// Stamina regained on each turn spent not attacking, blocking or dodging
+const STAMINA_REGEN: i32 = 2;
+/// How long running out of stamina leaves a creature reeling
+const STAGGER_TURNS: i32 = 2;
+
+/// Refills everyone's pools a little each turn, and staggers anyone who has run out of stamina
+pub struct PoolRegenSystem {}
+
+impl<'a> System<'a> for PoolRegenSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, RunState>,
+        WriteExpect<'a, GameLog>,
+        Entities<'a>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, Pools>,
+        WriteStorage<'a, StatusEffects>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (runstate, mut log, entities, names, mut pools, mut statuses) = data;
+
+        // Only count whole turns, not every pass through the systems
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        for (entity, pools) in (&entities, &mut pools).join() {
+            if pools.mana.current >= pools.mana.max {
+                pools.mana_regen_timer = MANA_REGEN_TURNS;
+            } else {
+                pools.mana_regen_timer -= 1;
+                if pools.mana_regen_timer <= 0 {
+                    pools.mana.current += 1;
+                    pools.mana_regen_timer = MANA_REGEN_TURNS;
+                }
+            }
+
+            let staggered = statuses
+                .get(entity)
+                .is_some_and(|active| active.has(Status::Staggered));
+            if pools.stamina.current == 0 && pools.stamina.max > 0 && !staggered {
+                StatusEffects::new_status(&mut statuses, entity, Status::Staggered, STAGGER_TURNS);
+                if let Some(name) = names.get(entity) {
+                    log.entries.push(format!("{} is staggered!", name.name));
+                }
+            }
+
+            if !pools.exerted {
+                pools.stamina.current =
+                    i32::min(pools.stamina.max, pools.stamina.current + STAMINA_REGEN);
+            }
+            pools.exerted = false;
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::ops::Deref;
+
+use bracket_lib::{random::RandomNumberGenerator, terminal::Point};
+use specs::{prelude::*, storage::MaskedStorage};
+
+use crate::{
+    item_name, roll_procs, spawner, AmmoKind, Ammunition, Awareness, AwarenessState, CombatStats,
+    Durability, Effect, EffectQueue, EmitsNoise, EquipmentBonus, EquipmentSlot, Equipped, GameLog,
+    IdentificationRegistry, InBackpack, Map, Name, Pools, Position, RangedWeapon, Targets,
+    WantsToShoot, ATTACK_STAMINA_COST, NOISE_FIGHTING, SNEAK_ATTACK_MULTIPLIER,
+};
+
+/// The ranged weapon `shooter` is wielding, along with the stack in their pack it shoots from,
+/// if they have both and the weapon isn't broken
+pub fn ready_ranged_weapon<D, W>(
+    entities: &Entities,
+    shooter: Entity,
+    equipped: &ReadStorage<Equipped>,
+    weapons: &ReadStorage<RangedWeapon>,
+    durability: &Storage<Durability, W>,
+    backpack: &ReadStorage<InBackpack>,
+    ammunition: &Storage<Ammunition, D>,
+) -> Option<(RangedWeapon, Entity)>
+where
+    D: Deref<Target = MaskedStorage<Ammunition>>,
+    W: Deref<Target = MaskedStorage<Durability>>,
+{
+    let weapon = (entities, equipped, weapons)
+        .join()
+        .find(|(_, equipped, _)| equipped.owner == shooter)
+        .filter(|(item, _, _)| durability.get(*item).is_none_or(|d| !d.is_broken()))
+        .map(|(_, _, weapon)| *weapon)?;
+    let stack = (entities, backpack, ammunition)
+        .join()
+        .find(|(_, carried, ammo)| {
+            carried.owner == shooter && ammo.kind == weapon.ammo && ammo.count > 0
+        })
+        .map(|(entity, _, _)| entity)?;
+    Some((weapon, stack))
+}
+
+/// Resolves shots. Each one spends a missile from the shooter's pack, needs a clear line of fire
+/// to its target, and may leave the missile lying at the target's feet to be picked up again.
+pub struct RangedCombatSystem {}
+
+impl<'a> System<'a> for RangedCombatSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, GameLog>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, IdentificationRegistry>,
+        Read<'a, LazyUpdate>,
+        WriteStorage<'a, WantsToShoot>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, RangedWeapon>,
+        WriteStorage<'a, Durability>,
+        ReadStorage<'a, EquipmentBonus>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, Ammunition>,
+        WriteStorage<'a, Pools>,
+        Write<'a, EffectQueue>,
+        WriteStorage<'a, Awareness>,
+        WriteStorage<'a, EmitsNoise>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut log,
+            map,
+            registry,
+            lazy,
+            mut wants_shoot,
+            names,
+            combat_stats,
+            positions,
+            equipped,
+            weapons,
+            mut durability,
+            bonuses,
+            backpack,
+            mut ammunition,
+            mut pools,
+            mut effects,
+            mut awareness,
+            mut noises,
+        ) = data;
+
+        let mut rng = RandomNumberGenerator::new();
+        let mut recovered: Vec<(Point, AmmoKind, i32)> = Vec::new();
+        let shots: Vec<(Entity, Entity)> = (&entities, &wants_shoot)
+            .join()
+            .map(|(entity, wants_shoot)| (entity, wants_shoot.target))
+            .collect();
+        wants_shoot.clear();
+
+        for (shooter, target) in shots {
+            let (Some(stats), Some(target_stats)) =
+                (combat_stats.get(shooter), combat_stats.get(target))
+            else {
+                continue;
+            };
+            if stats.hp <= 0 || target_stats.hp <= 0 {
+                continue;
+            }
+            let (Some(from), Some(to)) = (positions.get(shooter), positions.get(target)) else {
+                continue;
+            };
+            let (from, to) = (Point::new(from.x, from.y), Point::new(to.x, to.y));
+            let Some((weapon, stack)) = ready_ranged_weapon(
+                &entities,
+                shooter,
+                &equipped,
+                &weapons,
+                &durability,
+                &backpack,
+                &ammunition,
+            ) else {
+                continue;
+            };
+            let name = &names.get(shooter).unwrap().name;
+            let target_name = &names.get(target).unwrap().name;
+
+            // Something may have stepped into the way since the shot was lined up
+            if !map.has_line_of_fire(from, to) {
+                log.entries.push(format!("{} has no clear shot.", name));
+                continue;
+            }
+
+            let ammo = ammunition.get_mut(stack).unwrap();
+            ammo.count -= 1;
+            if ammo.count == 0 {
+                entities
+                    .delete(stack)
+                    .expect("Unable to delete spent ammunition");
+            }
+
+            if let Some(pools) = pools.get_mut(shooter) {
+                pools.spend_stamina(ATTACK_STAMINA_COST);
+            }
+            // Every shot wears the weapon a little
+            let weapon_item = (&entities, &equipped, &weapons)
+                .join()
+                .find(|(_, equipped, _)| equipped.owner == shooter)
+                .map(|(item, _, _)| item);
+            if let Some(item) = weapon_item {
+                if durability.get_mut(item).is_some_and(|d| d.wear()) {
+                    log.entries.push(format!(
+                        "{}'s {} breaks!",
+                        name,
+                        item_name(item, &names, &registry)
+                    ));
+                }
+            }
+            log.entries
+                .push(format!("{} shoots at {}.", name, target_name));
+            let bonus =
+                EquipmentBonus::in_slot(shooter, EquipmentSlot::Ranged, &equipped, &bonuses);
+            let power = weapon.power + bonus.map_or(0, |bonus| bonus.power);
+            let defense =
+                target_stats.defense + EquipmentBonus::defense_of(target, &equipped, &bonuses);
+            let mut damage = i32::max(0, power - defense);
+            if let Some(target_awareness) = awareness.get_mut(target) {
+                if target_awareness.state != AwarenessState::Aware {
+                    damage *= SNEAK_ATTACK_MULTIPLIER;
+                    log.entries
+                        .push(format!("{} catches {} unawares!", name, target_name));
+                }
+                target_awareness.state = AwarenessState::Aware;
+                target_awareness.investigating = None;
+            }
+
+            if damage == 0 {
+                log.entries
+                    .push(format!("{}'s shot glances off {}.", name, target_name));
+            } else {
+                effects.add_effect(
+                    Some(shooter),
+                    Effect::Damage {
+                        amount: damage,
+                        kind: bonus
+                            .and_then(|bonus| bonus.damage_type)
+                            .unwrap_or(weapon.ammo.damage_type()),
+                    },
+                    Targets::Single { target },
+                );
+                if let Some(bonus) = bonus {
+                    roll_procs(&mut rng, &mut effects, shooter, target, &bonus.procs);
+                }
+            }
+            EmitsNoise::new_noise(&mut noises, target, NOISE_FIGHTING);
+
+            if rng.roll_dice(1, 100) <= weapon.ammo.recovery_chance() {
+                match recovered
+                    .iter_mut()
+                    .find(|(point, kind, _)| *point == to && *kind == weapon.ammo)
+                {
+                    Some((_, _, count)) => *count += 1,
+                    None => recovered.push((to, weapon.ammo, 1)),
+                }
+            }
+        }
+
+        // Missiles that survived land on the floor, joining any of the same kind already there
+        for (point, kind, count) in recovered {
+            let on_floor = (&positions, &mut ammunition)
+                .join()
+                .find(|(pos, ammo)| pos.x == point.x && pos.y == point.y && ammo.kind == kind);
+            match on_floor {
+                Some((_, ammo)) => ammo.count += count,
+                None => {
+                    spawner::ammunition_stack(
+                        lazy.create_entity(&entities).with(Position {
+                            x: point.x,
+                            y: point.y,
+                        }),
+                        kind,
+                        count,
+                    )
+                    .build();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use bracket_lib::terminal::Point;
+use specs::prelude::*;
+
+use crate::{
+    spawner, Boss, CombatStats, GameLog, InBackpack, Map, Monster, Name, Pools, Position, Shrine,
+    SoulCache, Souls, StatusEffects, SufferDamage,
+};
+
+/// What happens when the player dies
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum GameMode {
+    /// Death ends the run
+    Permadeath,
+    /// Death sends the player back to the last shrine they rested at, leaving their souls behind.
+    /// Checkpoints are per floor: floors left behind are gone, so a new floor's entrance is where
+    /// the player wakes until they rest at one of its shrines.
+    Shrines,
+}
+
+/// Where the player wakes after dying, always on the current floor: its entrance, until they rest
+/// at one of its shrines
+pub struct RespawnPoint {
+    pub pos: Position,
+}
+
+/// The monsters a level started out with, by map index and spawn name. Resting or dying brings
+/// them all back, bosses excepted.
+pub struct LevelRoster {
+    pub monsters: Vec<(usize, String)>,
+}
+
+/// Notes down the monsters on a freshly spawned level
+pub fn record_roster(ecs: &mut World) {
+    let monsters = {
+        let map = ecs.fetch::<Map>();
+        let monsters = ecs.read_storage::<Monster>();
+        let bosses = ecs.read_storage::<Boss>();
+        let positions = ecs.read_storage::<Position>();
+        let names = ecs.read_storage::<Name>();
+        (&monsters, !&bosses, &positions, &names)
+            .join()
+            .map(|(_, _, pos, name)| (map.xy_idx(pos.x, pos.y), name.name.clone()))
+            .collect()
+    };
+    ecs.insert(LevelRoster { monsters });
+}
+
+/// Clears away every monster on the level that isn't a boss, along with what they carry, and
+/// spawns the level's roster afresh
+fn respawn_monsters(ecs: &mut World) {
+    let doomed: Vec<Entity> = {
+        let entities = ecs.entities();
+        let monsters = ecs.read_storage::<Monster>();
+        let bosses = ecs.read_storage::<Boss>();
+        let backpack = ecs.read_storage::<InBackpack>();
+        let respawning: Vec<Entity> = (&entities, &monsters, !&bosses)
+            .join()
+            .map(|(entity, _, _)| entity)
+            .collect();
+        let carried = (&entities, &backpack)
+            .join()
+            .filter(|(_, carried)| respawning.contains(&carried.owner))
+            .map(|(item, _)| item);
+        carried.chain(respawning.iter().copied()).collect()
+    };
+    ecs.delete_entities(&doomed)
+        .expect("Unable to clear away monsters");
+
+    let roster = ecs.fetch::<LevelRoster>().monsters.clone();
+    for spawn in roster.iter() {
+        spawner::spawn_entity(ecs, spawn);
+    }
+}
+
+/// Heals the player completely, refills their pools and clears whatever ails them
+fn restore_player(ecs: &mut World) {
+    let player_entity = *ecs.fetch::<Entity>();
+    if let Some(stats) = ecs.write_storage::<CombatStats>().get_mut(player_entity) {
+        stats.hp = stats.max_hp;
+    }
+    if let Some(pools) = ecs.write_storage::<Pools>().get_mut(player_entity) {
+        pools.mana.current = pools.mana.max;
+        pools.stamina.current = pools.stamina.max;
+    }
+    ecs.write_storage::<StatusEffects>().remove(player_entity);
+    ecs.write_storage::<SufferDamage>().remove(player_entity);
+}
+
+/// Rests at the shrine the player is standing on, if there is one: they are restored and will
+/// wake here if they die, but every monster on the level comes back too
+pub fn try_rest(ecs: &mut World) -> bool {
+    let player_pos = *ecs.fetch::<Point>();
+    let at_shrine = (
+        &ecs.read_storage::<Position>(),
+        &ecs.read_storage::<Shrine>(),
+    )
+        .join()
+        .any(|(pos, _)| pos.x == player_pos.x && pos.y == player_pos.y);
+    if !at_shrine {
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push("There is no shrine here to rest at.".to_string());
+        return false;
+    }
+
+    restore_player(ecs);
+    respawn_monsters(ecs);
+    ecs.insert(RespawnPoint {
+        pos: Position {
+            x: player_pos.x,
+            y: player_pos.y,
+        },
+    });
+
+    let mut log = ecs.write_resource::<GameLog>();
+    log.entries
+        .push("You rest at the shrine. Your wounds close, and the dead stir.".to_string());
+    true
+}
+
+/// Brings a dead player back to life, returning where they should wake. Their souls stay behind
+/// in a cache where they fell, and any cache left by an earlier death is gone for good.
+pub fn respawn_player(ecs: &mut World) -> Position {
+    let player_entity = *ecs.fetch::<Entity>();
+    let death_pos = *ecs.fetch::<Point>();
+
+    let old_caches: Vec<Entity> = (&ecs.entities(), &ecs.read_storage::<SoulCache>())
+        .join()
+        .map(|(entity, _)| entity)
+        .collect();
+    if !old_caches.is_empty() {
+        ecs.delete_entities(&old_caches)
+            .expect("Unable to delete soul cache");
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push("The souls you left behind before are lost forever.".to_string());
+    }
+
+    let souls = ecs
+        .write_storage::<Souls>()
+        .get_mut(player_entity)
+        .map_or(0, |souls| std::mem::take(&mut souls.amount));
+    if souls > 0 {
+        spawner::soul_cache(ecs, death_pos.x, death_pos.y, souls);
+        let mut log = ecs.write_resource::<GameLog>();
+        log.entries
+            .push(format!("You die, and leave {} souls behind.", souls));
+    }
+
+    restore_player(ecs);
+    respawn_monsters(ecs);
+
+    let mut log = ecs.write_resource::<GameLog>();
+    log.entries.push("You awaken at the shrine.".to_string());
+    ecs.fetch::<RespawnPoint>().pos.clone()
+}
@@ -0,0 +1,37 @@
+use bracket_lib::terminal::Point;
+use specs::prelude::*;
+
+use crate::{GameLog, Position, SoulCache, Souls};
+
+/// Hands a soul cache back to the player once they step onto it
+pub struct SoulSystem {}
+
+impl<'a> System<'a> for SoulSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Point>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, SoulCache>,
+        WriteStorage<'a, Souls>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, player_entity, player_pos, mut log, positions, caches, mut souls) = data;
+
+        for (cache_entity, pos, cache) in (&entities, &positions, &caches).join() {
+            if pos.x != player_pos.x || pos.y != player_pos.y {
+                continue;
+            }
+            if let Some(player_souls) = souls.get_mut(*player_entity) {
+                player_souls.amount += cache.souls;
+            }
+            log.entries
+                .push(format!("You reclaim {} souls.", cache.souls));
+            entities
+                .delete(cache_entity)
+                .expect("Unable to delete soul cache");
+        }
+    }
+}
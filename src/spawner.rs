@@ -1,40 +1,137 @@
 use std::collections::HashMap;
 
 use crate::{
-    BlocksTile, CombatStats, Map, Monster, Name, Position, Renderable, TileType, Viewshed,
+    loot, monster_def, Ally, AllyOrder, AmmoKind, Ammunition, Attributes, Awareness,
+    AwarenessState, BlocksTile, BlocksVisibility, Boss, CombatStats, Consumable, Cursed,
+    DamageType, Door, Durability, EquipmentSlot, Equippable, Equipped, IdentifiesItems, InBackpack,
+    Item, ItemRarity, KnownSpells, LightSource, LootRoll, Map, MeleeAttack, MeleeWeapon, Monster,
+    Name, Player, Pool, Pools, Position, ProvidesHealing, ProvidesMana, RangedAI, RangedWeapon,
+    RemovesCurses, Renderable, RepairsItems, Resistance, Resistances, Shield, Shrine, SoulCache,
+    Souls, Spell, Stealth, Summoned, TeachesSpell, TeleportsUser, TileType, Viewshed, Wand,
+    CLOSED_DOOR_GLYPH,
 };
 
 use super::Rect;
 use bracket_lib::{color::RGB, random::RandomNumberGenerator, terminal::FontCharType};
-use specs::{Builder, World, WorldExt};
+use specs::{world::EntityBuilder, Builder, Entity, World, WorldExt};
 
-pub const MAP_WIDTH: i32 = 80;
-pub const MAP_HEIGHT: i32 = 43;
 pub const MAX_MONSTERS: i32 = 3;
-pub const MAP_COUNT: usize = (MAP_WIDTH * MAP_HEIGHT) as usize;
 
-pub fn random_monster(ecs: &mut World, x: i32, y: i32) {
-    let monster_name: String;
-    let glyph: FontCharType;
-    let roll: i32;
-    {
+/// Spells that come in wands
+const WAND_SPELLS: [Spell; 3] = [Spell::Firebolt, Spell::FrostCone, Spell::Lightning];
+
+/// Spells whose books can turn up lying around; everyone starts out knowing Firebolt
+const FOUND_SPELLS: [Spell; 6] = [
+    Spell::Fireball,
+    Spell::Mend,
+    Spell::Blink,
+    Spell::FrostCone,
+    Spell::Lightning,
+    Spell::PoisonCloud,
+];
+
+/// The player, with no gear yet
+pub fn player(ecs: &mut World, x: i32, y: i32) -> Entity {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0x40,
+            fg: RGB::named(bracket_lib::terminal::YELLOW),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Player {})
+        .with(Name {
+            name: "Player".to_string(),
+        })
+        .with(Viewshed {
+            visible_tiles: Vec::new(),
+            range: 8,
+            dirty: true,
+        })
+        .with(CombatStats {
+            max_hp: 30,
+            hp: 30,
+            defense: 2,
+            power: 3,
+        })
+        .with(BlocksTile {})
+        .with(Stealth { skill: 3 })
+        .with(Attributes { might: 3 })
+        .with(Souls { amount: 0 })
+        .with(LightSource {
+            color: RGB::from_f32(1.0, 0.9, 0.7),
+            range: 6,
+        })
+        .with(pools(10, 12))
+        .with(KnownSpells {
+            spells: vec![Spell::Firebolt],
+        })
+        .build()
+}
+
+/// A brute who hides behind a buckler
+fn orc(ecs: &mut World, x: i32, y: i32) {
+    let orc = monster(ecs, x, y, "Orc").build();
+    buckler(ecs.create_entity())
+        .with(InBackpack { owner: orc })
+        .with(Equipped {
+            owner: orc,
+            slot: EquipmentSlot::Shield,
+        })
+        .build();
+}
+
+/// A goblin with a bow, who would rather shoot than close to blows
+fn goblin_archer(ecs: &mut World, x: i32, y: i32) {
+    let archer = monster(ecs, x, y, "Goblin Archer").build();
+    shortbow(ecs.create_entity())
+        .with(InBackpack { owner: archer })
+        .with(Equipped {
+            owner: archer,
+            slot: EquipmentSlot::Ranged,
+        })
+        .build();
+    let arrows = RandomNumberGenerator::new().roll_dice(2, 4) + 4;
+    ammunition_stack(ecs.create_entity(), AmmoKind::Arrows, arrows)
+        .with(InBackpack { owner: archer })
+        .build();
+}
+
+/// Full pools of mana and stamina
+pub fn pools(max_mana: i32, max_stamina: i32) -> Pools {
+    Pools {
+        mana: Pool {
+            current: max_mana,
+            max: max_mana,
+        },
+        mana_regen_timer: 0,
+        stamina: Pool {
+            current: max_stamina,
+            max: max_stamina,
+        },
+        exerted: false,
+    }
+}
+
+/// A monster as resources/monsters.txt describes it, ready for any gear it carries
+fn monster<'a>(ecs: &'a mut World, x: i32, y: i32, name: &str) -> EntityBuilder<'a> {
+    let def = monster_def(name).unwrap_or_else(|| panic!("No monster called {name} in the data"));
+
+    // Some monsters are found sleeping
+    let state = {
         let mut rng = RandomNumberGenerator::new();
-        roll = rng.roll_dice(1, 2);
-        match roll {
-            1 => {
-                glyph = 0x67;
-                monster_name = "Goblin".to_string()
-            }
-            _ => {
-                glyph = 0x6F;
-                monster_name = "Orc".to_string()
-            }
+        if rng.roll_dice(1, 3) == 1 {
+            AwarenessState::Asleep
+        } else {
+            AwarenessState::Unaware
         }
-    }
-    ecs.create_entity()
+    };
+
+    let mut builder = ecs
+        .create_entity()
         .with(Position { x, y })
         .with(Renderable {
-            glyph: glyph,
+            glyph: def.glyph,
             fg: RGB::named(bracket_lib::terminal::RED),
             bg: RGB::named(bracket_lib::terminal::BLACK),
         })
@@ -44,68 +141,698 @@ pub fn random_monster(ecs: &mut World, x: i32, y: i32) {
             dirty: true,
         })
         .with(Monster {})
+        .with(Souls { amount: def.souls })
+        .with(Awareness {
+            state,
+            perception: def.perception,
+            investigating: None,
+        })
         .with(Name {
-            name: monster_name.to_string(),
+            name: def.name.clone(),
         })
         .with(BlocksTile {})
         .with(CombatStats {
-            max_hp: 16,
-            hp: 16,
-            defense: 1,
-            power: 4,
+            max_hp: def.hp,
+            hp: def.hp,
+            defense: def.defense,
+            power: def.power,
+        })
+        .with(pools(def.mana, def.stamina));
+    if let Some(kind) = def.attack {
+        builder = builder.with(MeleeAttack { kind });
+    }
+    if !def.resistances.is_empty() {
+        builder = builder.with(Resistances {
+            against: def.resistances.clone(),
+        });
+    }
+    if !def.spells.is_empty() {
+        builder = builder.with(KnownSpells {
+            spells: def.spells.clone(),
+        });
+    }
+    if let Some(min_distance) = def.keep_distance {
+        builder = builder.with(RangedAI { min_distance });
+    }
+    if def.boss {
+        builder = builder.with(Boss {});
+    }
+    builder
+}
+
+fn health_potion(ecs: &mut World, x: i32, y: i32) {
+    potion(ecs, x, y, "Health Potion", 25)
+        .with(ProvidesHealing { heal_amount: 8 })
+        .build();
+}
+
+fn mana_potion(ecs: &mut World, x: i32, y: i32) {
+    potion(ecs, x, y, "Mana Potion", 30)
+        .with(ProvidesMana { mana_amount: 6 })
+        .build();
+}
+
+/// Every potion looks alike, so only its name, once known, gives it away
+fn potion<'a>(ecs: &'a mut World, x: i32, y: i32, name: &str, value: i32) -> EntityBuilder<'a> {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0xAD,
+            fg: RGB::named(bracket_lib::terminal::MAGENTA),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item { weight: 0.5, value })
+        .with(Consumable {})
+}
+
+fn identify_scroll(ecs: &mut World, x: i32, y: i32) {
+    scroll(ecs, x, y, "Scroll of Identify", 30)
+        .with(IdentifiesItems {})
+        .build();
+}
+
+fn teleport_scroll(ecs: &mut World, x: i32, y: i32) {
+    scroll(ecs, x, y, "Scroll of Teleportation", 40)
+        .with(TeleportsUser { range: 20 })
+        .build();
+}
+
+fn remove_curse_scroll(ecs: &mut World, x: i32, y: i32) {
+    scroll(ecs, x, y, "Scroll of Remove Curse", 50)
+        .with(RemovesCurses {})
+        .build();
+}
+
+fn scroll<'a>(ecs: &'a mut World, x: i32, y: i32, name: &str, value: i32) -> EntityBuilder<'a> {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0x3F,
+            fg: RGB::named(bracket_lib::terminal::WHEAT),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item { weight: 0.2, value })
+        .with(Consumable {})
+}
+
+/// Oil, rivets and a whetstone, enough to mend everything the user carries once
+fn repair_kit(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0x9D,
+            fg: RGB::named(bracket_lib::terminal::ORANGE),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: "Repair Kit".to_string(),
+        })
+        .with(Item {
+            weight: 3.0,
+            value: 40,
+        })
+        .with(Consumable {})
+        .with(RepairsItems {})
+        .build();
+}
+
+/// A wand holding a few charges of `spell`
+fn wand(ecs: &mut World, x: i32, y: i32, spell: Spell) {
+    let charges = RandomNumberGenerator::new().roll_dice(1, 3) + 2;
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0x2D,
+            fg: RGB::named(bracket_lib::terminal::GOLD),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: format!("Wand of {}", spell.info().name),
+        })
+        .with(Item {
+            weight: 1.0,
+            value: 100,
+        })
+        .with(Wand {
+            spell,
+            charges,
+            max_charges: charges,
+        })
+        .build();
+}
+
+/// A book that teaches `spell` to whoever reads it, crumbling to dust afterwards
+fn spellbook(ecs: &mut World, x: i32, y: i32, spell: Spell) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0x3F,
+            fg: RGB::named(bracket_lib::terminal::PURPLE),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: format!("Spellbook of {}", spell.info().name),
+        })
+        .with(Item {
+            weight: 2.0,
+            value: 150,
+        })
+        .with(Consumable {})
+        .with(TeachesSpell { spell })
+        .build();
+}
+
+/// A long knife, quick but light
+fn dagger<B: Builder>(builder: B) -> B {
+    melee_weapon(
+        builder,
+        "Dagger",
+        RGB::named(bracket_lib::terminal::SILVER),
+        1,
+        DamageType::Piercing,
+        30,
+    )
+    .with(Item {
+        weight: 1.0,
+        value: 10,
+    })
+}
+
+/// A plain soldier's blade
+fn short_sword<B: Builder>(builder: B) -> B {
+    melee_weapon(
+        builder,
+        "Short Sword",
+        RGB::named(bracket_lib::terminal::LIGHTSTEELBLUE),
+        2,
+        DamageType::Slashing,
+        40,
+    )
+    .with(Item {
+        weight: 3.0,
+        value: 25,
+    })
+}
+
+/// A flanged iron club, for cracking what blades glance off
+fn mace<B: Builder>(builder: B) -> B {
+    melee_weapon(
+        builder,
+        "Mace",
+        RGB::named(bracket_lib::terminal::SLATE_GRAY),
+        2,
+        DamageType::Bludgeoning,
+        50,
+    )
+    .with(Item {
+        weight: 6.0,
+        value: 30,
+    })
+}
+
+fn melee_weapon<B: Builder>(
+    builder: B,
+    name: &str,
+    fg: RGB,
+    power: i32,
+    kind: DamageType,
+    durability: i32,
+) -> B {
+    builder
+        .with(Renderable {
+            glyph: 0x2F,
+            fg,
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Melee,
+        })
+        .with(MeleeWeapon { power, kind })
+        .with(Durability::new(durability))
+}
+
+/// A leather sling; short ranged, but stones are easy to find again
+fn sling<B: Builder>(builder: B) -> B {
+    ranged_weapon(
+        builder,
+        "Sling",
+        RGB::named(bracket_lib::terminal::TAN),
+        5,
+        4,
+        AmmoKind::SlingStones,
+        40,
+    )
+    .with(Item {
+        weight: 0.5,
+        value: 5,
+    })
+}
+
+/// A hunting bow, hitting harder and further than a sling
+fn shortbow<B: Builder>(builder: B) -> B {
+    ranged_weapon(
+        builder,
+        "Shortbow",
+        RGB::named(bracket_lib::terminal::BURLYWOOD),
+        7,
+        5,
+        AmmoKind::Arrows,
+        30,
+    )
+    .with(Item {
+        weight: 2.0,
+        value: 30,
+    })
+}
+
+fn ranged_weapon<B: Builder>(
+    builder: B,
+    name: &str,
+    fg: RGB,
+    range: i32,
+    power: i32,
+    ammo: AmmoKind,
+    durability: i32,
+) -> B {
+    builder
+        .with(Renderable {
+            glyph: 0x7D,
+            fg,
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Ranged,
+        })
+        .with(RangedWeapon { range, power, ammo })
+        .with(Durability::new(durability))
+}
+
+/// A small round shield, good for turning aside a blow or two
+fn buckler<B: Builder>(builder: B) -> B {
+    builder
+        .with(Renderable {
+            glyph: 0x5B,
+            fg: RGB::named(bracket_lib::terminal::BURLYWOOD),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: "Buckler".to_string(),
         })
+        .with(Item {
+            weight: 5.0,
+            value: 15,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Shield,
+        })
+        .with(Shield { block: 2 })
+        .with(Durability::new(25))
+}
+
+/// Builds the base item loot is rolled on
+fn base_item<B: Builder>(builder: B, base: &str) -> B {
+    match base {
+        "Dagger" => dagger(builder),
+        "Short Sword" => short_sword(builder),
+        "Mace" => mace(builder),
+        "Sling" => sling(builder),
+        "Shortbow" => shortbow(builder),
+        "Buckler" => buckler(builder),
+        _ => panic!("No base item called {}", base),
+    }
+}
+
+/// A rolled piece of loot lying at (x, y)
+pub fn loot_item(ecs: &mut World, x: i32, y: i32, roll: &LootRoll) {
+    let builder = base_item(ecs.create_entity(), roll.base)
+        .with(Position { x, y })
+        // The rolled name replaces the base item's own
+        .with(Name {
+            name: roll.name.clone(),
+        })
+        .with(ItemRarity {
+            rarity: roll.rarity,
+        })
+        .with(roll.bonus.clone());
+    let item = if roll.cursed {
+        builder.with(Cursed { known: false }).build()
+    } else {
+        builder.build()
+    };
+    if let Some(item) = ecs.write_storage::<Item>().get_mut(item) {
+        item.value *= roll.rarity.value_multiplier();
+    }
+}
+
+/// A stack of `count` missiles, either lying somewhere or carried, depending on what the builder
+/// adds
+pub fn ammunition_stack<B: Builder>(builder: B, kind: AmmoKind, count: i32) -> B {
+    let (glyph, fg, name, weight) = match kind {
+        AmmoKind::SlingStones => (
+            0x07,
+            RGB::named(bracket_lib::terminal::GREY),
+            "Sling Stones",
+            0.1,
+        ),
+        AmmoKind::Arrows => (
+            0x18,
+            RGB::named(bracket_lib::terminal::BURLYWOOD),
+            "Arrows",
+            0.05,
+        ),
+    };
+    builder
+        .with(Renderable {
+            glyph,
+            fg,
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item { weight, value: 1 })
+        .with(Ammunition { kind, count })
+}
+
+/// Gives the player a sword and a sling to start with, and a pouch of stones for it
+pub fn starting_kit(ecs: &mut World, player: Entity) {
+    short_sword(ecs.create_entity())
+        .with(InBackpack { owner: player })
+        .with(Equipped {
+            owner: player,
+            slot: EquipmentSlot::Melee,
+        })
+        .build();
+    sling(ecs.create_entity())
+        .with(InBackpack { owner: player })
+        .with(Equipped {
+            owner: player,
+            slot: EquipmentSlot::Ranged,
+        })
+        .build();
+    ammunition_stack(ecs.create_entity(), AmmoKind::SlingStones, 20)
+        .with(InBackpack { owner: player })
+        .build();
+    buckler(ecs.create_entity())
+        .with(InBackpack { owner: player })
+        .with(Equipped {
+            owner: player,
+            slot: EquipmentSlot::Shield,
+        })
+        .build();
+}
+
+/// A door set into a room's entrance; they all start out closed
+fn door(ecs: &mut World, x: i32, y: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: CLOSED_DOOR_GLYPH,
+            fg: RGB::named(bracket_lib::terminal::CHOCOLATE),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: "Door".to_string(),
+        })
+        .with(Door { open: false })
+        .with(BlocksTile {})
+        .with(BlocksVisibility {})
+        .build();
+}
+
+/// A torch burning in a bracket on the wall
+fn torch(ecs: &mut World, x: i32, y: i32) {
+    light_fixture(ecs, x, y, 0xE7, "Torch", RGB::from_f32(1.0, 0.6, 0.2), 6).build();
+}
+
+/// A bowl of coals set in the middle of a room
+fn brazier(ecs: &mut World, x: i32, y: i32) {
+    light_fixture(ecs, x, y, 0xE8, "Brazier", RGB::from_f32(1.0, 0.4, 0.1), 8).build();
+}
+
+/// Somewhere to rest, and to wake again after dying
+fn shrine(ecs: &mut World, x: i32, y: i32) {
+    light_fixture(ecs, x, y, 0xF4, "Shrine", RGB::from_f32(0.9, 0.9, 0.6), 5)
+        .with(Shrine {})
         .build();
 }
 
-pub fn spawn_room(ecs: &mut World, room: &Rect) {
+/// The souls a dead player left behind
+pub fn soul_cache(ecs: &mut World, x: i32, y: i32, souls: i32) {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: 0x0F,
+            fg: RGB::named(bracket_lib::terminal::LIGHT_CYAN),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: "Soul Cache".to_string(),
+        })
+        .with(SoulCache { souls })
+        .build();
+}
+
+/// A cluster of pale, faintly shining cave fungus
+fn glowing_fungus(ecs: &mut World, x: i32, y: i32) {
+    light_fixture(
+        ecs,
+        x,
+        y,
+        0x05,
+        "Glowing Fungus",
+        RGB::from_f32(0.3, 0.9, 0.6),
+        4,
+    )
+    .build();
+}
+
+fn light_fixture<'a>(
+    ecs: &'a mut World,
+    x: i32,
+    y: i32,
+    glyph: FontCharType,
+    name: &str,
+    color: RGB,
+    range: i32,
+) -> EntityBuilder<'a> {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph,
+            fg: color,
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(LightSource { color, range })
+}
+
+/// A loyal hound that starts the game at the player's side
+pub fn hound(ecs: &mut World, x: i32, y: i32) {
+    ally(ecs, x, y, 0x64, "Hound", 12, 1, 3)
+        .with(MeleeAttack {
+            kind: DamageType::Piercing,
+        })
+        .build();
+}
+
+/// A spirit bound to the player's service; only one may be summoned at a time
+pub fn bound_spirit(ecs: &mut World, x: i32, y: i32) {
+    ally(ecs, x, y, 0x73, "Bound Spirit", 8, 0, 4)
+        .with(Summoned {})
+        .with(MeleeAttack {
+            kind: DamageType::Spirit,
+        })
+        .with(Resistances {
+            against: vec![
+                (DamageType::Poison, Resistance::Immune),
+                (DamageType::Spirit, Resistance::Weak),
+            ],
+        })
+        .build();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn ally<'a>(
+    ecs: &'a mut World,
+    x: i32,
+    y: i32,
+    glyph: FontCharType,
+    name: &str,
+    hp: i32,
+    defense: i32,
+    power: i32,
+) -> EntityBuilder<'a> {
+    ecs.create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph,
+            fg: RGB::named(bracket_lib::terminal::CYAN),
+            bg: RGB::named(bracket_lib::terminal::BLACK),
+        })
+        .with(Viewshed {
+            visible_tiles: Vec::new(),
+            range: 8,
+            dirty: true,
+        })
+        .with(Ally {
+            order: AllyOrder::Follow,
+        })
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(pools(0, 10))
+        .with(BlocksTile {})
+        .with(CombatStats {
+            max_hp: hp,
+            hp,
+            defense,
+            power,
+        })
+}
+
+/// Picks spawn points for the open floor inside a room
+pub fn spawn_room(
+    map: &Map,
+    rng: &mut RandomNumberGenerator,
+    room: &Rect,
+    spawn_list: &mut Vec<(usize, String)>,
+) {
     let mut possible_targets: Vec<usize> = Vec::new();
-    {
-        let map = ecs.fetch::<Map>();
-        for y in room.y1 + 1..room.y2 {
-            for x in room.x1 + 1..room.x2 {
-                let idx = map.xy_idx(x, y);
-                if map.tiles[idx] == TileType::Floor {
-                    possible_targets.push(idx);
-                }
+    for y in room.y1 + 1..room.y2 {
+        for x in room.x1 + 1..room.x2 {
+            let idx = map.xy_idx(x, y);
+            if map.tiles[idx] == TileType::Floor {
+                possible_targets.push(idx);
             }
         }
     }
 
-    spawn_region(ecs, &possible_targets);
+    spawn_region(rng, &possible_targets, spawn_list);
 }
 
-fn spawn_entity(ecs: &mut World, spawn: &(&usize, &String)) {
-    let x = (*spawn.0 % MAP_WIDTH as usize) as i32;
-    let y = (*spawn.0 / MAP_WIDTH as usize) as i32;
+/// Picks up to MAX_MONSTERS distinct tiles from the area, and what should spawn on each
+pub fn spawn_region(
+    rng: &mut RandomNumberGenerator,
+    area: &[usize],
+    spawn_list: &mut Vec<(usize, String)>,
+) {
+    let mut spawn_points: HashMap<usize, String> = HashMap::new();
+    let mut areas: Vec<usize> = Vec::from(area);
+
+    let num_spawns = i32::min(areas.len() as i32, rng.roll_dice(1, MAX_MONSTERS));
+    if num_spawns == 0 {
+        return;
+    }
+
+    for _i in 0..num_spawns {
+        let array_index = if areas.len() == 1 {
+            0usize
+        } else {
+            (rng.roll_dice(1, areas.len() as i32) - 1) as usize
+        };
+        let map_idx = areas[array_index];
+        // Eventually, replace this with a spawn table for depth
+        let name = match rng.roll_dice(1, 24) {
+            1..=5 => "Goblin".to_string(),
+            6..=9 => "Orc".to_string(),
+            10 => "Goblin Archer".to_string(),
+            11 => "Goblin Shaman".to_string(),
+            12 => "Bog Wraith".to_string(),
+            13 => "Sling Stones".to_string(),
+            14 => "Arrows".to_string(),
+            15 => "Health Potion".to_string(),
+            16 => "Mana Potion".to_string(),
+            17 => loot::random_base_item(rng).name.to_string(),
+            18 => "Scroll of Identify".to_string(),
+            19 => "Scroll of Teleportation".to_string(),
+            20 => "Scroll of Remove Curse".to_string(),
+            21 => "Repair Kit".to_string(),
+            22 => {
+                let spell = WAND_SPELLS[(rng.roll_dice(1, WAND_SPELLS.len() as i32) - 1) as usize];
+                format!("Wand of {}", spell.info().name)
+            }
+            _ => {
+                let spell =
+                    FOUND_SPELLS[(rng.roll_dice(1, FOUND_SPELLS.len() as i32) - 1) as usize];
+                format!("Spellbook of {}", spell.info().name)
+            }
+        };
+        spawn_points.insert(map_idx, name);
+        areas.remove(array_index);
+    }
 
-    // Eventually, replace this with a spawn table for depth
-    random_monster(ecs, x, y);
+    for spawn in spawn_points {
+        spawn_list.push(spawn);
+    }
 }
 
-pub fn spawn_region(ecs: &mut World, area: &[usize]) {
-    let mut spawn_points: HashMap<usize, String> = HashMap::new();
-    let mut areas: Vec<usize> = Vec::from(area);
+/// Creates the named entity at a map index chosen while building the level
+pub fn spawn_entity(ecs: &mut World, spawn: &(usize, String)) {
+    let width = ecs.fetch::<Map>().width as usize;
+    let x = (spawn.0 % width) as i32;
+    let y = (spawn.0 / width) as i32;
 
-    {
-        let mut rng = RandomNumberGenerator::new();
-        let num_spawns = i32::min(areas.len() as i32, rng.roll_dice(1, MAX_MONSTERS));
-        if num_spawns == 0 {
-            return;
+    match spawn.1.as_ref() {
+        "Orc" => orc(ecs, x, y),
+        "Door" => door(ecs, x, y),
+        "Goblin Archer" => goblin_archer(ecs, x, y),
+        "Health Potion" => health_potion(ecs, x, y),
+        "Mana Potion" => mana_potion(ecs, x, y),
+        "Scroll of Identify" => identify_scroll(ecs, x, y),
+        "Scroll of Teleportation" => teleport_scroll(ecs, x, y),
+        "Scroll of Remove Curse" => remove_curse_scroll(ecs, x, y),
+        "Repair Kit" => repair_kit(ecs, x, y),
+        "Bound Spirit" => bound_spirit(ecs, x, y),
+        "Sling Stones" => {
+            let count = RandomNumberGenerator::new().roll_dice(1, 6) + 4;
+            ammunition_stack(ecs.create_entity(), AmmoKind::SlingStones, count)
+                .with(Position { x, y })
+                .build();
         }
-
-        for _i in 0..num_spawns {
-            let array_index = if areas.len() == 1 {
-                0usize
+        "Arrows" => {
+            let count = RandomNumberGenerator::new().roll_dice(1, 6) + 2;
+            ammunition_stack(ecs.create_entity(), AmmoKind::Arrows, count)
+                .with(Position { x, y })
+                .build();
+        }
+        "Torch" => torch(ecs, x, y),
+        "Brazier" => brazier(ecs, x, y),
+        "Glowing Fungus" => glowing_fungus(ecs, x, y),
+        "Shrine" => shrine(ecs, x, y),
+        name if monster_def(name).is_some() => {
+            monster(ecs, x, y, name).build();
+        }
+        name => {
+            // Weapons and armor lying about are rolled as loot for the level they're found on
+            if let Some(base) = loot::base_item(name) {
+                let depth = ecs.fetch::<Map>().depth;
+                let roll = loot::roll_loot(&mut RandomNumberGenerator::new(), base, depth);
+                loot_item(ecs, x, y, &roll);
+            } else if let Some(spell) = name
+                .strip_prefix("Spellbook of ")
+                .and_then(Spell::from_name)
+            {
+                spellbook(ecs, x, y, spell);
+            } else if let Some(spell) = name.strip_prefix("Wand of ").and_then(Spell::from_name) {
+                wand(ecs, x, y, spell);
             } else {
-                (rng.roll_dice(1, areas.len() as i32) - 1) as usize
-            };
-            let map_idx = areas[array_index];
-            spawn_points.insert(map_idx, "".to_string()); // Eventually this will use a spawn table
-            areas.remove(array_index);
+                panic!("Nothing called '{}' to spawn", name);
+            }
         }
     }
-
-    for spawn in spawn_points.iter() {
-        spawn_entity(ecs, &spawn);
-    }
 }
@@ -0,0 +1,115 @@
+use bracket_lib::terminal::Point;
+use specs::prelude::*;
+
+use crate::{
+    item_name, CombatStats, EffectQueue, EmitsNoise, GameLog, IdentificationRegistry, Map, Name,
+    Pools, Position, Wand, WantsToCastSpell, NOISE_FIGHTING,
+};
+
+/// Resolves spells: the caster pays the mana, or zaps a charge out of a wand, and the spell's
+/// effects are queued up on whatever its shape reaches
+pub struct SpellCastingSystem {}
+
+impl<'a> System<'a> for SpellCastingSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, IdentificationRegistry>,
+        WriteStorage<'a, WantsToCastSpell>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Pools>,
+        WriteStorage<'a, Wand>,
+        ReadStorage<'a, CombatStats>,
+        Write<'a, EffectQueue>,
+        WriteStorage<'a, EmitsNoise>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            mut log,
+            map,
+            registry,
+            mut wants_cast,
+            names,
+            positions,
+            mut pools,
+            mut wands,
+            combat_stats,
+            mut effects,
+            mut noises,
+        ) = data;
+
+        let casts: Vec<(Entity, WantsToCastSpell)> = (&entities, &wants_cast)
+            .join()
+            .map(|(entity, cast)| (entity, cast.clone()))
+            .collect();
+        wants_cast.clear();
+
+        for (caster, cast) in casts {
+            if combat_stats.get(caster).is_none_or(|stats| stats.hp <= 0) {
+                continue;
+            }
+            let (Some(pos), Some(caster_pools)) = (positions.get(caster), pools.get_mut(caster))
+            else {
+                continue;
+            };
+            let caster_pos = Point::new(pos.x, pos.y);
+            let info = cast.spell.info();
+            let caster_name = &names.get(caster).unwrap().name;
+
+            let wand = cast
+                .wand
+                .and_then(|wand| wands.get_mut(wand).map(|charged| (wand, charged)));
+            if let Some((wand, charged)) = &wand {
+                if charged.charges <= 0 {
+                    log.entries.push(format!(
+                        "The {} is out of charges.",
+                        item_name(*wand, &names, &registry)
+                    ));
+                    continue;
+                }
+            } else if caster_pools.mana.current < info.mana_cost {
+                if caster == *player_entity {
+                    log.entries
+                        .push(format!("You don't have enough mana for {}.", info.name));
+                }
+                continue;
+            }
+            if !cast.spell.can_reach(&map, caster_pos, cast.target) {
+                if caster == *player_entity {
+                    log.entries
+                        .push(format!("You can't reach there with {}.", info.name));
+                }
+                continue;
+            }
+
+            match wand {
+                Some((wand, charged)) => {
+                    charged.charges -= 1;
+                    log.entries.push(format!(
+                        "{} zaps the {}.",
+                        caster_name,
+                        item_name(wand, &names, &registry)
+                    ));
+                }
+                None => {
+                    caster_pools.mana.current -= info.mana_cost;
+                    log.entries
+                        .push(format!("{} casts {}.", caster_name, info.name));
+                }
+            }
+            EmitsNoise::new_noise(&mut noises, caster, NOISE_FIGHTING);
+
+            let targets = cast.spell.targets(caster, caster_pos, cast.target);
+            for effect in &info.effects {
+                effects.add_effect(Some(caster), *effect, targets);
+            }
+        }
+    }
+}
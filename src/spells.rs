@@ -0,0 +1,256 @@
+use std::sync::OnceLock;
+
+use bracket_lib::terminal::{DistanceAlg, Point, RGB};
+use specs::Entity;
+
+use crate::{DamageType, Effect, Map, Status, Targets};
+
+/// Every spell; resources/spells.txt must list them in this same order
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum Spell {
+    Firebolt,
+    Fireball,
+    Mend,
+    Blink,
+    FrostCone,
+    Lightning,
+    PoisonCloud,
+}
+
+/// Which tiles a spell reaches
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum SpellShape {
+    /// Only the caster
+    Caster,
+    /// One creature, which the caster needs a clear line of fire to
+    Single,
+    /// Everything within this many tiles of a target tile, unless a wall shelters it
+    Radius(i32),
+    /// A quarter circle spreading from the caster towards the target, out to the spell's range
+    Cone,
+    /// Every tile from the caster to the target, until a wall stops it
+    Line,
+}
+
+impl SpellShape {
+    /// True if nobody may stand between the caster and the target. Area spells only need a path
+    /// clear of walls, so they can pass through one creature to reach another, or be aimed at a
+    /// tile behind someone.
+    pub fn needs_line_of_fire(self) -> bool {
+        self == SpellShape::Single
+    }
+}
+
+/// What casting a spell costs and does, as resources/spells.txt describes it
+#[derive(Debug, Clone)]
+pub struct SpellInfo {
+    pub spell: Spell,
+    pub name: String,
+    /// A short name for the spell bar
+    pub abbr: String,
+    pub mana_cost: i32,
+    /// How far away the target can be; spells on the caster ignore it
+    pub range: i32,
+    pub shape: SpellShape,
+    /// Applied in order to everything the shape reaches
+    pub effects: Vec<Effect>,
+}
+
+const SPELL_DATA: &str = include_str!("../resources/spells.txt");
+
+/// How long a spell's flash stays on screen, in milliseconds
+const FLASH_MS: f32 = 200.0;
+
+fn spell_table() -> &'static [SpellInfo] {
+    static SPELLS: OnceLock<Vec<SpellInfo>> = OnceLock::new();
+    SPELLS.get_or_init(|| parse_spells(SPELL_DATA))
+}
+
+/// Reads spells in the format described at the top of resources/spells.txt. Like the bestiary,
+/// the file ships inside the binary, so a mistake in it panics with its line number.
+fn parse_spells(text: &str) -> Vec<SpellInfo> {
+    let mut spells: Vec<SpellInfo> = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fail = |why: &str| -> ! { panic!("spells.txt line {}: {}", line_no + 1, why) };
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let name = name.trim();
+            let Some(&spell) = Spell::ALL.get(spells.len()) else {
+                fail("more spells than the Spell enum has")
+            };
+            if format!("{:?}", spell) != name.replace(' ', "") {
+                fail("out of order; entries must follow the Spell enum")
+            }
+            spells.push(SpellInfo {
+                spell,
+                name: name.to_string(),
+                abbr: name.to_string(),
+                mana_cost: 0,
+                range: 0,
+                shape: SpellShape::Caster,
+                effects: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            fail("expected `key = value`")
+        };
+        let Some(info) = spells.last_mut() else {
+            fail("a value before the first [Spell]")
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let number = |word: Option<&&str>| {
+            word.and_then(|word| word.parse::<i32>().ok())
+                .unwrap_or_else(|| fail("expected a number"))
+        };
+        let color = |word: Option<&&str>| {
+            word.and_then(|word| word.parse::<f32>().ok())
+                .unwrap_or_else(|| fail("expected a color component"))
+        };
+
+        match key {
+            "abbr" => info.abbr = value.to_string(),
+            "mana" => info.mana_cost = number(words.first()),
+            "range" => info.range = number(words.first()),
+            "shape" => {
+                info.shape = match words.first().copied() {
+                    Some("caster") => SpellShape::Caster,
+                    Some("single") => SpellShape::Single,
+                    Some("cone") => SpellShape::Cone,
+                    Some("line") => SpellShape::Line,
+                    Some("radius") => SpellShape::Radius(number(words.get(1))),
+                    _ => fail("unknown shape"),
+                }
+            }
+            "flash" => info.effects.push(Effect::Particle {
+                glyph: words
+                    .first()
+                    .and_then(|code| u16::from_str_radix(code, 16).ok())
+                    .unwrap_or_else(|| fail("expected a glyph code in hex")),
+                fg: RGB::from_f32(
+                    color(words.get(1)),
+                    color(words.get(2)),
+                    color(words.get(3)),
+                ),
+                lifetime_ms: FLASH_MS,
+            }),
+            "damage" => info.effects.push(Effect::Damage {
+                amount: number(words.first()),
+                kind: words
+                    .get(1)
+                    .and_then(|name| DamageType::from_name(name))
+                    .unwrap_or_else(|| fail("unknown damage type")),
+            }),
+            "heal" => info.effects.push(Effect::Heal {
+                amount: number(words.first()),
+            }),
+            "teleport" => info.effects.push(Effect::Teleport {
+                range: number(words.first()),
+            }),
+            "status" => info.effects.push(Effect::ApplyStatus {
+                status: words
+                    .first()
+                    .and_then(|name| Status::from_name(name))
+                    .unwrap_or_else(|| fail("unknown status")),
+                turns: number(words.get(1)),
+            }),
+            _ => fail("unknown key"),
+        }
+    }
+    if spells.len() != Spell::ALL.len() {
+        panic!(
+            "spells.txt is missing spells; it has {} of {}",
+            spells.len(),
+            Spell::ALL.len()
+        );
+    }
+    spells
+}
+
+impl Spell {
+    pub const ALL: [Spell; 7] = [
+        Spell::Firebolt,
+        Spell::Fireball,
+        Spell::Mend,
+        Spell::Blink,
+        Spell::FrostCone,
+        Spell::Lightning,
+        Spell::PoisonCloud,
+    ];
+
+    pub fn info(self) -> &'static SpellInfo {
+        &spell_table()[self as usize]
+    }
+
+    /// The spell called `name`, if there is one
+    pub fn from_name(name: &str) -> Option<Spell> {
+        spell_table()
+            .iter()
+            .find(|info| info.name == name)
+            .map(|info| info.spell)
+    }
+
+    /// Where the spell lands when `caster`, standing at `caster_pos`, aims it at `target`
+    pub fn targets(self, caster: Entity, caster_pos: Point, target: Point) -> Targets {
+        let info = self.info();
+        match info.shape {
+            SpellShape::Caster => Targets::Single { target: caster },
+            SpellShape::Single => Targets::Tile { tile: target },
+            SpellShape::Radius(radius) => Targets::TilesInRadius {
+                center: target,
+                radius,
+            },
+            SpellShape::Cone => Targets::Cone {
+                origin: caster_pos,
+                toward: target,
+                range: info.range,
+            },
+            SpellShape::Line => Targets::Line {
+                from: caster_pos,
+                to: target,
+            },
+        }
+    }
+
+    /// True if a caster at `caster_pos` is close enough to `target`, with a clear enough way to it,
+    /// to cast the spell there
+    pub fn can_reach(self, map: &Map, caster_pos: Point, target: Point) -> bool {
+        let info = self.info();
+        if info.shape == SpellShape::Caster {
+            return true;
+        }
+        let clear = if info.shape.needs_line_of_fire() {
+            map.has_line_of_fire(caster_pos, target)
+        } else {
+            map.has_clear_path(caster_pos, target)
+        };
+        clear && DistanceAlg::Pythagoras.distance2d(caster_pos, target) <= info.range as f32
+    }
+
+    /// True if the spell, cast from `caster_pos` at `target`, would reach the caster too
+    pub fn catches_caster(self, caster_pos: Point, target: Point) -> bool {
+        match self.info().shape {
+            SpellShape::Caster => true,
+            SpellShape::Radius(radius) => {
+                DistanceAlg::Pythagoras.distance2d(caster_pos, target) <= radius as f32
+            }
+            _ => false,
+        }
+    }
+
+    /// True if the spell hurts or hinders whatever it reaches, rather than helping
+    pub fn is_hostile(self) -> bool {
+        self.info()
+            .effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::Damage { .. } | Effect::ApplyStatus { .. }))
+    }
+}
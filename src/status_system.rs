@@ -0,0 +1,63 @@
+use specs::prelude::*;
+
+use crate::{
+    DamageType, Effect, EffectQueue, GameLog, Name, RunState, Status, StatusEffects, Targets,
+};
+
+/// Damage dealt by poison each turn
+const POISON_DAMAGE: i32 = 1;
+
+/// Runs down everyone's statuses once a turn, hurting the poisoned along the way
+pub struct StatusSystem {}
+
+impl<'a> System<'a> for StatusSystem {
+    type SystemData = (
+        ReadExpect<'a, RunState>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        Write<'a, EffectQueue>,
+        Entities<'a>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, StatusEffects>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (runstate, player_entity, mut log, mut effects, entities, names, mut statuses) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        let mut recovered: Vec<Entity> = Vec::new();
+        for (entity, active) in (&entities, &mut statuses).join() {
+            if active.has(Status::Poisoned) {
+                effects.add_effect(
+                    None,
+                    Effect::Damage {
+                        amount: POISON_DAMAGE,
+                        kind: DamageType::Poison,
+                    },
+                    Targets::Single { target: entity },
+                );
+            }
+
+            for (status, turns) in active.statuses.iter_mut() {
+                *turns -= 1;
+                if *turns <= 0 && entity == *player_entity {
+                    if let Some(name) = names.get(entity) {
+                        log.entries
+                            .push(format!("{} is no longer {}.", name.name, status.name()));
+                    }
+                }
+            }
+            active.statuses.retain(|(_, turns)| *turns > 0);
+            if active.statuses.is_empty() {
+                recovered.push(entity);
+            }
+        }
+
+        for entity in recovered {
+            statuses.remove(entity);
+        }
+    }
+}
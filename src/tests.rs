@@ -0,0 +1,731 @@
+use bracket_lib::terminal::Point;
+use specs::prelude::*;
+
+use super::*;
+
+const ROOM_WIDTH: i32 = 20;
+const ROOM_HEIGHT: i32 = 12;
+
+/// A walled room of open floor with the player standing at (x, y), carrying nothing, and no
+/// monsters yet
+fn test_state(x: i32, y: i32) -> State {
+    let mut ecs = World::new();
+    register_components(&mut ecs);
+
+    let mut map = Map::new(ROOM_WIDTH, ROOM_HEIGHT, 1);
+    for y in 1..ROOM_HEIGHT - 1 {
+        for x in 1..ROOM_WIDTH - 1 {
+            let idx = map.xy_idx(x, y);
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+    ecs.insert(map);
+
+    let player_entity = spawner::player(&mut ecs, x, y);
+    ecs.insert(player_entity);
+    ecs.insert(Point::new(x, y));
+    ecs.insert(EffectQueue::default());
+    ecs.insert(GameMode::Permadeath);
+    ecs.insert(IdentificationRegistry::new(1));
+    ecs.insert(RespawnPoint {
+        pos: Position { x, y },
+    });
+    ecs.insert(LevelRoster {
+        monsters: Vec::new(),
+    });
+    ecs.insert(RunState::PreRun);
+    ecs.insert(GameLog {
+        entries: Vec::new(),
+    });
+
+    let mut gs = State {
+        ecs,
+        show_mapgen: false,
+        mapgen_next_state: None,
+        mapgen_history: Vec::new(),
+        mapgen_index: 0,
+        mapgen_timer: 0.0,
+    };
+    gs.run_systems();
+    gs
+}
+
+/// Spawns whatever the spawner calls `name` at (x, y), returning it
+fn spawn(gs: &mut State, name: &str, x: i32, y: i32) -> Entity {
+    let idx = gs.ecs.fetch::<Map>().xy_idx(x, y);
+    spawner::spawn_entity(&mut gs.ecs, &(idx, name.to_string()));
+    let entities = gs.ecs.entities();
+    let positions = gs.ecs.read_storage::<Position>();
+    let names = gs.ecs.read_storage::<Name>();
+    (&entities, &positions, &names)
+        .join()
+        .find(|(_, pos, spawned)| pos.x == x && pos.y == y && spawned.name == name)
+        .map(|(entity, _, _)| entity)
+        .unwrap_or_else(|| panic!("Nothing called {name} spawned at ({x}, {y})"))
+}
+
+/// Spawns a monster that is wide awake, so it acts on its very next turn
+fn spawn_awake(gs: &mut State, name: &str, x: i32, y: i32) -> Entity {
+    let monster = spawn(gs, name, x, y);
+    if let Some(awareness) = gs.ecs.write_storage::<Awareness>().get_mut(monster) {
+        awareness.state = AwarenessState::Aware;
+    }
+    monster
+}
+
+/// Moves an item off the floor and into the player's pack
+fn give_player(gs: &mut State, item: Entity) {
+    let player = player_entity(gs);
+    gs.ecs.write_storage::<Position>().remove(item);
+    gs.ecs
+        .write_storage::<InBackpack>()
+        .insert(item, InBackpack { owner: player })
+        .expect("Unable to insert backpack entry");
+}
+
+/// A plain weapon for the player's melee slot, worn already if `worn`
+fn carried_weapon(gs: &mut State, name: &str, worn: bool) -> Entity {
+    let player = player_entity(gs);
+    let weapon = gs
+        .ecs
+        .create_entity()
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Item {
+            weight: 1.0,
+            value: 10,
+        })
+        .with(Equippable {
+            slot: EquipmentSlot::Melee,
+        })
+        .with(InBackpack { owner: player })
+        .build();
+    if worn {
+        gs.ecs
+            .write_storage::<Equipped>()
+            .insert(
+                weapon,
+                Equipped {
+                    owner: player,
+                    slot: EquipmentSlot::Melee,
+                },
+            )
+            .expect("Unable to equip weapon");
+    }
+    weapon
+}
+
+fn use_item(gs: &mut State, item: Entity) {
+    let player = player_entity(gs);
+    gs.ecs
+        .write_storage::<WantsToUseItem>()
+        .insert(player, WantsToUseItem { item })
+        .expect("Unable to insert intent");
+    player_turn(gs);
+}
+
+/// Runs the systems as the player's half of a turn
+fn player_turn(gs: &mut State) {
+    *gs.ecs.write_resource::<RunState>() = RunState::PlayerTurn;
+    gs.run_systems();
+    damage_system::delete_the_dead(&mut gs.ecs);
+}
+
+/// Runs the monsters' half of a turn, returning whose turn the game says is next
+fn monster_turn(gs: &mut State) -> RunState {
+    *gs.ecs.write_resource::<RunState>() = RunState::MonsterTurn;
+    let next = gs.monster_turn();
+    damage_system::delete_the_dead(&mut gs.ecs);
+    next
+}
+
+/// Puts the player at (x, y) without spending a turn
+fn move_player(gs: &mut State, x: i32, y: i32) {
+    let player = player_entity(gs);
+    if let Some(pos) = gs.ecs.write_storage::<Position>().get_mut(player) {
+        pos.x = x;
+        pos.y = y;
+    }
+    *gs.ecs.write_resource::<Point>() = Point::new(x, y);
+}
+
+/// Kills the player carrying `souls` souls, the way the game loop does
+fn die_with_souls(gs: &mut State, souls: i32) -> RunState {
+    let player = player_entity(gs);
+    gs.ecs
+        .write_storage::<Souls>()
+        .get_mut(player)
+        .unwrap()
+        .amount = souls;
+    gs.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = 0;
+    gs.player_died()
+}
+
+fn player_pos(gs: &State) -> Position {
+    let player = player_entity(gs);
+    gs.ecs
+        .read_storage::<Position>()
+        .get(player)
+        .unwrap()
+        .clone()
+}
+
+fn player_entity(gs: &State) -> Entity {
+    *gs.ecs.fetch::<Entity>()
+}
+
+fn hp(gs: &State, entity: Entity) -> i32 {
+    gs.ecs.read_storage::<CombatStats>().get(entity).unwrap().hp
+}
+
+fn times_logged(gs: &State, text: &str) -> usize {
+    gs.ecs
+        .fetch::<GameLog>()
+        .entries
+        .iter()
+        .filter(|entry| entry.contains(text))
+        .count()
+}
+
+fn logged(gs: &State, text: &str) -> bool {
+    gs.ecs
+        .fetch::<GameLog>()
+        .entries
+        .iter()
+        .any(|entry| entry.contains(text))
+}
+
+fn cast(gs: &mut State, spell: Spell, x: i32, y: i32) {
+    let player = player_entity(gs);
+    if let Some(pools) = gs.ecs.write_storage::<Pools>().get_mut(player) {
+        pools.mana.current = pools.mana.max;
+    }
+    gs.ecs
+        .write_storage::<WantsToCastSpell>()
+        .insert(
+            player,
+            WantsToCastSpell {
+                spell,
+                target: Point::new(x, y),
+                wand: None,
+            },
+        )
+        .expect("Unable to insert spell");
+    player_turn(gs);
+}
+
+#[test]
+#[should_panic(expected = "Nothing called 'Goblin Kinj' to spawn")]
+fn spawning_an_unknown_name_is_an_error() {
+    let mut gs = test_state(2, 5);
+    spawn(&mut gs, "Goblin Kinj", 4, 5);
+}
+
+#[test]
+fn a_shrine_stands_out_from_the_ground_under_it() {
+    let mut gs = test_state(2, 5);
+    let shrine = spawn(&mut gs, "Shrine", 4, 5);
+    let glyph = gs
+        .ecs
+        .read_storage::<Renderable>()
+        .get(shrine)
+        .unwrap()
+        .glyph;
+    let tiles = [
+        TileType::Wall,
+        TileType::Floor,
+        TileType::DownStairs,
+        TileType::ShallowWater,
+        TileType::DeepWater,
+        TileType::Lava,
+        TileType::Bridge,
+        TileType::Rubble,
+        TileType::Grass,
+        TileType::TallGrass,
+        TileType::Tree,
+        TileType::Stalactite,
+        TileType::Gravel,
+        TileType::Door,
+    ];
+    for tile in tiles {
+        assert_ne!(
+            tile.info().glyph,
+            glyph,
+            "a shrine looks just like {tile:?}"
+        );
+    }
+}
+
+#[test]
+fn lightning_passes_through_one_monster_to_the_next() {
+    let mut gs = test_state(2, 5);
+    let near = spawn(&mut gs, "Goblin", 4, 5);
+    let far = spawn(&mut gs, "Goblin", 6, 5);
+    let (near_hp, far_hp) = (hp(&gs, near), hp(&gs, far));
+
+    cast(&mut gs, Spell::Lightning, 6, 5);
+
+    assert!(hp(&gs, near) < near_hp, "the nearer goblin wasn't hit");
+    assert!(
+        hp(&gs, far) < far_hp,
+        "the bolt stopped at the nearer goblin"
+    );
+}
+
+#[test]
+fn area_spells_can_be_aimed_behind_a_monster() {
+    let mut gs = test_state(2, 5);
+    spawn(&mut gs, "Goblin", 4, 5);
+    let behind = spawn(&mut gs, "Goblin", 7, 5);
+    let behind_hp = hp(&gs, behind);
+
+    cast(&mut gs, Spell::Fireball, 7, 5);
+
+    assert!(hp(&gs, behind) < behind_hp);
+}
+
+#[test]
+fn single_target_spells_still_need_a_clear_line_of_fire() {
+    let mut gs = test_state(2, 5);
+    spawn(&mut gs, "Goblin", 4, 5);
+    let far = spawn(&mut gs, "Goblin", 6, 5);
+    let far_hp = hp(&gs, far);
+
+    cast(&mut gs, Spell::Firebolt, 6, 5);
+
+    assert_eq!(hp(&gs, far), far_hp);
+    assert!(logged(&gs, "You can't reach there with Firebolt."));
+}
+
+#[test]
+fn monsters_take_their_resistances_from_the_data_file() {
+    let mut gs = test_state(2, 5);
+    let wraith = spawn(&mut gs, "Bog Wraith", 3, 5);
+    let player = player_entity(&gs);
+    gs.ecs
+        .write_storage::<WantsToMelee>()
+        .insert(player, WantsToMelee { target: wraith })
+        .expect("Unable to insert attack");
+    player_turn(&mut gs);
+    assert!(logged(&gs, "resists your blows"));
+
+    cast(&mut gs, Spell::Firebolt, 3, 5);
+    assert!(logged(&gs, "is vulnerable to"));
+}
+
+#[test]
+fn lightning_does_lightning_damage() {
+    let mut gs = test_state(2, 5);
+    let wraith = spawn(&mut gs, "Bog Wraith", 4, 5);
+    gs.ecs
+        .write_storage::<Resistances>()
+        .insert(
+            wraith,
+            Resistances {
+                against: vec![(DamageType::Lightning, Resistance::Immune)],
+            },
+        )
+        .expect("Unable to insert resistances");
+    let wraith_hp = hp(&gs, wraith);
+
+    cast(&mut gs, Spell::Lightning, 4, 5);
+
+    assert_eq!(hp(&gs, wraith), wraith_hp);
+    assert!(logged(&gs, "unharmed by"));
+}
+
+#[test]
+fn every_monster_the_spawner_places_is_in_the_data_file() {
+    let mut gs = test_state(2, 5);
+    for (x, name) in (4..).zip([
+        "Goblin",
+        "Orc",
+        "Goblin Archer",
+        "Goblin Shaman",
+        "Bog Wraith",
+        "Orc Warlord",
+    ]) {
+        let monster = spawn(&mut gs, name, x, 5);
+        assert!(gs.ecs.read_storage::<Monster>().contains(monster));
+    }
+}
+
+#[test]
+fn every_spell_is_in_the_data_file() {
+    for spell in Spell::ALL {
+        let info = spell.info();
+        assert_eq!(info.spell, spell);
+        assert_eq!(Spell::from_name(&info.name), Some(spell));
+        assert!(!info.effects.is_empty());
+    }
+    assert_eq!(Spell::FrostCone.info().shape, SpellShape::Cone);
+    assert_eq!(Spell::Fireball.info().shape, SpellShape::Radius(2));
+}
+
+#[test]
+fn the_hotbar_shortens_spells_to_fit_beside_the_indicators() {
+    let width = |slots: &[String]| slots.iter().map(|slot| slot.len()).sum::<usize>();
+
+    let roomy = hotbar_slots(&Spell::ALL, 200);
+    assert_eq!(roomy.len(), Spell::ALL.len());
+    assert!(roomy[0].contains("Firebolt (3)"));
+
+    let compact = hotbar_slots(&Spell::ALL, 60);
+    assert_eq!(compact.len(), Spell::ALL.len());
+    assert!(compact[0].contains("1:Bolt"));
+    assert!(width(&compact) <= 60);
+
+    // Room for the "+n" marker is kept once some spells have to go
+    let cramped = hotbar_slots(&Spell::ALL, 24);
+    assert!(cramped.len() < Spell::ALL.len());
+    assert!(width(&cramped) + 2 <= 24);
+}
+
+#[test]
+fn monsters_dont_catch_themselves_in_their_own_blasts() {
+    let mut gs = test_state(5, 5);
+    let shaman = spawn_awake(&mut gs, "Goblin Shaman", 6, 5);
+    gs.ecs
+        .write_storage::<KnownSpells>()
+        .insert(
+            shaman,
+            KnownSpells {
+                spells: vec![Spell::Fireball, Spell::Firebolt],
+            },
+        )
+        .expect("Unable to insert spells");
+    let shaman_hp = hp(&gs, shaman);
+
+    monster_turn(&mut gs);
+
+    assert!(!logged(&gs, "casts Fireball"));
+    assert!(logged(&gs, "casts Firebolt"));
+    assert_eq!(hp(&gs, shaman), shaman_hp);
+}
+
+#[test]
+fn a_dodge_roll_covers_the_next_two_monster_turns() {
+    let mut gs = test_state(5, 5);
+    spawn_awake(&mut gs, "Goblin", 8, 5);
+    let player = player_entity(&gs);
+    let full_hp = hp(&gs, player);
+
+    // Rolling two tiles east lands the player right beside the goblin
+    assert!(player::try_dodge_roll(1, 0, &mut gs.ecs) == RunState::PlayerTurn);
+    player_turn(&mut gs);
+    assert_eq!(*gs.ecs.fetch::<Point>(), Point::new(7, 5));
+
+    monster_turn(&mut gs);
+    assert_eq!(hp(&gs, player), full_hp, "hit straight after the roll");
+    assert!(logged(&gs, "rolls out of harm's way"));
+
+    player_turn(&mut gs);
+    monster_turn(&mut gs);
+    assert_eq!(hp(&gs, player), full_hp, "hit on the second turn");
+
+    player_turn(&mut gs);
+    monster_turn(&mut gs);
+    assert!(hp(&gs, player) < full_hp, "still untouchable on turn 3");
+}
+
+#[test]
+fn dying_again_loses_the_souls_left_behind_the_first_time() {
+    let mut gs = test_state(2, 5);
+    gs.ecs.insert(GameMode::Shrines);
+
+    move_player(&mut gs, 10, 5);
+    die_with_souls(&mut gs, 10);
+    move_player(&mut gs, 12, 8);
+    die_with_souls(&mut gs, 5);
+
+    let caches: Vec<(i32, i32, i32)> = (
+        &gs.ecs.read_storage::<SoulCache>(),
+        &gs.ecs.read_storage::<Position>(),
+    )
+        .join()
+        .map(|(cache, pos)| (pos.x, pos.y, cache.souls))
+        .collect();
+    assert_eq!(caches, vec![(12, 8, 5)]);
+    assert!(logged(
+        &gs,
+        "The souls you left behind before are lost forever."
+    ));
+}
+
+#[test]
+fn the_player_wakes_at_the_last_shrine_rested_at_on_this_floor() {
+    let mut gs = test_state(2, 5);
+    gs.ecs.insert(GameMode::Shrines);
+    spawn(&mut gs, "Shrine", 9, 3);
+
+    move_player(&mut gs, 9, 3);
+    assert!(shrine::try_rest(&mut gs.ecs));
+    move_player(&mut gs, 15, 9);
+    assert!(die_with_souls(&mut gs, 0) == RunState::PreRun);
+    assert_eq!((player_pos(&gs).x, player_pos(&gs).y), (9, 3));
+
+    // A new floor's entrance is a checkpoint of its own
+    let start = gs.generate_world_map(2);
+    let respawn = gs.ecs.fetch::<RespawnPoint>().pos.clone();
+    assert_eq!((respawn.x, respawn.y), (start.x, start.y));
+}
+
+#[test]
+fn a_slain_boss_does_not_rise_when_the_player_rests() {
+    let mut gs = test_state(2, 5);
+    gs.ecs.insert(GameMode::Shrines);
+    spawn(&mut gs, "Shrine", 2, 5);
+    let orc = spawn(&mut gs, "Orc", 8, 5);
+    let warlord = spawn(&mut gs, "Orc Warlord", 10, 5);
+    assert!(gs.ecs.read_storage::<Boss>().contains(warlord));
+    shrine::record_roster(&mut gs.ecs);
+
+    gs.ecs
+        .delete_entities(&[orc, warlord])
+        .expect("Unable to slay monsters");
+    assert!(shrine::try_rest(&mut gs.ecs));
+
+    let names = gs.ecs.read_storage::<Name>();
+    let risen: Vec<&str> = (&gs.ecs.read_storage::<Monster>(), &names)
+        .join()
+        .map(|(_, name)| name.name.as_str())
+        .collect();
+    assert_eq!(risen, ["Orc"]);
+}
+
+#[test]
+fn the_same_seed_disguises_things_the_same_way() {
+    let first = IdentificationRegistry::new(42);
+    assert_eq!(first.disguises, IdentificationRegistry::new(42).disguises);
+    assert!((43..53).any(|seed| IdentificationRegistry::new(seed).disguises != first.disguises));
+}
+
+#[test]
+fn using_a_potion_identifies_every_potion_of_its_kind() {
+    let mut gs = test_state(2, 5);
+    let potions = [
+        spawn(&mut gs, "Health Potion", 4, 4),
+        spawn(&mut gs, "Health Potion", 6, 6),
+    ];
+    for potion in potions {
+        give_player(&mut gs, potion);
+    }
+    let disguise = gs
+        .ecs
+        .fetch::<IdentificationRegistry>()
+        .name_for("Health Potion")
+        .to_string();
+    assert_ne!(disguise, "Health Potion");
+
+    use_item(&mut gs, potions[0]);
+
+    assert!(logged(&gs, "It was a Health Potion!"));
+    let registry = gs.ecs.fetch::<IdentificationRegistry>();
+    let names = gs.ecs.read_storage::<Name>();
+    assert_eq!(item_name(potions[1], &names, &registry), "Health Potion");
+}
+
+#[test]
+fn cursed_gear_cannot_be_swapped_out_or_dropped() {
+    let mut gs = test_state(2, 5);
+    let player = player_entity(&gs);
+    let cursed = carried_weapon(&mut gs, "Dagger", true);
+    gs.ecs
+        .write_storage::<Cursed>()
+        .insert(cursed, Cursed { known: false })
+        .expect("Unable to insert curse");
+    let spare = carried_weapon(&mut gs, "Mace", false);
+
+    use_item(&mut gs, spare);
+    assert!(logged(&gs, "won't let go of you"));
+    assert!(gs.ecs.read_storage::<Equipped>().contains(cursed));
+    assert!(!gs.ecs.read_storage::<Equipped>().contains(spare));
+    assert!(gs.ecs.read_storage::<Cursed>().get(cursed).unwrap().known);
+
+    gs.ecs
+        .write_storage::<WantsToDropItem>()
+        .insert(player, WantsToDropItem { item: cursed })
+        .expect("Unable to insert intent");
+    player_turn(&mut gs);
+    assert!(gs.ecs.read_storage::<InBackpack>().contains(cursed));
+    assert!(!gs.ecs.read_storage::<Position>().contains(cursed));
+}
+
+#[test]
+fn a_wand_stops_working_when_its_charges_run_out() {
+    let mut gs = test_state(2, 5);
+    let player = player_entity(&gs);
+    let wand = spawn(&mut gs, "Wand of Firebolt", 3, 3);
+    give_player(&mut gs, wand);
+    gs.ecs
+        .write_storage::<Wand>()
+        .get_mut(wand)
+        .unwrap()
+        .charges = 1;
+    let goblin = spawn(&mut gs, "Goblin", 5, 5);
+
+    let zap = |gs: &mut State| {
+        gs.ecs
+            .write_storage::<WantsToCastSpell>()
+            .insert(
+                player,
+                WantsToCastSpell {
+                    spell: Spell::Firebolt,
+                    target: Point::new(5, 5),
+                    wand: Some(wand),
+                },
+            )
+            .expect("Unable to insert spell");
+        player_turn(gs);
+    };
+
+    let full_hp = hp(&gs, goblin);
+    zap(&mut gs);
+    let zapped_hp = hp(&gs, goblin);
+    assert!(zapped_hp < full_hp);
+    assert_eq!(gs.ecs.read_storage::<Wand>().get(wand).unwrap().charges, 0);
+
+    zap(&mut gs);
+    assert_eq!(hp(&gs, goblin), zapped_hp);
+    assert!(logged(&gs, "is out of charges."));
+}
+
+#[test]
+fn a_repair_kit_restores_worn_gear() {
+    let mut gs = test_state(2, 5);
+    let weapon = carried_weapon(&mut gs, "Short Sword", true);
+    gs.ecs
+        .write_storage::<Durability>()
+        .insert(
+            weapon,
+            Durability {
+                current: 0,
+                max: 20,
+            },
+        )
+        .expect("Unable to insert durability");
+    let kit = spawn(&mut gs, "Repair Kit", 3, 3);
+    give_player(&mut gs, kit);
+
+    use_item(&mut gs, kit);
+
+    let durability = gs.ecs.read_storage::<Durability>();
+    let wear = durability.get(weapon).unwrap();
+    assert_eq!((wear.current, wear.max), (20, 20));
+    assert!(logged(&gs, "Your gear is as good as new."));
+    assert!(!gs.ecs.is_alive(kit), "the kit wasn't used up");
+}
+
+/// Puts something of the given weight in the player's pack
+fn carry_weight(gs: &mut State, weight: f32) -> Entity {
+    let player = player_entity(gs);
+    gs.ecs
+        .create_entity()
+        .with(Name {
+            name: "Boulder".to_string(),
+        })
+        .with(Item { weight, value: 0 })
+        .with(InBackpack { owner: player })
+        .build()
+}
+
+fn set_might(gs: &mut State, might: i32) {
+    let player = player_entity(gs);
+    gs.ecs
+        .write_storage::<Attributes>()
+        .get_mut(player)
+        .unwrap()
+        .might = might;
+}
+
+fn is_burdened(gs: &State) -> bool {
+    let player = player_entity(gs);
+    gs.ecs
+        .read_storage::<Encumbrance>()
+        .get(player)
+        .is_some_and(|encumbrance| encumbrance.is_burdened())
+}
+
+#[test]
+fn might_sets_how_much_can_be_carried() {
+    let mut gs = test_state(2, 5);
+    carry_weight(&mut gs, 28.0);
+
+    set_might(&mut gs, 3);
+    player_turn(&mut gs);
+    assert!(!is_burdened(&gs), "30 lbs of capacity at might 3");
+
+    set_might(&mut gs, 2);
+    player_turn(&mut gs);
+    assert!(is_burdened(&gs), "25 lbs of capacity at might 2");
+}
+
+#[test]
+fn crossing_the_carrying_limit_is_logged_each_way() {
+    let mut gs = test_state(2, 5);
+    let capacity = Attributes { might: 3 }.carry_capacity();
+    carry_weight(&mut gs, capacity);
+    player_turn(&mut gs);
+    assert!(!is_burdened(&gs), "carrying exactly the limit is fine");
+
+    let pebble = carry_weight(&mut gs, 1.0);
+    player_turn(&mut gs);
+    player_turn(&mut gs);
+    assert!(is_burdened(&gs));
+    assert_eq!(
+        times_logged(
+            &gs,
+            "You are burdened by your load, and every step comes slowly."
+        ),
+        1
+    );
+
+    gs.ecs
+        .delete_entity(pebble)
+        .expect("Unable to delete pebble");
+    player_turn(&mut gs);
+    assert!(!is_burdened(&gs));
+    assert_eq!(times_logged(&gs, "You are no longer burdened."), 1);
+}
+
+#[test]
+fn a_burdened_step_or_roll_gives_the_monsters_a_second_turn() {
+    let mut gs = test_state(2, 5);
+    spawn_awake(&mut gs, "Goblin", 4, 6);
+
+    // Unburdened, one step is one monster turn
+    assert!(player::walk_player(0, -1, &mut gs.ecs) == RunState::PlayerTurn);
+    player_turn(&mut gs);
+    assert!(monster_turn(&mut gs) == RunState::AwaitingInput);
+
+    carry_weight(&mut gs, 100.0);
+    player_turn(&mut gs);
+    let hits_before = times_logged(&gs, "Goblin hits Player");
+
+    assert!(player::walk_player(0, 1, &mut gs.ecs) == RunState::PlayerTurn);
+    player_turn(&mut gs);
+    assert!(monster_turn(&mut gs) == RunState::MonsterTurn);
+    assert!(monster_turn(&mut gs) == RunState::AwaitingInput);
+    assert_eq!(times_logged(&gs, "Goblin hits Player") - hits_before, 2);
+
+    // So does a burdened dodge roll
+    assert!(player::try_dodge_roll(-1, 0, &mut gs.ecs) == RunState::PlayerTurn);
+    player_turn(&mut gs);
+    assert!(monster_turn(&mut gs) == RunState::MonsterTurn);
+    assert!(monster_turn(&mut gs) == RunState::AwaitingInput);
+}
+
+/// Levels of every size come and go, the fixed-size keep among them, and everything they name has to
+/// be something the spawner knows how to make
+#[test]
+fn descending_twenty_levels_goes_smoothly() {
+    let mut gs = test_state(2, 5);
+    for _ in 0..20 {
+        gs.goto_next_level();
+    }
+}
@@ -0,0 +1,227 @@
+use bracket_lib::terminal::{FontCharType, RGB};
+
+/// Every kind of tile; `TILE_TABLE` must list them in this same order
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum TileType {
+    Wall,
+    Floor,
+    DownStairs,
+    ShallowWater,
+    DeepWater,
+    Lava,
+    Bridge,
+    Rubble,
+    Grass,
+    TallGrass,
+    Tree,
+    Stalactite,
+    Gravel,
+    /// A doorway; the door itself is an entity standing in it
+    Door,
+}
+
+/// How a kind of tile looks, and how it affects movement and sight
+pub struct TileInfo {
+    pub tile: TileType,
+    pub name: &'static str,
+    /// Used for plain text map dumps and hand-authored templates
+    pub ascii: char,
+    pub glyph: FontCharType,
+    pub fg: RGB,
+    pub bg: RGB,
+    pub walkable: bool,
+    pub opaque: bool,
+    /// What a step onto the tile costs when pathing, relative to plain floor
+    pub cost: f32,
+}
+
+const fn rgb(r: f32, g: f32, b: f32) -> RGB {
+    RGB { r, g, b }
+}
+
+const BLACK: RGB = rgb(0.0, 0.0, 0.0);
+
+static TILE_TABLE: [TileInfo; 14] = [
+    TileInfo {
+        tile: TileType::Wall,
+        name: "Wall",
+        ascii: '#',
+        glyph: 0x23,
+        fg: rgb(0.0, 1.0, 0.0),
+        bg: BLACK,
+        walkable: false,
+        opaque: true,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Floor,
+        name: "Floor",
+        ascii: '.',
+        glyph: 0x2E,
+        fg: rgb(0.5, 0.5, 0.5),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::DownStairs,
+        name: "Stairs down",
+        ascii: '>',
+        glyph: 0x3E,
+        fg: rgb(0.0, 1.0, 1.0),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::ShallowWater,
+        name: "Shallow water",
+        ascii: '~',
+        glyph: 0xF7,
+        fg: rgb(0.3, 0.6, 1.0),
+        bg: rgb(0.0, 0.1, 0.3),
+        walkable: true,
+        opaque: false,
+        cost: 2.0,
+    },
+    TileInfo {
+        tile: TileType::DeepWater,
+        name: "Deep water",
+        ascii: 'W',
+        glyph: 0xF7,
+        fg: rgb(0.1, 0.2, 0.9),
+        bg: rgb(0.0, 0.0, 0.4),
+        walkable: false,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Lava,
+        name: "Lava",
+        ascii: 'L',
+        glyph: 0xF7,
+        fg: rgb(1.0, 0.8, 0.0),
+        bg: rgb(0.7, 0.1, 0.0),
+        walkable: false,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Bridge,
+        name: "Bridge",
+        ascii: '=',
+        glyph: 0x3D,
+        fg: rgb(0.6, 0.4, 0.2),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Rubble,
+        name: "Rubble",
+        ascii: ';',
+        glyph: 0x3B,
+        fg: rgb(0.6, 0.5, 0.4),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 2.0,
+    },
+    TileInfo {
+        tile: TileType::Grass,
+        name: "Grass",
+        ascii: '"',
+        glyph: 0x22,
+        fg: rgb(0.2, 0.7, 0.2),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::TallGrass,
+        name: "Tall grass",
+        ascii: '%',
+        glyph: 0xB1,
+        fg: rgb(0.3, 0.9, 0.3),
+        bg: BLACK,
+        walkable: true,
+        opaque: true,
+        cost: 1.5,
+    },
+    TileInfo {
+        tile: TileType::Tree,
+        name: "Tree",
+        ascii: 'T',
+        glyph: 0x06,
+        fg: rgb(0.0, 0.6, 0.0),
+        bg: BLACK,
+        walkable: false,
+        opaque: true,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Stalactite,
+        name: "Stalactite",
+        ascii: '^',
+        glyph: 0xD0,
+        fg: rgb(0.5, 0.4, 0.3),
+        bg: BLACK,
+        walkable: false,
+        opaque: true,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Gravel,
+        name: "Gravel",
+        ascii: ',',
+        glyph: 0x2C,
+        fg: rgb(0.6, 0.6, 0.6),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 1.0,
+    },
+    TileInfo {
+        tile: TileType::Door,
+        name: "Doorway",
+        ascii: '+',
+        glyph: 0x2B,
+        fg: rgb(0.55, 0.35, 0.1),
+        bg: BLACK,
+        walkable: true,
+        opaque: false,
+        cost: 1.0,
+    },
+];
+
+impl TileType {
+    pub fn info(self) -> &'static TileInfo {
+        let info = &TILE_TABLE[self as usize];
+        debug_assert_eq!(info.tile, self, "TILE_TABLE is out of order");
+        info
+    }
+
+    /// The tile written as `ascii` in text maps, if any
+    pub fn from_ascii(ascii: char) -> Option<TileType> {
+        TILE_TABLE
+            .iter()
+            .find(|info| info.ascii == ascii)
+            .map(|info| info.tile)
+    }
+
+    pub fn walkable(self) -> bool {
+        self.info().walkable
+    }
+
+    pub fn opaque(self) -> bool {
+        self.info().opaque
+    }
+
+    pub fn cost(self) -> f32 {
+        self.info().cost
+    }
+}
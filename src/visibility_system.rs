@@ -28,6 +28,10 @@ impl<'a> System<'a> for VisibilitySystem {
 
                 let p: Option<&Player> = player.get(ent);
                 if let Some(_p) = p {
+                    // The player needs light to see by, as well as a clear line of sight
+                    viewshed
+                        .visible_tiles
+                        .retain(|p| map.is_lit(map.xy_idx(p.x, p.y)));
                     for t in map.visible_tiles.iter_mut() {
                         *t = false;
                     }